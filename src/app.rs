@@ -7,65 +7,147 @@ use clap::Parser;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use walkdir::WalkDir;
+use foundry::{
+    EdgeFilter, META_SCHEMA_VERSION, SpecEdge, SpecGraph, SpecNodeMeta, doc_roots, is_meta_json,
+};
 use crate::cli::*;
 mod agent;
+mod archive;
 mod ask;
+mod audit;
+mod bundle;
+mod completions;
 mod core;
+mod coverage;
 mod derive;
+mod diff;
+mod doctor;
+mod embedding;
+mod history;
 mod impact;
 mod init;
 mod lint;
 mod link;
+mod logging;
+mod migrate;
+mod node;
 mod plan;
 mod runtime;
 mod search;
+#[cfg(feature = "serve")]
+mod serve;
+mod term;
+mod validate;
 mod write;
+use archive::*;
+use audit::*;
+use bundle::*;
+use completions::*;
 use core::*;
+use coverage::*;
 use derive::*;
+use diff::*;
+use doctor::*;
+use embedding::*;
+use history::*;
 use impact::*;
 use init::*;
 use lint::*;
 use link::*;
+use logging::*;
+use migrate::*;
+use node::*;
 use plan::*;
 use runtime::*;
 use search::*;
+#[cfg(feature = "serve")]
+use serve::*;
+use term::*;
+use validate::*;
 use write::*;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SpecNodeMeta {
-    id: String,
-    #[serde(rename = "type")]
-    node_type: String,
-    status: String,
-    title: String,
-    body_md_path: String,
-    terms: Vec<String>,
-    hash: String,
-    edges: Vec<SpecEdge>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SpecEdge {
-    to: String,
-    #[serde(rename = "type")]
-    edge_type: String,
-    rationale: String,
-    confidence: f64,
-    status: String,
-}
-
-#[derive(Default)]
+#[derive(Default, Serialize)]
 struct InitSummary {
     created: usize,
     updated: usize,
     skipped: usize,
     errors: usize,
+    /// Ids whose meta was created or updated, in file order.
+    ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_templates: Option<agent::AgentTemplateSummary>,
 }
 
 #[derive(Default)]
 struct LintState {
-    errors: Vec<String>,
+    findings: Vec<LintFinding>,
+}
+
+struct LintFinding {
+    message: String,
+    /// Rule that produced the finding; `None` for failures outside any rule,
+    /// such as meta files that could not be loaded.
+    tag: Option<LintTag>,
+    location: Option<LintLocation>,
+}
+
+struct LintTag {
+    rule: &'static str,
+    /// Node ids a graph-level finding concerns, e.g. an orphan or every
+    /// member of a refines chain; empty for findings located by `push_at`.
+    nodes: Vec<String>,
+}
+
+/// Where a finding points inside a meta file.
+struct LintLocation {
+    path: String,
+    /// JSON pointer of the offending field.
+    pointer: Option<String>,
+    /// One-based `(line, column)` of a parse failure.
+    position: Option<(usize, usize)>,
+}
+
+impl LintState {
+    fn push_for(&mut self, rule: &'static str, nodes: Vec<String>, message: String) {
+        self.findings.push(LintFinding {
+            message,
+            tag: Some(LintTag { rule, nodes }),
+            location: None,
+        });
+    }
+
+    fn push_at(
+        &mut self,
+        rule: &'static str,
+        path: &Path,
+        pointer: impl Into<String>,
+        message: String,
+    ) {
+        self.findings.push(LintFinding {
+            message,
+            tag: Some(LintTag {
+                rule,
+                nodes: Vec::new(),
+            }),
+            location: Some(LintLocation {
+                path: path.display().to_string(),
+                pointer: Some(pointer.into()),
+                position: None,
+            }),
+        });
+    }
+
+    fn push_load_error(&mut self, err: foundry::MetaLoadError) {
+        self.findings.push(LintFinding {
+            message: err.message,
+            tag: None,
+            location: Some(LintLocation {
+                path: err.path.display().to_string(),
+                pointer: None,
+                position: err.position,
+            }),
+        });
+    }
 }
 
 const NODE_TYPES: &[&str] = &[
@@ -100,9 +182,17 @@ const NODE_STATUSES: &[&str] = &[
     "done",
     "blocked",
 ];
+/// Node priorities, most urgent first; `plan ready` sorts by this order.
+const NODE_PRIORITIES: &[&str] = &["critical", "high", "medium", "low"];
 const EDGE_TYPES: &[&str] = &["depends_on", "refines", "conflicts_with", "tests", "impacts"];
 const EDGE_STATUSES: &[&str] = &["confirmed", "proposed"];
-const EMBEDDING_DIM: usize = 256;
+/// `SpecEdge.source` of edges written by hand (`link add`, `propose --from`).
+const EDGE_SOURCE_MANUAL: &str = "manual";
+/// `SpecEdge.source` of edges written by `derive design|tasks`.
+const EDGE_SOURCE_DERIVE: &str = "derive";
+/// Local embedding size (and `vec0` column width) when
+/// `search.embedding_dim` is unset; also assumed for indexes that predate it.
+const DEFAULT_EMBEDDING_DIM: usize = 256;
 
 pub fn run_main() {
     match run() {
@@ -115,21 +205,28 @@ pub fn run_main() {
 }
 
 fn run() -> Result<i32> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    init_logging(cli.verbose, cli.quiet);
+    let Command::Spec(spec) = &mut cli.command;
+    // `init` creates spec/ where it runs, so it never climbs to a parent.
+    let discover = !matches!(spec.command, SpecSubcommand::Init(_));
+    let invocation_dir = std::env::current_dir().ok();
+    if enter_project_root(cli.root.as_deref().map(Path::new), discover)?
+        && let Some(dir) = invocation_dir
+    {
+        resolve_user_path_args(&mut spec.command, &dir);
+        resolve_search_db_env(&dir);
+    }
+    set_walk_options(foundry::WalkOptions {
+        respect_gitignore: !cli.no_ignore,
+    });
+    if let Ok(cwd) = std::env::current_dir() {
+        log::debug!("project root: {}", cwd.display());
+    }
     match cli.command {
         Command::Spec(spec) => match spec.command {
             SpecSubcommand::Init(args) => {
-                run_init(
-                    args.sync,
-                    &args.agent,
-                    args.agent_sync,
-                    args.agent_output,
-                    args.codex_home.as_deref(),
-                    args.claude_dir.as_deref(),
-                    args.template_source,
-                    &args.template_repo,
-                    &args.template_ref,
-                )?;
+                run_init(&args)?;
                 Ok(0)
             }
             SpecSubcommand::Write(args) => {
@@ -141,25 +238,57 @@ fn run() -> Result<i32> {
                 Ok(0)
             }
             SpecSubcommand::Lint(args) => Ok(run_lint(&args)?),
-            SpecSubcommand::Link(link) => {
-                run_link(link)?;
+            SpecSubcommand::Link(link) => Ok(run_link(link)?),
+            SpecSubcommand::Impact(args) => Ok(run_impact(&args)?),
+            SpecSubcommand::Plan(plan) => Ok(run_plan(plan)?),
+            SpecSubcommand::Agent(agent) => Ok(agent::run_agent(agent)?),
+            SpecSubcommand::Search(search) => Ok(run_search(search)?),
+            SpecSubcommand::Ask(args) => {
+                ask::run_ask(&args)?;
                 Ok(0)
             }
-            SpecSubcommand::Impact(args) => {
-                run_impact(&args)?;
+            SpecSubcommand::Validate(validate) => Ok(run_validate(validate)?),
+            SpecSubcommand::Diff(args) => Ok(run_diff(&args)?),
+            SpecSubcommand::Archive(args) => {
+                run_archive(&args)?;
                 Ok(0)
             }
-            SpecSubcommand::Plan(plan) => {
-                run_plan(plan)?;
+            SpecSubcommand::Export(export) => {
+                run_export(export)?;
                 Ok(0)
             }
-            SpecSubcommand::Agent(agent) => Ok(agent::run_agent(agent)?),
-            SpecSubcommand::Search(search) => {
-                run_search(search)?;
+            SpecSubcommand::Import(import) => {
+                run_import(import)?;
                 Ok(0)
             }
-            SpecSubcommand::Ask(args) => {
-                ask::run_ask(&args)?;
+            SpecSubcommand::Node(node) => {
+                run_node(node)?;
+                Ok(0)
+            }
+            SpecSubcommand::Migrate => {
+                run_migrate()?;
+                Ok(0)
+            }
+            SpecSubcommand::Term(term) => {
+                run_term(term)?;
+                Ok(0)
+            }
+            SpecSubcommand::Doctor(args) => Ok(run_doctor(&args)?),
+            SpecSubcommand::History(args) => {
+                run_history(&args)?;
+                Ok(0)
+            }
+            SpecSubcommand::Coverage(args) => {
+                run_coverage(&args)?;
+                Ok(0)
+            }
+            SpecSubcommand::Completions(args) => {
+                run_completions(&args)?;
+                Ok(0)
+            }
+            #[cfg(feature = "serve")]
+            SpecSubcommand::Serve(args) => {
+                run_serve(&args)?;
                 Ok(0)
             }
         },
@@ -36,7 +36,7 @@ struct TemplateContext {
     default_depth: String,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize)]
 pub(super) struct AgentTemplateSummary {
     pub(super) written: usize,
     pub(super) skipped: usize,
@@ -50,10 +50,72 @@ pub(super) struct TemplateConfig {
     pub(super) output: AgentOutput,
     pub(super) codex_home: Option<String>,
     pub(super) claude_dir: Option<String>,
+    pub(super) gemini_home: Option<String>,
+    pub(super) agent_dirs: Vec<GenericAgentDir>,
+}
+
+/// `--agent-dir <name>:<path>`: a tool without built-in support, rendered
+/// from the `generic` overlays straight into `<path>/{commands|skills}/`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct GenericAgentDir {
+    name: String,
+    dir: PathBuf,
+}
+
+pub(super) fn parse_agent_dirs(values: &[String]) -> Result<Vec<GenericAgentDir>> {
+    values
+        .iter()
+        .map(|value| {
+            let (name, dir) = value
+                .split_once(':')
+                .filter(|(name, dir)| !name.trim().is_empty() && !dir.trim().is_empty())
+                .with_context(|| format!("--agent-dir expects <name>:<path>, got {value:?}"))?;
+            Ok(GenericAgentDir {
+                name: name.trim().to_string(),
+                dir: PathBuf::from(dir.trim()),
+            })
+        })
+        .collect()
+}
+
+/// One destination for rendered templates: a built-in agent or an
+/// `--agent-dir` generic one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum AgentDest {
+    Builtin(AgentTarget),
+    Generic(GenericAgentDir),
+}
+
+impl AgentDest {
+    /// Build the destination list: `agents` first, then `config.agent_dirs`,
+    /// without duplicates.
+    fn collect(agents: &[AgentTarget], config: &TemplateConfig) -> Vec<Self> {
+        let mut uniq = HashSet::new();
+        agents
+            .iter()
+            .map(|agent| Self::Builtin(*agent))
+            .chain(config.agent_dirs.iter().cloned().map(Self::Generic))
+            .filter(|dest| uniq.insert(dest.clone()))
+            .collect()
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Builtin(agent) => agent_slug(*agent),
+            Self::Generic(generic) => &generic.name,
+        }
+    }
+
+    fn overlay_slug(&self) -> &'static str {
+        match self {
+            Self::Builtin(agent) => agent_slug(*agent),
+            Self::Generic(_) => "generic",
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
-struct AgentDoctorIssue {
+pub(super) struct AgentDoctorIssue {
     agent: String,
     artifact: String,
     phase: String,
@@ -61,11 +123,20 @@ struct AgentDoctorIssue {
     detail: String,
 }
 
+impl AgentDoctorIssue {
+    pub(super) fn describe(&self) -> String {
+        format!(
+            "agent={} artifact={} phase={} kind={} detail={}",
+            self.agent, self.artifact, self.phase, self.kind, self.detail
+        )
+    }
+}
+
 #[derive(Debug, Serialize)]
-struct AgentDoctorOutput {
-    ok: bool,
+pub(super) struct AgentDoctorOutput {
+    pub(super) ok: bool,
     checked: usize,
-    issues: Vec<AgentDoctorIssue>,
+    pub(super) issues: Vec<AgentDoctorIssue>,
 }
 
 pub(super) fn run_agent(agent: AgentCommand) -> Result<i32> {
@@ -78,10 +149,82 @@ pub(super) fn run_agent(agent: AgentCommand) -> Result<i32> {
                 output: args.agent_output,
                 codex_home: args.codex_home.clone(),
                 claude_dir: args.claude_dir.clone(),
+                gemini_home: args.gemini_home.clone(),
+                agent_dirs: parse_agent_dirs(&args.agent_dirs)?,
             };
             run_agent_doctor(&args, &config)
         }
+        AgentSubcommand::List(args) => run_agent_list(&args).map(|_| 0),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AgentListOutput {
+    agents: Vec<AgentListEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct AgentListEntry {
+    agent: String,
+    docs_dir: String,
+    install_dirs: Vec<AgentInstallDir>,
+}
+
+#[derive(Debug, Serialize)]
+struct AgentInstallDir {
+    artifact: String,
+    path: String,
+}
+
+/// Every `--agent` value with where `--agent-output docs` and `install`
+/// would write its files.
+fn run_agent_list(args: &AgentListArgs) -> Result<()> {
+    let config = TemplateConfig {
+        source: TemplateSource::Local,
+        repo: String::new(),
+        git_ref: String::new(),
+        output: AgentOutput::Both,
+        codex_home: args.codex_home.clone(),
+        claude_dir: args.claude_dir.clone(),
+        gemini_home: args.gemini_home.clone(),
+        agent_dirs: Vec::new(),
+    };
+    let agents = <AgentTarget as clap::ValueEnum>::value_variants()
+        .iter()
+        .map(|agent| {
+            let slug = agent_slug(*agent);
+            let install_dirs = TEMPLATE_ARTIFACTS
+                .iter()
+                .map(|artifact| AgentInstallDir {
+                    artifact: artifact.label.to_string(),
+                    path: install_dir(*agent, *artifact, &config)
+                        .to_string_lossy()
+                        .to_string(),
+                })
+                .collect();
+            AgentListEntry {
+                agent: slug.to_string(),
+                docs_dir: format!("docs/agents/{slug}"),
+                install_dirs,
+            }
+        })
+        .collect();
+    let output = AgentListOutput { agents };
+    match args.format {
+        AgentFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        AgentFormat::Table => {
+            for entry in &output.agents {
+                let install = entry
+                    .install_dirs
+                    .iter()
+                    .map(|dir| format!("{}={}", dir.artifact, dir.path))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                println!("{} | docs={} | {install}", entry.agent, entry.docs_dir);
+            }
+        }
     }
+    Ok(())
 }
 
 pub(super) fn generate_agent_templates(
@@ -92,12 +235,8 @@ pub(super) fn generate_agent_templates(
     let mut summary = AgentTemplateSummary::default();
     let template_base_root = resolve_template_base_root(config);
     let context = build_template_context();
-    let mut uniq = HashSet::new();
-    for agent in agents {
-        if !uniq.insert(*agent) {
-            continue;
-        }
-        let slug = agent_slug(*agent);
+    for dest in AgentDest::collect(agents, config) {
+        let slug = dest.overlay_slug();
         for artifact in TEMPLATE_ARTIFACTS {
             let template_root = template_base_root.join(artifact.template_subdir);
             for phase in TEMPLATE_PHASES {
@@ -107,7 +246,7 @@ pub(super) fn generate_agent_templates(
                     Ok(v) => v,
                     Err(err) => {
                         summary.errors += 1;
-                        eprintln!("agent template error reading {}: {err}", base_path.display());
+                        log::error!("reading agent template {}: {err}", base_path.display());
                         continue;
                     }
                 };
@@ -115,16 +254,13 @@ pub(super) fn generate_agent_templates(
                     Ok(v) => v,
                     Err(err) => {
                         summary.errors += 1;
-                        eprintln!(
-                            "agent template error reading {}: {err}",
-                            overlay_path.display()
-                        );
+                        log::error!("reading agent template {}: {err}", overlay_path.display());
                         continue;
                     }
                 };
 
-                let rendered = render_template(*agent, *artifact, &base, &overlay, &context);
-                let out_paths = output_paths(*agent, *artifact, phase, config);
+                let rendered = render_template(&dest, *artifact, &base, &overlay, &context);
+                let out_paths = output_paths(&dest, *artifact, phase, config);
                 let mut uniq_paths = HashSet::new();
                 for out_path in out_paths {
                     if !uniq_paths.insert(out_path.clone()) {
@@ -138,12 +274,12 @@ pub(super) fn generate_agent_templates(
                         && let Err(err) = fs::create_dir_all(parent)
                     {
                         summary.errors += 1;
-                        eprintln!("agent template error creating {}: {err}", parent.display());
+                        log::error!("creating {}: {err}", parent.display());
                         continue;
                     }
                     if let Err(err) = fs::write(&out_path, &rendered) {
                         summary.errors += 1;
-                        eprintln!("agent template error writing {}: {err}", out_path.display());
+                        log::error!("writing agent template {}: {err}", out_path.display());
                         continue;
                     }
                     summary.written += 1;
@@ -155,31 +291,46 @@ pub(super) fn generate_agent_templates(
 }
 
 fn run_agent_doctor(args: &AgentDoctorArgs, config: &TemplateConfig) -> Result<i32> {
-    let agents = if args.agent.is_empty() {
-        vec![AgentTarget::Codex, AgentTarget::Claude]
+    let output = check_agent_templates(&args.agent, config);
+    match args.format {
+        AgentFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        AgentFormat::Table => print_agent_doctor_table(&output),
+    }
+    if output.ok {
+        Ok(0)
     } else {
-        args.agent.clone()
+        Ok(1)
+    }
+}
+
+/// Compares generated agent files against freshly rendered templates; with
+/// neither `agents` nor `--agent-dir` given, checks codex and claude.
+pub(super) fn check_agent_templates(
+    agents: &[AgentTarget],
+    config: &TemplateConfig,
+) -> AgentDoctorOutput {
+    let agents = if agents.is_empty() && config.agent_dirs.is_empty() {
+        AgentDest::collect(&[AgentTarget::Codex, AgentTarget::Claude], config)
+    } else {
+        AgentDest::collect(agents, config)
     };
-    let mut uniq = HashSet::new();
-    let agents = agents
-        .into_iter()
-        .filter(|a| uniq.insert(*a))
-        .collect::<Vec<_>>();
     let context = build_template_context();
     let template_base_root = resolve_template_base_root(config);
 
     let mut issues = Vec::<AgentDoctorIssue>::new();
     let mut checked = 0usize;
 
-    for agent in agents {
-        let slug = agent_slug(agent).to_string();
+    for dest in agents {
+        let slug = dest.name().to_string();
+        let overlay_slug = dest.overlay_slug();
         for artifact in TEMPLATE_ARTIFACTS {
             let template_root = template_base_root.join(artifact.template_subdir);
             for phase in TEMPLATE_PHASES {
                 checked += 1;
                 let base_path = template_root.join(format!("base/{phase}.md"));
-                let overlay_path = template_root.join(format!("overlays/{slug}/{phase}.md"));
-                let out_paths = output_paths(agent, *artifact, phase, config);
+                let overlay_path =
+                    template_root.join(format!("overlays/{overlay_slug}/{phase}.md"));
+                let out_paths = output_paths(&dest, *artifact, phase, config);
 
                 let base = match fs::read_to_string(&base_path) {
                     Ok(v) => v,
@@ -210,7 +361,7 @@ fn run_agent_doctor(args: &AgentDoctorArgs, config: &TemplateConfig) -> Result<i
                         continue;
                     }
                 };
-                let expected = render_template(agent, *artifact, &base, &overlay, &context);
+                let expected = render_template(&dest, *artifact, &base, &overlay, &context);
                 let mut uniq_paths = HashSet::new();
                 for out_path in out_paths {
                     if !uniq_paths.insert(out_path.clone()) {
@@ -249,19 +400,10 @@ fn run_agent_doctor(args: &AgentDoctorArgs, config: &TemplateConfig) -> Result<i
         }
     }
 
-    let output = AgentDoctorOutput {
+    AgentDoctorOutput {
         ok: issues.is_empty(),
         checked,
         issues,
-    };
-    match args.format {
-        AgentFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
-        AgentFormat::Table => print_agent_doctor_table(&output),
-    }
-    if output.ok {
-        Ok(0)
-    } else {
-        Ok(1)
     }
 }
 
@@ -272,8 +414,8 @@ fn resolve_template_base_root(config: &TemplateConfig) -> PathBuf {
         TemplateSource::Github => match sync_templates_from_github(config) {
             Ok(path) => path,
             Err(err) => {
-                eprintln!(
-                    "agent template warning: failed to fetch templates from github ({err}). fallback to local templates."
+                log::warn!(
+                    "failed to fetch agent templates from github ({err}); falling back to local templates"
                 );
                 local_root
             }
@@ -394,10 +536,7 @@ fn print_agent_doctor_table(output: &AgentDoctorOutput) {
         return;
     }
     for issue in &output.issues {
-        println!(
-            "agent doctor: issue: agent={} artifact={} phase={} kind={} detail={}",
-            issue.agent, issue.artifact, issue.phase, issue.kind, issue.detail
-        );
+        println!("agent doctor: issue: {}", issue.describe());
     }
     println!(
         "agent doctor summary: checked={} issues={}",
@@ -407,7 +546,7 @@ fn print_agent_doctor_table(output: &AgentDoctorOutput) {
 }
 
 fn render_template(
-    agent: AgentTarget,
+    dest: &AgentDest,
     artifact: TemplateArtifact,
     base: &str,
     overlay: &str,
@@ -415,7 +554,7 @@ fn render_template(
 ) -> String {
     let mut out = String::new();
     // Codex command prompts require metadata at the file head.
-    if agent == AgentTarget::Codex && artifact.output_subdir == "commands" {
+    if *dest == AgentDest::Builtin(AgentTarget::Codex) && artifact.output_subdir == "commands" {
         out.push_str(overlay.trim_end());
         out.push_str("\n\n---\n\n");
         out.push_str(base.trim_end());
@@ -432,6 +571,7 @@ fn agent_slug(agent: AgentTarget) -> &'static str {
     match agent {
         AgentTarget::Codex => "codex",
         AgentTarget::Claude => "claude",
+        AgentTarget::Gemini => "gemini",
     }
 }
 
@@ -482,11 +622,22 @@ fn apply_placeholders(text: &str, context: &TemplateContext) -> String {
 }
 
 fn output_paths(
-    agent: AgentTarget,
+    dest: &AgentDest,
     artifact: TemplateArtifact,
     phase: &str,
     config: &TemplateConfig,
 ) -> Vec<PathBuf> {
+    let agent = match dest {
+        AgentDest::Builtin(agent) => *agent,
+        AgentDest::Generic(generic) => {
+            return vec![
+                generic
+                    .dir
+                    .join(artifact.output_subdir)
+                    .join(format!("{phase}.md")),
+            ];
+        }
+    };
     let mut paths = Vec::new();
     if matches!(config.output, AgentOutput::Docs | AgentOutput::Both) {
         let slug = agent_slug(agent);
@@ -507,23 +658,26 @@ fn install_path(
     phase: &str,
     config: &TemplateConfig,
 ) -> PathBuf {
+    install_dir(agent, artifact, config).join(format!("{phase}.md"))
+}
+
+fn install_dir(agent: AgentTarget, artifact: TemplateArtifact, config: &TemplateConfig) -> PathBuf {
     match agent {
         AgentTarget::Codex => {
             if artifact.output_subdir == "commands" {
-                resolve_codex_home(config)
-                    .join("prompts")
-                    .join(format!("{phase}.md"))
+                resolve_codex_home(config).join("prompts")
             } else {
                 resolve_codex_home(config)
                     .join(artifact.output_subdir)
                     .join("foundry")
-                    .join(format!("{phase}.md"))
             }
         }
         AgentTarget::Claude => resolve_claude_dir(config)
             .join(artifact.output_subdir)
-            .join("foundry")
-            .join(format!("{phase}.md")),
+            .join("foundry"),
+        AgentTarget::Gemini => resolve_gemini_home(config)
+            .join(artifact.output_subdir)
+            .join("foundry"),
     }
 }
 
@@ -540,6 +694,23 @@ fn resolve_codex_home(config: &TemplateConfig) -> PathBuf {
     PathBuf::from(".codex")
 }
 
+fn resolve_gemini_home(config: &TemplateConfig) -> PathBuf {
+    if let Some(path) = config.gemini_home.as_ref().filter(|s| !s.trim().is_empty()) {
+        return PathBuf::from(path);
+    }
+    if let Ok(path) = std::env::var("GEMINI_HOME")
+        && !path.trim().is_empty()
+    {
+        return PathBuf::from(path);
+    }
+    if let Ok(home) = std::env::var("HOME")
+        && !home.trim().is_empty()
+    {
+        return PathBuf::from(home).join(".gemini");
+    }
+    PathBuf::from(".gemini")
+}
+
 fn resolve_claude_dir(config: &TemplateConfig) -> PathBuf {
     if let Some(path) = config.claude_dir.as_ref().filter(|s| !s.trim().is_empty()) {
         return PathBuf::from(path);
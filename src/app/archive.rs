@@ -0,0 +1,109 @@
+use super::*;
+
+const ARCHIVE_ROOT: &str = "spec/archive";
+
+pub(super) fn run_archive(args: &ArchiveArgs) -> Result<()> {
+    let spec_root = Path::new("spec");
+    let metas = load_all_meta(spec_root, &mut LintState::default())?;
+    let mut by_id = HashMap::<String, (PathBuf, SpecNodeMeta)>::new();
+    for (path, meta) in metas {
+        by_id.insert(meta.id.clone(), (path, meta));
+    }
+    let (meta_path, meta) = by_id
+        .get(&args.node_id)
+        .cloned()
+        .with_context(|| format!("node not found: {}", args.node_id))?;
+    if meta.body_md_path.starts_with(&format!("{ARCHIVE_ROOT}/")) {
+        anyhow::bail!("node already archived: {} ({})", meta.id, meta.body_md_path);
+    }
+
+    let mut blockers = by_id
+        .values()
+        .filter(|(_, m)| m.id != meta.id && !is_done_status(&m.status))
+        .filter(|(_, m)| {
+            m.edges.iter().any(|e| {
+                e.to == meta.id && e.edge_type == "depends_on" && e.status == "confirmed"
+            })
+        })
+        .map(|(_, m)| m.id.clone())
+        .collect::<Vec<_>>();
+    blockers.sort();
+    if !blockers.is_empty() && !args.force {
+        anyhow::bail!(
+            "cannot archive {}: active nodes depend on it ({}); use --force to archive anyway",
+            meta.id,
+            blockers.join(", ")
+        );
+    }
+
+    let new_md_path = archived_body_path(&meta.body_md_path);
+    let new_md = PathBuf::from(&new_md_path);
+    let new_meta_path = md_to_meta_path(&new_md)?;
+    if new_md.exists() || new_meta_path.exists() {
+        anyhow::bail!("archive target already exists: {new_md_path}");
+    }
+    if let Some(parent) = new_md.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed creating {}", parent.display()))?;
+    }
+    // Meta first: if it cannot be written nothing has moved, and if the body
+    // then fails to move the new meta is dropped again, so no meta is left
+    // pointing at a missing markdown file.
+    let mut archived = meta.clone();
+    archived.status = "archived".to_string();
+    archived.body_md_path = new_md_path.clone();
+    write_meta_json(&new_meta_path, &archived)?;
+    if let Err(err) = fs::rename(&meta.body_md_path, &new_md) {
+        let _ = fs::remove_file(&new_meta_path);
+        return Err(err)
+            .with_context(|| format!("failed moving {} to {new_md_path}", meta.body_md_path));
+    }
+    fs::remove_file(&meta_path)
+        .with_context(|| format!("failed removing {}", meta_path.display()))?;
+
+    let mut downgraded = 0usize;
+    let mut audit_ids = vec![meta.id.clone()];
+    let mut ids = by_id.keys().cloned().collect::<Vec<_>>();
+    ids.sort();
+    for id in ids {
+        if id == meta.id {
+            continue;
+        }
+        let (path, from_meta) = by_id.get_mut(&id).expect("id exists");
+        let mut changed = false;
+        for edge in &mut from_meta.edges {
+            if edge.to == meta.id
+                && matches!(edge.edge_type.as_str(), "depends_on" | "refines")
+                && edge.status != "proposed"
+            {
+                edge.status = "proposed".to_string();
+                changed = true;
+                downgraded += 1;
+                println!(
+                    "  edge downgraded to proposed: {} -> {} ({})",
+                    from_meta.id, edge.to, edge.edge_type
+                );
+            }
+        }
+        if changed {
+            write_meta_json(path, from_meta)?;
+            audit_ids.push(id);
+        }
+    }
+    record_audit("archive", "archive", &audit_ids);
+
+    println!(
+        "spec archive: id={} md={} meta={} downgraded_edges={}",
+        archived.id,
+        new_md_path,
+        new_meta_path.display(),
+        downgraded
+    );
+    Ok(())
+}
+
+/// `spec/a/b.md` -> `spec/archive/a/b.md`, `tasks/t.md` -> `spec/archive/tasks/t.md`.
+fn archived_body_path(body_md_path: &str) -> String {
+    let relative = body_md_path.strip_prefix("spec/").unwrap_or(body_md_path);
+    format!("{ARCHIVE_ROOT}/{relative}")
+}
@@ -3,17 +3,26 @@ mod retrieval;
 mod synthesis;
 
 #[derive(Debug, Serialize)]
-struct AskCitation {
-    id: String,
+pub(super) struct AskCitation {
+    pub(super) id: String,
     title: String,
     path: String,
+    /// Full markdown of the node with `--cite-bodies`, possibly shortened
+    /// to fit `--max-context-chars`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    body_truncated: bool,
+    /// Set when `--cite-bodies` could not read `path`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    body_unavailable: bool,
 }
 
 #[derive(Debug, Serialize)]
-struct AskEvidence {
-    id: String,
+pub(super) struct AskEvidence {
+    pub(super) id: String,
     snippet: String,
-    score: f64,
+    pub(super) score: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -23,41 +32,92 @@ pub(super) struct AskExplanation {
 }
 
 #[derive(Debug, Serialize)]
-struct AskOutput {
+pub(super) struct AskOutput {
     question: String,
     mode: String,
     answer: String,
     confidence: f64,
-    citations: Vec<AskCitation>,
-    evidence: Vec<AskEvidence>,
+    pub(super) citations: Vec<AskCitation>,
+    pub(super) evidence: Vec<AskEvidence>,
     explanations: Vec<AskExplanation>,
     gaps: Vec<String>,
 }
 
 pub(super) fn run_ask(args: &AskArgs) -> Result<()> {
     let config = load_runtime_config();
-    let retrieved = retrieval::retrieve_ask_inputs(args)?;
-    let output = synthesis::synthesize_ask_output(
-        args,
-        retrieved.mode,
-        retrieved.hits,
-        &retrieved.meta_by_id,
-        &config.ask,
-    );
+    let Some(questions_file) = &args.questions_file else {
+        let question = args.question.as_deref().expect("required by clap");
+        let corpus = retrieval::load_ask_corpus(args)?;
+        let output = answer_question(&corpus, args, question, &config)?;
+        match args.format {
+            AskFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+            AskFormat::Table => print_ask_table(&output),
+            AskFormat::Markdown => print!("{}", synthesis::render_ask_markdown(&output)),
+        }
+        return Ok(());
+    };
+
+    let raw = fs::read_to_string(questions_file)
+        .with_context(|| format!("failed reading --questions-file: {questions_file}"))?;
+    let questions = raw
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    if questions.is_empty() {
+        anyhow::bail!("no questions in --questions-file: {questions_file}");
+    }
+    let corpus = retrieval::load_ask_corpus(args)?;
+    let outputs = questions
+        .iter()
+        .map(|question| answer_question(&corpus, args, question, &config))
+        .collect::<Result<Vec<_>>>()?;
     match args.format {
-        AskFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
-        AskFormat::Table => print_ask_table(&output),
+        AskFormat::Json => println!("{}", serde_json::to_string_pretty(&outputs)?),
+        AskFormat::Table => {
+            for (idx, output) in outputs.iter().enumerate() {
+                if idx > 0 {
+                    println!();
+                }
+                print_ask_table(output);
+            }
+        }
+        AskFormat::Markdown => {
+            let reports = outputs
+                .iter()
+                .map(synthesis::render_ask_markdown)
+                .collect::<Vec<_>>();
+            print!("{}", reports.join("\n---\n\n"));
+        }
     }
     Ok(())
 }
 
+fn answer_question(
+    corpus: &retrieval::AskCorpus,
+    args: &AskArgs,
+    question: &str,
+    config: &RuntimeConfig,
+) -> Result<AskOutput> {
+    let hits = retrieval::retrieve_ask_hits(corpus, args, question, config)?;
+    Ok(synthesis::synthesize_ask_output(
+        args,
+        question,
+        retrieval::ask_mode_label(args.mode),
+        hits,
+        &corpus.meta_by_id,
+        &config.ask,
+    ))
+}
+
 pub(super) fn expand_ask_context(
     hits: &[SearchHit],
     meta_by_id: &HashMap<String, SpecNodeMeta>,
     limit: usize,
     weights: &AskEdgeWeightConfig,
+    filter: &EdgeFilter,
 ) -> (Vec<String>, Vec<String>) {
-    retrieval::expand_ask_context(hits, meta_by_id, limit, weights)
+    retrieval::expand_ask_context(hits, meta_by_id, limit, weights, filter)
 }
 
 #[allow(dead_code)]
@@ -71,9 +131,42 @@ pub(super) fn build_ask_explanations(
     synthesis::build_ask_explanations(question, hits, related_ids, meta_by_id, weights)
 }
 
+#[allow(dead_code)]
+pub(super) fn synthesize_ask_output(
+    args: &AskArgs,
+    hits: Vec<SearchHit>,
+    meta_by_id: &HashMap<String, SpecNodeMeta>,
+    config: &AskRuntimeConfig,
+) -> AskOutput {
+    let question = args.question.as_deref().unwrap_or_default();
+    synthesis::synthesize_ask_output(
+        args,
+        question,
+        "lexical".to_string(),
+        hits,
+        meta_by_id,
+        config,
+    )
+}
+
 fn print_ask_table(output: &AskOutput) {
     synthesis::print_ask_table(output);
 }
+
+pub(super) fn edge_contribution(edge: &SpecEdge, w: &AskEdgeWeightConfig) -> f64 {
+    edge_weight(edge.edge_type.as_str(), w)
+        * edge.confidence.clamp(0.0, 1.0)
+        * edge_status_factor(&edge.status)
+}
+
+fn edge_status_factor(status: &str) -> f64 {
+    match status {
+        "confirmed" => 1.0,
+        "proposed" => 0.5,
+        _ => 0.0,
+    }
+}
+
 pub(super) fn edge_weight(edge_type: &str, w: &AskEdgeWeightConfig) -> f64 {
     match edge_type {
         "depends_on" => w.depends_on,
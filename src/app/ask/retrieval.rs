@@ -1,15 +1,15 @@
 use super::*;
 
-pub(super) struct AskRetrieved {
-    pub(super) mode: String,
-    pub(super) hits: Vec<SearchHit>,
+/// Index connection and meta map, loaded once and shared by every question
+/// of an `ask` run.
+pub(super) struct AskCorpus {
+    conn: Connection,
     pub(super) meta_by_id: HashMap<String, SpecNodeMeta>,
 }
 
-pub(super) fn retrieve_ask_inputs(args: &AskArgs) -> Result<AskRetrieved> {
-    let conn = open_search_db()?;
+pub(super) fn load_ask_corpus(args: &AskArgs) -> Result<AskCorpus> {
+    let conn = open_search_db(args.db.as_deref().map(Path::new))?;
     ensure_search_schema_readonly(&conn)?;
-    let hits = build_search_hits(&conn, &args.question, args.top_k, args.mode)?;
 
     let spec_root = Path::new("spec");
     let mut lint = LintState::default();
@@ -18,18 +18,37 @@ pub(super) fn retrieve_ask_inputs(args: &AskArgs) -> Result<AskRetrieved> {
     for (_, meta) in metas {
         meta_by_id.insert(meta.id.clone(), meta);
     }
+    Ok(AskCorpus { conn, meta_by_id })
+}
+
+pub(super) fn retrieve_ask_hits(
+    corpus: &AskCorpus,
+    args: &AskArgs,
+    question: &str,
+    config: &RuntimeConfig,
+) -> Result<Vec<SearchHit>> {
+    // Fetch through the end of the requested page, then drop the ranks
+    // before it; everything downstream only sees the offset window.
+    Ok(build_search_hits(
+        &corpus.conn,
+        question,
+        args.offset + args.top_k,
+        args.mode,
+        &SearchFilter::default(),
+        config,
+    )?
+    .into_iter()
+    .skip(args.offset)
+    .collect())
+}
 
-    let mode = match args.mode {
+pub(super) fn ask_mode_label(mode: SearchMode) -> String {
+    match mode {
         SearchMode::Lexical => "lexical",
         SearchMode::Hybrid => "hybrid",
+        SearchMode::Rerank => "rerank",
     }
-    .to_string();
-
-    Ok(AskRetrieved {
-        mode,
-        hits,
-        meta_by_id,
-    })
+    .to_string()
 }
 
 pub(super) fn expand_ask_context(
@@ -37,6 +56,7 @@ pub(super) fn expand_ask_context(
     meta_by_id: &HashMap<String, SpecNodeMeta>,
     limit: usize,
     weights: &AskEdgeWeightConfig,
+    filter: &EdgeFilter,
 ) -> (Vec<String>, Vec<String>) {
     let seed_ids = hits.iter().map(|h| h.id.clone()).collect::<HashSet<_>>();
     let mut related_score = HashMap::<String, f64>::new();
@@ -44,15 +64,15 @@ pub(super) fn expand_ask_context(
 
     for seed_id in &seed_ids {
         if let Some(meta) = meta_by_id.get(seed_id) {
-            for edge in &meta.edges {
+            for edge in meta.edges.iter().filter(|e| filter.allows(e)) {
                 match edge.edge_type.as_str() {
                     "depends_on" | "tests" | "refines" | "impacts" => {
                         *related_score.entry(edge.to.clone()).or_insert(0.0) +=
-                            edge_weight(edge.edge_type.as_str(), weights);
+                            edge_contribution(edge, weights);
                     }
                     "conflicts_with" => {
                         *related_score.entry(edge.to.clone()).or_insert(0.0) +=
-                            edge_weight(edge.edge_type.as_str(), weights);
+                            edge_contribution(edge, weights);
                         conflicts.insert(edge.to.clone());
                     }
                     _ => {}
@@ -61,17 +81,17 @@ pub(super) fn expand_ask_context(
         }
         for (id, candidate) in meta_by_id {
             for edge in &candidate.edges {
-                if edge.to != *seed_id {
+                if edge.to != *seed_id || !filter.allows(edge) {
                     continue;
                 }
                 match edge.edge_type.as_str() {
                     "depends_on" | "tests" | "refines" | "impacts" => {
                         *related_score.entry(id.clone()).or_insert(0.0) +=
-                            edge_weight(edge.edge_type.as_str(), weights) * 0.9;
+                            edge_contribution(edge, weights) * 0.9;
                     }
                     "conflicts_with" => {
                         *related_score.entry(id.clone()).or_insert(0.0) +=
-                            edge_weight(edge.edge_type.as_str(), weights) * 0.9;
+                            edge_contribution(edge, weights) * 0.9;
                         conflicts.insert(id.clone());
                     }
                     _ => {}
@@ -2,33 +2,49 @@ use super::*;
 
 pub(super) fn synthesize_ask_output(
     args: &AskArgs,
+    question: &str,
     mode: String,
     hits: Vec<SearchHit>,
     meta_by_id: &HashMap<String, SpecNodeMeta>,
     config: &AskRuntimeConfig,
 ) -> AskOutput {
     if hits.is_empty() {
+        let gap = if args.offset > 0 {
+            format!(
+                "No matching spec nodes beyond offset {}. Try a smaller --offset.",
+                args.offset
+            )
+        } else {
+            "No matching spec nodes. Try a broader query or run `foundry spec search index --rebuild`."
+                .to_string()
+        };
         return AskOutput {
-            question: args.question.clone(),
+            question: question.to_string(),
             mode,
             answer: "No relevant spec nodes were found for this question.".to_string(),
             confidence: 0.0,
             citations: Vec::new(),
             evidence: Vec::new(),
             explanations: Vec::new(),
-            gaps: vec![
-                "No matching spec nodes. Try a broader query or run `foundry spec search index --rebuild`."
-                    .to_string(),
-            ],
+            gaps: vec![gap],
         };
     }
 
-    let (related_ids, conflict_risks) = expand_ask_context(
-        &hits,
-        meta_by_id,
-        config.neighbor_limit,
-        &config.edge_weight,
-    );
+    let hits = dedupe_hits_by_id(hits);
+    let (related_ids, conflict_risks) = if args.no_graph {
+        (Vec::new(), Vec::new())
+    } else {
+        expand_ask_context(
+            &hits,
+            meta_by_id,
+            config.neighbor_limit,
+            &config.edge_weight,
+            &EdgeFilter {
+                min_confidence: args.min_confidence,
+                confirmed_only: args.confirmed_only,
+            },
+        )
+    };
     let primary_ids = hits.iter().map(|h| h.id.clone()).collect::<HashSet<_>>();
     let mut citations = hits
         .iter()
@@ -36,10 +52,14 @@ pub(super) fn synthesize_ask_output(
             id: hit.id.clone(),
             title: hit.title.clone(),
             path: hit.path.clone(),
+            body: None,
+            body_truncated: false,
+            body_unavailable: false,
         })
         .collect::<Vec<_>>();
+    let mut neighbor_ids = HashSet::new();
     for related_id in &related_ids {
-        if primary_ids.contains(related_id) {
+        if primary_ids.contains(related_id) || !neighbor_ids.insert(related_id) {
             continue;
         }
         if let Some(meta) = meta_by_id.get(related_id) {
@@ -47,19 +67,23 @@ pub(super) fn synthesize_ask_output(
                 id: meta.id.clone(),
                 title: meta.title.clone(),
                 path: meta.body_md_path.clone(),
+                body: None,
+                body_truncated: false,
+                body_unavailable: false,
             });
         }
     }
 
-    let evidence = hits
+    let mut evidence = hits
         .iter()
         .map(|hit| AskEvidence {
             id: hit.id.clone(),
             snippet: hit.snippet.clone(),
             score: hit.score,
         })
+        // Taking ids out of `neighbor_ids` emits each cited neighbor once.
         .chain(related_ids.iter().filter_map(|id| {
-            if primary_ids.contains(id) {
+            if !neighbor_ids.remove(id) {
                 return None;
             }
             meta_by_id.get(id).map(|meta| AskEvidence {
@@ -69,6 +93,8 @@ pub(super) fn synthesize_ask_output(
             })
         }))
         .collect::<Vec<_>>();
+    let max_context_chars = args.max_context_chars.unwrap_or(config.max_context_chars);
+    let budget_gap = apply_evidence_char_budget(&mut evidence, max_context_chars);
 
     let focus_titles = citations
         .iter()
@@ -77,9 +103,12 @@ pub(super) fn synthesize_ask_output(
         .collect::<Vec<_>>()
         .join(", ");
     let top_score = hits.first().map(|h| h.score).unwrap_or(0.0);
-    let confidence = confidence_from_hits(top_score, hits.len(), conflict_risks.is_empty());
+    let no_conflict_risk = (!args.no_graph).then_some(conflict_risks.is_empty());
+    let confidence = confidence_from_hits(top_score, hits.len(), no_conflict_risk);
 
-    let related_summary = if related_ids.is_empty() {
+    let related_summary = if args.no_graph {
+        "Graph context was skipped (--no-graph).".to_string()
+    } else if related_ids.is_empty() {
         "No adjacent dependency/test nodes were found.".to_string()
     } else {
         format!(
@@ -92,7 +121,9 @@ pub(super) fn synthesize_ask_output(
                 .join(", ")
         )
     };
-    let risk_summary = if conflict_risks.is_empty() {
+    let risk_summary = if args.no_graph {
+        "Conflict edges were not checked.".to_string()
+    } else if conflict_risks.is_empty() {
         "No direct conflict edges were detected in the 1-hop context.".to_string()
     } else {
         format!("Conflict risks to review: {}.", conflict_risks.join(", "))
@@ -114,13 +145,17 @@ pub(super) fn synthesize_ask_output(
     if hits.len() < 2 {
         gaps.push("Low evidence count: fewer than 2 strong retrieval hits.".to_string());
     }
-    if citations.len() <= 1 {
+    if citations.len() <= 1 && !args.no_graph {
         gaps.push("Limited cross-spec context: consider adding more explicit links.".to_string());
     }
+    gaps.extend(budget_gap);
+    if args.cite_bodies {
+        gaps.extend(attach_citation_bodies(&mut citations, max_context_chars));
+    }
 
     let explanations = if args.explain {
         build_ask_explanations(
-            &args.question,
+            question,
             &hits,
             &related_ids,
             meta_by_id,
@@ -131,7 +166,7 @@ pub(super) fn synthesize_ask_output(
     };
 
     AskOutput {
-        question: args.question.clone(),
+        question: question.to_string(),
         mode,
         answer,
         confidence,
@@ -142,7 +177,24 @@ pub(super) fn synthesize_ask_output(
     }
 }
 
-fn confidence_from_hits(top_score: f64, hit_count: usize, no_conflict_risk: bool) -> f64 {
+/// One hit per id: a repeated id keeps its first position but takes the
+/// fields of its highest-scoring occurrence.
+fn dedupe_hits_by_id(hits: Vec<SearchHit>) -> Vec<SearchHit> {
+    let mut out = Vec::<SearchHit>::with_capacity(hits.len());
+    for hit in hits {
+        match out.iter_mut().find(|kept| kept.id == hit.id) {
+            Some(kept) if kept.score < hit.score => *kept = hit,
+            Some(_) => {}
+            None => out.push(hit),
+        }
+    }
+    out
+}
+
+/// `no_conflict_risk` is `None` under `--no-graph`: conflicts were never
+/// looked for, so the risk signal is dropped and the other two keep their
+/// relative weights.
+fn confidence_from_hits(top_score: f64, hit_count: usize, no_conflict_risk: Option<bool>) -> f64 {
     if hit_count == 0 {
         return 0.0;
     }
@@ -152,8 +204,14 @@ fn confidence_from_hits(top_score: f64, hit_count: usize, no_conflict_risk: bool
         top_score.abs().min(1.0)
     };
     let coverage_signal = (hit_count as f64 / 5.0).min(1.0);
-    let risk_signal = if no_conflict_risk { 1.0 } else { 0.6 };
-    ((score_signal * 0.5) + (coverage_signal * 0.35) + (risk_signal * 0.15)).min(1.0)
+    let retrieval = (score_signal * 0.5) + (coverage_signal * 0.35);
+    match no_conflict_risk {
+        Some(no_risk) => {
+            let risk_signal = if no_risk { 1.0 } else { 0.6 };
+            (retrieval + risk_signal * 0.15).min(1.0)
+        }
+        None => (retrieval / 0.85).min(1.0),
+    }
 }
 
 pub(super) fn print_ask_table(output: &AskOutput) {
@@ -191,6 +249,90 @@ pub(super) fn print_ask_table(output: &AskOutput) {
     }
 }
 
+/// Markdown for pasting into chat tools: citations as links, one fenced block
+/// per evidence item, and a delimited context section with every snippet.
+pub(super) fn render_ask_markdown(output: &AskOutput) -> String {
+    let mut md = format!("## Question\n\n{}\n\n", output.question);
+    md.push_str(&format!(
+        "## Answer\n\n{}\n\nconfidence: {:.2} (mode: {})\n\n",
+        output.answer, output.confidence, output.mode
+    ));
+    md.push_str("## Citations\n\n");
+    if output.citations.is_empty() {
+        md.push_str("- (none)\n");
+    }
+    for c in &output.citations {
+        md.push_str(&format!(
+            "- [{}: {}]({})\n",
+            c.id,
+            c.title,
+            c.path.replace(' ', "%20")
+        ));
+    }
+    md.push_str("\n## Evidence\n\n");
+    if output.evidence.is_empty() {
+        md.push_str("(none)\n\n");
+    }
+    for e in &output.evidence {
+        let fence = markdown_fence(&e.snippet);
+        md.push_str(&format!(
+            "{fence}text id={} score={:.4}\n{}\n{fence}\n\n",
+            e.id, e.score, e.snippet
+        ));
+    }
+    if !output.gaps.is_empty() {
+        md.push_str("## Gaps\n\n");
+        for gap in &output.gaps {
+            md.push_str(&format!("- {gap}\n"));
+        }
+        md.push('\n');
+    }
+    if output
+        .citations
+        .iter()
+        .any(|c| c.body.is_some() || c.body_unavailable)
+    {
+        md.push_str("## Cited bodies\n\n");
+        for c in &output.citations {
+            match (&c.body, c.body_unavailable) {
+                (Some(body), _) => {
+                    let fence = markdown_fence(body);
+                    let note = if c.body_truncated { " (truncated)" } else { "" };
+                    md.push_str(&format!(
+                        "### {}: {}{note}\n\n{fence}markdown\n{}\n{fence}\n\n",
+                        c.id,
+                        c.title,
+                        body.trim_end()
+                    ));
+                }
+                (None, true) => {
+                    md.push_str(&format!(
+                        "### {}: {}\n\n(body unavailable)\n\n",
+                        c.id, c.title
+                    ));
+                }
+                (None, false) => {}
+            }
+        }
+    }
+    md.push_str("## Context for answer\n\n<!-- BEGIN CONTEXT -->\n");
+    for e in &output.evidence {
+        md.push_str(&format!("[{}] {}\n\n", e.id, e.snippet));
+    }
+    md.push_str("<!-- END CONTEXT -->\n");
+    md
+}
+
+/// A backtick fence longer than any backtick run inside `text`.
+fn markdown_fence(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
 pub(super) fn build_ask_explanations(
     question: &str,
     hits: &[SearchHit],
@@ -271,7 +413,7 @@ fn edge_reasons_to_primary(
     if let Some(meta) = meta_by_id.get(candidate_id) {
         for edge in &meta.edges {
             if primary_ids.contains(&edge.to) {
-                let weight = edge_weight(edge.edge_type.as_str(), weights);
+                let weight = edge_contribution(edge, weights);
                 let label = format!(
                     "{} -> {} ({},w={:.2})",
                     candidate_id, edge.to, edge.edge_type, weight
@@ -287,7 +429,7 @@ fn edge_reasons_to_primary(
         if let Some(primary) = meta_by_id.get(primary_id) {
             for edge in &primary.edges {
                 if edge.to == candidate_id {
-                    let weight = edge_weight(edge.edge_type.as_str(), weights) * 0.9;
+                    let weight = edge_contribution(edge, weights) * 0.9;
                     let label = format!(
                         "{} -> {} ({},w={:.2})",
                         primary_id, candidate_id, edge.edge_type, weight
@@ -325,3 +467,78 @@ fn markdown_head_snippet(path: &str, max_len: usize) -> String {
         Err(_) => "(snippet unavailable)".to_string(),
     }
 }
+
+/// Reads each citation's markdown into `body`, in citation order, until the
+/// bodies add up to `max_chars`; the body crossing the cap is shortened and
+/// later ones are left out. Unreadable files are flagged instead of failing.
+/// Returns a gap note when anything was cut.
+fn attach_citation_bodies(citations: &mut [AskCitation], max_chars: usize) -> Option<String> {
+    let mut remaining = max_chars;
+    let mut truncated = 0usize;
+    let mut omitted = 0usize;
+    for citation in citations.iter_mut() {
+        let Ok(body) = fs::read_to_string(&citation.path) else {
+            citation.body_unavailable = true;
+            continue;
+        };
+        let len = body.chars().count();
+        if len <= remaining {
+            remaining -= len;
+            citation.body = Some(body);
+        } else if remaining > 0 {
+            citation.body = Some(body.chars().take(remaining).collect());
+            citation.body_truncated = true;
+            remaining = 0;
+            truncated += 1;
+        } else {
+            omitted += 1;
+        }
+    }
+    if truncated == 0 && omitted == 0 {
+        return None;
+    }
+    Some(format!(
+        "Citation bodies truncated to fit max_context_chars={max_chars}: {truncated} body(ies) shortened, {omitted} omitted."
+    ))
+}
+
+/// Caps the summed evidence snippet length at `max_chars`, keeping
+/// higher-scored evidence intact and shortening or dropping the rest.
+/// Returns a gap note when anything was cut.
+fn apply_evidence_char_budget(
+    evidence: &mut Vec<AskEvidence>,
+    max_chars: usize,
+) -> Option<String> {
+    let mut order = (0..evidence.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| evidence[*b].score.total_cmp(&evidence[*a].score).then(a.cmp(b)));
+
+    let mut remaining = max_chars;
+    let mut truncated = 0usize;
+    let mut dropped = HashSet::new();
+    for idx in order {
+        let snippet = &mut evidence[idx].snippet;
+        let len = snippet.chars().count();
+        if len <= remaining {
+            remaining -= len;
+        } else if remaining > 0 {
+            *snippet = snippet.chars().take(remaining).collect();
+            remaining = 0;
+            truncated += 1;
+        } else {
+            dropped.insert(idx);
+        }
+    }
+    if truncated == 0 && dropped.is_empty() {
+        return None;
+    }
+    let mut idx = 0;
+    evidence.retain(|_| {
+        let keep = !dropped.contains(&idx);
+        idx += 1;
+        keep
+    });
+    Some(format!(
+        "Evidence truncated to fit max_context_chars={max_chars}: {truncated} snippet(s) shortened, {} dropped.",
+        dropped.len()
+    ))
+}
@@ -0,0 +1,44 @@
+use super::*;
+use std::io::Write;
+
+const AUDIT_LOG_PATH: &str = ".foundry/audit.jsonl";
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp: i64,
+    command: &'a str,
+    action: &'a str,
+    ids: &'a [String],
+}
+
+/// Appends one line to `.foundry/audit.jsonl` when `audit.enabled` is set.
+/// The command has already succeeded, so a failed append is only logged.
+pub(super) fn record_audit(command: &str, action: &str, ids: &[String]) {
+    if !load_runtime_config().audit.enabled {
+        return;
+    }
+    let entry = AuditEntry {
+        timestamp: unix_ts(),
+        command,
+        action,
+        ids,
+    };
+    if let Err(err) = append_audit_entry(&entry) {
+        log::warn!("audit log not written: {err:#}");
+    }
+}
+
+fn append_audit_entry(entry: &AuditEntry<'_>) -> Result<()> {
+    let path = Path::new(AUDIT_LOG_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed creating directory: {}", parent.display()))?;
+    }
+    let line = serde_json::to_string(entry)? + "\n";
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("failed appending to {}", path.display()))
+}
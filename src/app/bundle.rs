@@ -0,0 +1,138 @@
+use super::*;
+use std::path::Component;
+
+const BUNDLE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SpecBundle {
+    schema_version: u32,
+    generated_at: i64,
+    nodes: Vec<BundleNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleNode {
+    meta: SpecNodeMeta,
+    body: String,
+}
+
+pub(super) fn run_export(export: ExportCommand) -> Result<()> {
+    match export.command {
+        ExportSubcommand::Bundle(args) => export_bundle(&args),
+    }
+}
+
+pub(super) fn run_import(import: ImportCommand) -> Result<()> {
+    match import.command {
+        ImportSubcommand::Bundle(args) => import_bundle(&args),
+    }
+}
+
+fn export_bundle(args: &ExportBundleArgs) -> Result<()> {
+    let mut lint = LintState::default();
+    let metas = load_all_meta(Path::new("spec"), &mut lint)?;
+    if let Some(err) = lint.findings.first().map(|f| &f.message) {
+        anyhow::bail!("cannot export bundle: {err}");
+    }
+    let mut nodes = metas
+        .into_iter()
+        .map(|(_, meta)| {
+            let body = fs::read_to_string(&meta.body_md_path).with_context(|| {
+                format!("failed reading {} (id={})", meta.body_md_path, meta.id)
+            })?;
+            Ok(BundleNode { meta, body })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    nodes.sort_by(|a, b| a.meta.id.cmp(&b.meta.id));
+
+    let bundle = SpecBundle {
+        schema_version: BUNDLE_SCHEMA_VERSION,
+        generated_at: unix_ts(),
+        nodes,
+    };
+    let text = serde_json::to_string_pretty(&bundle)?;
+    fs::write(&args.out, text + "\n")
+        .with_context(|| format!("failed writing bundle: {}", args.out))?;
+    println!("export bundle: nodes={} out={}", bundle.nodes.len(), args.out);
+    Ok(())
+}
+
+fn import_bundle(args: &ImportBundleArgs) -> Result<()> {
+    let raw = fs::read_to_string(&args.input)
+        .with_context(|| format!("failed reading bundle: {}", args.input))?;
+    let bundle: SpecBundle = serde_json::from_str(&raw)
+        .with_context(|| format!("invalid bundle: {}", args.input))?;
+    if bundle.schema_version != BUNDLE_SCHEMA_VERSION {
+        anyhow::bail!(
+            "unsupported bundle schema_version {} (expected {BUNDLE_SCHEMA_VERSION})",
+            bundle.schema_version
+        );
+    }
+
+    // Resolve and check every target before writing anything.
+    let mut targets = Vec::with_capacity(bundle.nodes.len());
+    let mut seen = HashSet::new();
+    for node in &bundle.nodes {
+        let md_path = PathBuf::from(&node.meta.body_md_path);
+        validate_bundle_path(&md_path)
+            .with_context(|| format!("invalid body_md_path for {}", node.meta.id))?;
+        if !seen.insert(node.meta.id.clone()) {
+            anyhow::bail!("duplicate node id in bundle: {}", node.meta.id);
+        }
+        let meta_path = md_to_meta_path(&md_path)?;
+        targets.push((md_path, meta_path));
+    }
+    if !args.force {
+        let existing = targets
+            .iter()
+            .flat_map(|(md, meta)| [md, meta])
+            .filter(|p| p.exists())
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>();
+        if !existing.is_empty() {
+            anyhow::bail!(
+                "refusing to overwrite {} existing file(s) (first: {}); use --force",
+                existing.len(),
+                existing[0]
+            );
+        }
+    }
+
+    for (node, (md_path, meta_path)) in bundle.nodes.iter().zip(&targets) {
+        if let Some(parent) = md_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed creating {}", parent.display()))?;
+        }
+        fs::write(md_path, &node.body)
+            .with_context(|| format!("failed writing {}", md_path.display()))?;
+        write_meta_json(meta_path, &node.meta)?;
+    }
+    let ids = bundle
+        .nodes
+        .iter()
+        .map(|node| node.meta.id.clone())
+        .collect::<Vec<_>>();
+    record_audit("import bundle", "import", &ids);
+    println!("import bundle: nodes={} in={}", bundle.nodes.len(), args.input);
+    Ok(())
+}
+
+fn validate_bundle_path(md_path: &Path) -> Result<()> {
+    let root = md_path
+        .components()
+        .next()
+        .and_then(|c| c.as_os_str().to_str());
+    if root != Some("spec") && root != Some("tasks") {
+        anyhow::bail!("path must be under spec/ or tasks/: {}", md_path.display());
+    }
+    if md_path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        anyhow::bail!("path must be relative without `..`: {}", md_path.display());
+    }
+    if md_path.extension().and_then(|e| e.to_str()) != Some("md") {
+        anyhow::bail!("path must end with .md: {}", md_path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use super::*;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+pub(super) fn run_completions(args: &CompletionsArgs) -> Result<()> {
+    let shell = match args.shell {
+        Some(CompletionShell::Bash) => Shell::Bash,
+        Some(CompletionShell::Zsh) => Shell::Zsh,
+        Some(CompletionShell::Fish) => Shell::Fish,
+        Some(CompletionShell::Powershell) => Shell::PowerShell,
+        None => Shell::from_env()
+            .context("cannot detect shell from $SHELL; pass bash, zsh, fish or powershell")?,
+    };
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+    Ok(())
+}
@@ -1,26 +1,36 @@
 use super::*;
+use foundry::WalkOptions;
+use std::io::Write;
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+static WALK_OPTIONS: OnceLock<WalkOptions> = OnceLock::new();
+
+/// Fixes how this process walks `spec/`/`tasks/` (from the global
+/// `--no-ignore` flag); only the first call takes effect.
+pub(super) fn set_walk_options(options: WalkOptions) {
+    let _ = WALK_OPTIONS.set(options);
+}
+
+fn walk_options() -> WalkOptions {
+    WALK_OPTIONS.get().copied().unwrap_or_default()
+}
+
+pub(super) fn walk_doc_files(spec_root: &Path) -> Vec<PathBuf> {
+    foundry::walk_doc_files(spec_root, walk_options())
+}
+
 pub(super) fn load_existing_ids(spec_root: &Path) -> Result<HashSet<String>> {
     let mut ids = HashSet::new();
-    for root in doc_roots(spec_root) {
-        if !root.exists() {
+    for path in walk_doc_files(spec_root) {
+        if !is_meta_json(&path) {
             continue;
         }
-        for entry in WalkDir::new(&root)
-            .into_iter()
-            .filter_map(std::result::Result::ok)
-        {
-            let path = entry.path();
-            if !is_meta_json(path) {
-                continue;
-            }
-            let raw = fs::read_to_string(path)
-                .with_context(|| format!("failed to read meta file: {}", path.display()))?;
-            let meta: SpecNodeMeta = serde_json::from_str(&raw)
-                .with_context(|| format!("invalid meta file: {}", path.display()))?;
-            ids.insert(meta.id);
-        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read meta file: {}", path.display()))?;
+        let meta: SpecNodeMeta = serde_json::from_str(&raw)
+            .with_context(|| format!("invalid meta file: {}", path.display()))?;
+        ids.insert(meta.id);
     }
     Ok(ids)
 }
@@ -29,115 +39,127 @@ pub(super) fn load_all_meta(
     spec_root: &Path,
     lint: &mut LintState,
 ) -> Result<Vec<(PathBuf, SpecNodeMeta)>> {
-    let mut metas = Vec::new();
-    for root in doc_roots(spec_root) {
-        if !root.exists() {
-            continue;
-        }
-        for entry in WalkDir::new(&root)
-            .into_iter()
-            .filter_map(std::result::Result::ok)
-        {
-            let path = entry.path();
-            if !is_meta_json(path) {
-                continue;
-            }
-            let raw = match fs::read_to_string(path) {
-                Ok(v) => v,
-                Err(err) => {
-                    lint.errors
-                        .push(format!("cannot read {}: {err}", path.display()));
-                    continue;
-                }
-            };
-            match serde_json::from_str::<SpecNodeMeta>(&raw) {
-                Ok(meta) => metas.push((path.to_path_buf(), meta)),
-                Err(err) => lint
-                    .errors
-                    .push(format!("invalid json {}: {err}", path.display())),
-            }
-        }
+    let mut errors = Vec::new();
+    let metas = foundry::load_all_meta(spec_root, walk_options(), &mut errors);
+    for err in errors {
+        lint.push_load_error(err);
     }
     Ok(metas)
 }
 
+pub(super) fn find_meta_files(spec_root: &Path) -> Vec<PathBuf> {
+    let mut files = walk_doc_files(spec_root)
+        .into_iter()
+        .filter(|path| is_meta_json(path))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
 pub(super) fn find_markdown_files(spec_root: &Path) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
-    for root in doc_roots(spec_root) {
-        if !root.exists() {
-            continue;
-        }
-        for entry in WalkDir::new(&root)
-            .into_iter()
-            .filter_map(std::result::Result::ok)
-        {
-            if entry.file_type().is_file() {
-                let path = entry.path();
-                if path.extension().is_some_and(|ext| ext == "md")
-                    && !path
-                        .file_name()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("")
-                        .ends_with(".meta.md")
-                {
-                    files.push(path.to_path_buf());
-                }
-            }
-        }
-    }
+    let mut files = walk_doc_files(spec_root)
+        .into_iter()
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "md")
+                && !path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .ends_with(".meta.md")
+        })
+        .collect::<Vec<_>>();
     files.sort();
     Ok(files)
 }
 
 pub(super) fn validate_meta_semantics(path: &Path, meta: &SpecNodeMeta, lint: &mut LintState) {
     if !is_valid_node_id(&meta.id) {
-        lint.errors.push(format!(
-            "invalid node id format in {}: {}",
-            path.display(),
-            meta.id
-        ));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/id",
+            format!("invalid node id format in {}: {}", path.display(), meta.id),
+        );
     }
     if meta.title.trim().is_empty() {
-        lint.errors
-            .push(format!("empty title in {} (id={})", path.display(), meta.id));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/title",
+            format!("empty title in {} (id={})", path.display(), meta.id),
+        );
     }
     if meta.body_md_path.trim().is_empty() {
-        lint.errors.push(format!(
-            "empty body_md_path in {} (id={})",
-            path.display(),
-            meta.id
-        ));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/body_md_path",
+            format!("empty body_md_path in {} (id={})", path.display(), meta.id),
+        );
     } else if !is_valid_doc_body_path(&meta.body_md_path) {
-        lint.errors.push(format!(
-            "invalid body_md_path format in {} (id={}): {}",
-            path.display(),
-            meta.id,
-            meta.body_md_path
-        ));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/body_md_path",
+            format!(
+                "invalid body_md_path format in {} (id={}): {}",
+                path.display(),
+                meta.id,
+                meta.body_md_path
+            ),
+        );
     }
     if !NODE_TYPES.contains(&meta.node_type.as_str()) {
-        lint.errors.push(format!(
-            "invalid node type in {} (id={}): {}",
-            path.display(),
-            meta.id,
-            meta.node_type
-        ));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/type",
+            format!(
+                "invalid node type in {} (id={}): {}",
+                path.display(),
+                meta.id,
+                meta.node_type
+            ),
+        );
     }
     if !NODE_STATUSES.contains(&meta.status.as_str()) {
-        lint.errors.push(format!(
-            "invalid node status in {} (id={}): {}",
-            path.display(),
-            meta.id,
-            meta.status
-        ));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/status",
+            format!(
+                "invalid node status in {} (id={}): {}",
+                path.display(),
+                meta.id,
+                meta.status
+            ),
+        );
+    }
+    if !meta.priority.is_empty() && !NODE_PRIORITIES.contains(&meta.priority.as_str()) {
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/priority",
+            format!(
+                "invalid node priority in {} (id={}): {}",
+                path.display(),
+                meta.id,
+                meta.priority
+            ),
+        );
     }
     if !is_valid_sha256(&meta.hash) {
-        lint.errors.push(format!(
-            "invalid hash format in {} (id={}): {}",
-            path.display(),
-            meta.id,
-            meta.hash
-        ));
+        lint.push_at(
+            "invalid-field",
+            path,
+            "/hash",
+            format!(
+                "invalid hash format in {} (id={}): {}",
+                path.display(),
+                meta.id,
+                meta.hash
+            ),
+        );
     }
 }
 
@@ -166,6 +188,88 @@ pub(super) fn tokenize(text: &str) -> HashSet<String> {
         .collect()
 }
 
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been",
+    "before", "but", "by", "can", "could", "did", "do", "does", "for", "from", "had", "has",
+    "have", "how", "i", "if", "in", "into", "is", "it", "its", "may", "more", "must", "no", "not",
+    "of", "on", "or", "other", "our", "should", "so", "some", "such", "than", "that", "the",
+    "their", "them", "then", "there", "these", "they", "this", "those", "to", "was", "we", "were",
+    "what", "when", "where", "which", "while", "who", "why", "will", "with", "would", "you",
+    "your",
+];
+
+pub(super) fn is_stopword(token: &str) -> bool {
+    ENGLISH_STOPWORDS.contains(&token)
+}
+
+/// Tokens used for ranking signals (boosts, matched terms, local embeddings).
+/// FTS matching keeps the raw tokens; only this side is filtered and stemmed.
+pub(super) fn ranking_tokens(text: &str, config: &SearchRuntimeConfig) -> HashSet<String> {
+    tokenize(text)
+        .into_iter()
+        .filter(|t| !(config.stopwords && is_stopword(t)))
+        .map(|t| ranking_key(&t, config))
+        .collect()
+}
+
+pub(super) fn ranking_key(token: &str, config: &SearchRuntimeConfig) -> String {
+    if config.stemming {
+        stem_token(token)
+    } else {
+        token.to_string()
+    }
+}
+
+/// Light Porter-style suffix stripping (plural, -ed/-ing, trailing y) so that
+/// `logs`, `logged` and `logging` share a key with `log`.
+pub(super) fn stem_token(token: &str) -> String {
+    if !token.is_ascii() || token.len() <= 3 {
+        return token.to_string();
+    }
+    let mut stem = token.to_string();
+    if let Some(base) = stem.strip_suffix("sses") {
+        stem = format!("{base}ss");
+    } else if let Some(base) = stem.strip_suffix("ies") {
+        stem = format!("{base}i");
+    } else if !stem.ends_with("ss")
+        && let Some(base) = stem.strip_suffix('s')
+        && base.len() >= 3
+    {
+        stem = base.to_string();
+    }
+
+    for suffix in ["ing", "ed"] {
+        if let Some(base) = stem.strip_suffix(suffix)
+            && base.len() >= 3
+            && base.chars().any(is_vowel)
+        {
+            stem = base.to_string();
+            let bytes = stem.as_bytes();
+            let n = bytes.len();
+            if n >= 2
+                && bytes[n - 1] == bytes[n - 2]
+                && !matches!(bytes[n - 1], b'l' | b's' | b'z')
+                && !is_vowel(bytes[n - 1] as char)
+            {
+                stem.pop();
+            }
+            break;
+        }
+    }
+
+    if let Some(base) = stem.strip_suffix('y')
+        && base.len() >= 2
+        && base.chars().any(is_vowel)
+    {
+        stem = format!("{base}i");
+    }
+    stem
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
 pub(super) fn score_to_confidence(score: usize) -> f64 {
     match score {
         0 => 0.0,
@@ -177,14 +281,6 @@ pub(super) fn score_to_confidence(score: usize) -> f64 {
     }
 }
 
-pub(super) fn is_meta_json(path: &Path) -> bool {
-    path.is_file()
-        && path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .is_some_and(|name| name.ends_with(".meta.json"))
-}
-
 pub(super) fn md_to_meta_path(md_path: &Path) -> Result<PathBuf> {
     let file_name = md_path
         .file_name()
@@ -196,10 +292,50 @@ pub(super) fn md_to_meta_path(md_path: &Path) -> Result<PathBuf> {
     Ok(md_path.with_file_name(format!("{base}.meta.json")))
 }
 
+/// Writes the meta file through a sibling temp file that is synced and then
+/// renamed over `path`, so an interrupted write or a full disk leaves either
+/// the old file or the new one, never a truncated mix. Edges are written
+/// sorted by `to`, then type, so the file does not depend on the order in
+/// which commands added them.
 pub(super) fn write_meta_json(path: &Path, meta: &SpecNodeMeta) -> Result<()> {
-    let text = serde_json::to_string_pretty(meta)?;
-    fs::write(path, text + "\n")
-        .with_context(|| format!("failed writing meta file: {}", path.display()))?;
+    let text = if meta.edges.is_sorted_by(|a, b| edge_order(a, b).is_le()) {
+        serde_json::to_string_pretty(meta)?
+    } else {
+        let mut sorted = meta.clone();
+        sorted.edges.sort_by(edge_order);
+        serde_json::to_string_pretty(&sorted)?
+    } + "\n";
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("invalid meta path: {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    let written = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(text.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp_path, path));
+    if let Err(err) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err).with_context(|| format!("failed writing meta file: {}", path.display()));
+    }
+    Ok(())
+}
+
+fn edge_order(a: &SpecEdge, b: &SpecEdge) -> std::cmp::Ordering {
+    a.to.cmp(&b.to).then_with(|| a.edge_type.cmp(&b.edge_type))
+}
+
+/// Copies an existing meta file to `<name>.meta.json.bak` before it is
+/// rewritten; a missing file has nothing to back up.
+pub(super) fn backup_meta_json(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    fs::copy(path, &backup)
+        .with_context(|| format!("failed backing up meta file: {}", path.display()))?;
     Ok(())
 }
 
@@ -207,15 +343,146 @@ pub(super) fn normalize_path(path: &Path) -> PathBuf {
     PathBuf::from(path.to_string_lossy().replace('\\', "/"))
 }
 
+/// Makes the project root the working directory so `spec/`, `tasks/`, and
+/// every `body_md_path` resolve the same from anywhere in the repository.
+/// The root is `root` when given, else (with `discover`) the nearest ancestor
+/// of the current directory that has a `spec/` or `tasks/` directory; the
+/// climb stops at the first directory holding `.git` or `.foundry`, so an
+/// unrelated parent tree is never picked up. Returns whether the working
+/// directory changed.
+pub(super) fn enter_project_root(root: Option<&Path>, discover: bool) -> Result<bool> {
+    let has_project_dirs = |dir: &Path| dir.join("spec").is_dir() || dir.join("tasks").is_dir();
+    let is_boundary = |dir: &Path| dir.join(".git").exists() || dir.join(".foundry").is_dir();
+    let target = match root {
+        Some(root) => {
+            if !root.is_dir() {
+                anyhow::bail!("--root is not a directory: {}", root.display());
+            }
+            root.to_path_buf()
+        }
+        None if discover => {
+            let cwd = std::env::current_dir().context("cannot read current directory")?;
+            let mut found = None;
+            for dir in cwd.ancestors() {
+                if has_project_dirs(dir) {
+                    found = Some(dir);
+                    break;
+                }
+                if is_boundary(dir) {
+                    break;
+                }
+            }
+            match found {
+                Some(found) if found != cwd => found.to_path_buf(),
+                _ => return Ok(false),
+            }
+        }
+        None => return Ok(false),
+    };
+    std::env::set_current_dir(&target)
+        .with_context(|| format!("cannot enter project root {}", target.display()))?;
+    Ok(true)
+}
+
+/// Makes the file arguments a user types (`--body-file`, `--bulk`, `--db`,
+/// ...) absolute against `base`, the directory the command was run from, so
+/// they keep pointing at the same files after `enter_project_root`. Spec
+/// paths such as `write --path` stay relative to the project root.
+pub(super) fn resolve_user_path_args(command: &mut SpecSubcommand, base: &Path) {
+    let resolve = |path: &mut String| {
+        if Path::new(path.as_str()).is_relative() {
+            *path = base.join(path.as_str()).to_string_lossy().into_owned();
+        }
+    };
+    // `--agent-dir <name>:<path>`: only the path part is a file argument.
+    let resolve_agent_dir = |value: &mut String| {
+        if let Some((name, dir)) = value.split_once(':')
+            && !dir.trim().is_empty()
+            && Path::new(dir.trim()).is_relative()
+        {
+            *value = format!("{name}:{}", base.join(dir.trim()).display());
+        }
+    };
+    match command {
+        SpecSubcommand::Init(args) => {
+            args.agent_dirs.iter_mut().for_each(resolve_agent_dir);
+            args.codex_home.iter_mut().for_each(resolve);
+            args.claude_dir.iter_mut().for_each(resolve);
+            args.gemini_home.iter_mut().for_each(resolve);
+        }
+        SpecSubcommand::Agent(agent) => match &mut agent.command {
+            AgentSubcommand::List(args) => {
+                args.codex_home.iter_mut().for_each(resolve);
+                args.claude_dir.iter_mut().for_each(resolve);
+                args.gemini_home.iter_mut().for_each(resolve);
+            }
+            AgentSubcommand::Doctor(args) => {
+                args.agent_dirs.iter_mut().for_each(resolve_agent_dir);
+                args.codex_home.iter_mut().for_each(resolve);
+                args.claude_dir.iter_mut().for_each(resolve);
+                args.gemini_home.iter_mut().for_each(resolve);
+            }
+        },
+        SpecSubcommand::Write(args) => args.body_file.iter_mut().for_each(resolve),
+        SpecSubcommand::Derive(derive) => match &mut derive.command {
+            DeriveSubcommand::Design(args) => args.body_file.iter_mut().for_each(resolve),
+            DeriveSubcommand::Tasks(args) => args.body_file.iter_mut().for_each(resolve),
+            DeriveSubcommand::Test(args) => args.body_file.iter_mut().for_each(resolve),
+        },
+        SpecSubcommand::Link(link) => {
+            if let LinkSubcommand::Add(args) = &mut link.command {
+                args.bulk.iter_mut().for_each(resolve);
+            }
+        }
+        SpecSubcommand::Search(search) => search.db.iter_mut().for_each(resolve),
+        SpecSubcommand::Ask(args) => {
+            args.questions_file.iter_mut().for_each(resolve);
+            args.db.iter_mut().for_each(resolve);
+        }
+        SpecSubcommand::Doctor(args) => {
+            args.db.iter_mut().for_each(resolve);
+            args.agent_dirs.iter_mut().for_each(resolve_agent_dir);
+            args.codex_home.iter_mut().for_each(resolve);
+            args.claude_dir.iter_mut().for_each(resolve);
+            args.gemini_home.iter_mut().for_each(resolve);
+        }
+        #[cfg(feature = "serve")]
+        SpecSubcommand::Serve(args) => args.db.iter_mut().for_each(resolve),
+        SpecSubcommand::Validate(validate) => match &mut validate.command {
+            ValidateSubcommand::Schema(args) => args.paths.iter_mut().for_each(resolve),
+        },
+        SpecSubcommand::Export(export) => match &mut export.command {
+            ExportSubcommand::Bundle(args) => resolve(&mut args.out),
+        },
+        SpecSubcommand::Import(import) => match &mut import.command {
+            ImportSubcommand::Bundle(args) => resolve(&mut args.input),
+        },
+        _ => {}
+    }
+}
+
 pub(super) fn extract_title(body: &str, path: &Path) -> String {
-    for line in body.lines() {
-        let line = line.trim();
-        if let Some(value) = line.strip_prefix("# ") {
+    let lines = split_front_matter(body)
+        .1
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    for (idx, line) in lines.iter().enumerate() {
+        if let Some(value) = line.strip_prefix('#')
+            && value.starts_with(char::is_whitespace)
+        {
             let v = value.trim();
             if !v.is_empty() {
                 return v.to_string();
             }
         }
+        if !line.is_empty()
+            && !is_atx_heading(line)
+            && let Some(next) = lines.get(idx + 1)
+            && is_setext_h1_underline(next)
+        {
+            return line.to_string();
+        }
     }
     path.file_stem()
         .and_then(|s| s.to_str())
@@ -223,6 +490,37 @@ pub(super) fn extract_title(body: &str, path: &Path) -> String {
         .to_string()
 }
 
+/// Splits a leading YAML front-matter block (`---` ... `---`, delimiters and
+/// trailing newline included) from the rest of a markdown body.
+pub(super) fn split_front_matter(body: &str) -> (Option<&str>, &str) {
+    let Some(after_open) = body
+        .strip_prefix("---\n")
+        .or_else(|| body.strip_prefix("---\r\n"))
+    else {
+        return (None, body);
+    };
+    let mut offset = body.len() - after_open.len();
+    for line in after_open.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == "---" {
+            return (Some(&body[..offset]), &body[offset..]);
+        }
+    }
+    (None, body)
+}
+
+fn is_setext_h1_underline(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| c == '=')
+}
+
+/// `#` to `######` followed by whitespace or nothing; such a line is already a
+/// heading and cannot be the text of a setext one.
+fn is_atx_heading(line: &str) -> bool {
+    let rest = line.trim_start_matches('#');
+    let level = line.len() - rest.len();
+    (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
 pub(super) fn sha256_hex(bytes: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(bytes);
@@ -240,6 +538,31 @@ pub(super) fn next_available_id(existing: &HashSet<String>) -> usize {
         + 1
 }
 
+pub(super) fn lowest_free_id(existing: &HashSet<String>) -> usize {
+    let taken = existing
+        .iter()
+        .filter_map(|id| id.strip_prefix("SPC-"))
+        .filter_map(|v| v.parse::<usize>().ok())
+        .collect::<HashSet<_>>();
+    (1..).find(|n| !taken.contains(n)).expect("unbounded range")
+}
+
+/// Picks the next unused id for `mode` and reserves it in `used_ids`, so
+/// repeated calls within one run never hand out the same id.
+pub(super) fn allocate_id(used_ids: &mut HashSet<String>, mode: IdAllocation) -> String {
+    let mut n = match mode {
+        IdAllocation::Append => next_available_id(used_ids),
+        IdAllocation::Dense => lowest_free_id(used_ids),
+    };
+    loop {
+        let candidate = format!("SPC-{n:03}");
+        if used_ids.insert(candidate.clone()) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
 pub(super) fn unix_ts() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -247,14 +570,82 @@ pub(super) fn unix_ts() -> i64 {
         .unwrap_or(0)
 }
 
-fn doc_roots(spec_root: &Path) -> Vec<PathBuf> {
-    let spec = normalize_path(spec_root);
-    let parent = spec
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_default();
-    let tasks = normalize_path(&parent.join("tasks"));
-    vec![spec, tasks]
+/// Parses unix seconds or an RFC 3339 timestamp (`2024-05-01T12:00:00Z`,
+/// optional fractional seconds, `Z` or `+HH:MM`/`-HH:MM` offset) into unix
+/// seconds; fractions are truncated.
+pub(super) fn parse_timestamp(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if let Ok(secs) = raw.parse::<i64>() {
+        return Some(secs);
+    }
+    let (date, time) = raw.split_once(['T', 't', ' '])?;
+    let mut date_parts = date.split('-');
+    let year = parse_fixed_digits(date_parts.next()?, 4)?;
+    let month = parse_fixed_digits(date_parts.next()?, 2)?;
+    let day = parse_fixed_digits(date_parts.next()?, 2)?;
+    if date_parts.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=month_days).contains(&day) {
+        return None;
+    }
+
+    let clock = time.get(..8)?;
+    let hour = parse_fixed_digits(clock.get(..2)?, 2)?;
+    let minute = parse_fixed_digits(clock.get(3..5)?, 2)?;
+    let second = parse_fixed_digits(clock.get(6..)?, 2)?;
+    if &clock[2..3] != ":" || &clock[5..6] != ":" || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let mut zone = &time[8..];
+    if let Some(fraction) = zone.strip_prefix('.') {
+        let digits = fraction.find(|c: char| !c.is_ascii_digit())?;
+        if digits == 0 {
+            return None;
+        }
+        zone = &fraction[digits..];
+    }
+    let offset = match zone {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match zone.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let (offset_hour, offset_minute) = zone[1..].split_once(':')?;
+            let offset_hour = parse_fixed_digits(offset_hour, 2)?;
+            let offset_minute = parse_fixed_digits(offset_minute, 2)?;
+            if offset_hour > 23 || offset_minute > 59 {
+                return None;
+            }
+            sign * (offset_hour * 3600 + offset_minute * 60)
+        }
+    };
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar, counting
+    // years from March so the leap day falls at the end.
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+fn parse_fixed_digits(raw: &str, len: usize) -> Option<i64> {
+    if raw.len() != len || !raw.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    raw.parse().ok()
 }
 
 fn is_valid_doc_body_path(path: &str) -> bool {
@@ -0,0 +1,152 @@
+use super::*;
+
+/// Node types reported when `--type` is not given.
+const DEFAULT_COVERAGE_TYPES: &[&str] = &["feature_requirement", "api_contract"];
+
+/// Statuses that no longer need tests; such nodes are left out of the report.
+const RETIRED_STATUSES: &[&str] = &["deprecated", "archived"];
+
+#[derive(Debug, Serialize)]
+struct CoverageOutput {
+    node_types: Vec<String>,
+    total: usize,
+    covered: usize,
+    coverage_percent: f64,
+    by_type: Vec<CoverageByType>,
+    nodes: Vec<CoverageNode>,
+}
+
+#[derive(Debug, Serialize)]
+struct CoverageByType {
+    node_type: String,
+    total: usize,
+    covered: usize,
+    coverage_percent: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct CoverageNode {
+    id: String,
+    node_type: String,
+    title: String,
+    covered: bool,
+    /// `test_spec` nodes with a confirmed `tests` edge to this node.
+    tested_by: Vec<String>,
+}
+
+pub(super) fn run_coverage(args: &CoverageArgs) -> Result<()> {
+    for node_type in &args.node_types {
+        if !NODE_TYPES.contains(&node_type.as_str()) {
+            anyhow::bail!("invalid --type filter: {node_type}");
+        }
+    }
+    let node_types = if args.node_types.is_empty() {
+        DEFAULT_COVERAGE_TYPES
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    } else {
+        args.node_types.clone()
+    };
+
+    let metas = load_all_meta(Path::new("spec"), &mut LintState::default())?;
+    // Incoming confirmed `tests` edges from test_spec nodes, keyed by target.
+    let mut tested_by = HashMap::<&str, BTreeSet<&str>>::new();
+    for (_, meta) in &metas {
+        if meta.node_type != "test_spec" {
+            continue;
+        }
+        for edge in &meta.edges {
+            if edge.edge_type == "tests" && edge.status == "confirmed" {
+                tested_by
+                    .entry(edge.to.as_str())
+                    .or_default()
+                    .insert(meta.id.as_str());
+            }
+        }
+    }
+
+    let mut nodes = metas
+        .iter()
+        .map(|(_, meta)| meta)
+        .filter(|meta| node_types.contains(&meta.node_type))
+        .filter(|meta| !RETIRED_STATUSES.contains(&meta.status.as_str()))
+        .map(|meta| {
+            let tests = tested_by
+                .get(meta.id.as_str())
+                .map(|ids| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            CoverageNode {
+                id: meta.id.clone(),
+                node_type: meta.node_type.clone(),
+                title: meta.title.clone(),
+                covered: !tests.is_empty(),
+                tested_by: tests,
+            }
+        })
+        .collect::<Vec<_>>();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let by_type = node_types
+        .iter()
+        .map(|node_type| {
+            let of_type = nodes.iter().filter(|n| &n.node_type == node_type);
+            let total = of_type.clone().count();
+            let covered = of_type.filter(|n| n.covered).count();
+            CoverageByType {
+                node_type: node_type.clone(),
+                total,
+                covered,
+                coverage_percent: coverage_percent(covered, total),
+            }
+        })
+        .collect();
+    let total = nodes.len();
+    let covered = nodes.iter().filter(|n| n.covered).count();
+    let output = CoverageOutput {
+        node_types,
+        total,
+        covered,
+        coverage_percent: coverage_percent(covered, total),
+        by_type,
+        nodes,
+    };
+    match args.format {
+        CoverageFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        CoverageFormat::Table => print_coverage_table(&output),
+    }
+    Ok(())
+}
+
+/// Share of covered nodes, rounded to one decimal; `100.0` when there is
+/// nothing to cover.
+fn coverage_percent(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    (covered as f64 * 1000.0 / total as f64).round() / 10.0
+}
+
+fn print_coverage_table(output: &CoverageOutput) {
+    for node in &output.nodes {
+        let state = if node.covered {
+            format!("covered by {}", node.tested_by.join(","))
+        } else {
+            "uncovered".to_string()
+        };
+        println!(
+            "coverage: {} [{}] {state} | {}",
+            node.id, node.node_type, node.title
+        );
+    }
+    for by_type in &output.by_type {
+        println!(
+            "coverage: {}: {}/{} ({:.1}%)",
+            by_type.node_type, by_type.covered, by_type.total, by_type.coverage_percent
+        );
+    }
+    println!(
+        "coverage summary: covered={} total={} percent={:.1}",
+        output.covered, output.total, output.coverage_percent
+    );
+}
@@ -4,6 +4,7 @@ pub(super) fn run_derive(args: DeriveCommand) -> Result<()> {
     match args.command {
         DeriveSubcommand::Design(design) => run_derive_design(&design),
         DeriveSubcommand::Tasks(tasks) => run_derive_tasks(&tasks),
+        DeriveSubcommand::Test(test) => run_derive_test(&test),
     }
 }
 
@@ -75,10 +76,16 @@ fn run_derive_design(args: &DeriveDesignArgs) -> Result<()> {
         id: None,
         node_type: Some(args.node_type.clone()),
         status: Some(args.status.clone()),
+        priority: None,
         title: Some(title),
         body: Some(body),
         body_file: None,
         terms: args.terms.clone(),
+        auto_terms: false,
+        append: false,
+        front_matter: Vec::new(),
+        backup: false,
+        format: WriteFormat::Table,
     };
     let design_id = super::write::run_write_silent(&write_args)?;
     if design_id == args.from {
@@ -108,6 +115,103 @@ fn run_derive_design(args: &DeriveDesignArgs) -> Result<()> {
             status: "confirmed".to_string(),
         }],
     };
+    record_audit(
+        "derive design",
+        "derive",
+        &[design_id.clone(), args.from.clone()],
+    );
+    print_design_output(&output, args.format)?;
+    Ok(())
+}
+
+fn run_derive_test(args: &DeriveTestArgs) -> Result<()> {
+    let spec_root = Path::new("spec");
+    if !spec_root.exists() {
+        anyhow::bail!("spec/ directory not found");
+    }
+    let mut lint = LintState::default();
+    let metas = load_all_meta(spec_root, &mut lint)?;
+    let by_id: HashMap<String, SpecNodeMeta> =
+        metas.into_iter().map(|(_, m)| (m.id.clone(), m)).collect();
+    let from_meta = by_id
+        .get(&args.from)
+        .with_context(|| format!("source node not found: {}", args.from))?;
+
+    if args.body.is_some() && args.body_file.is_some() {
+        anyhow::bail!("--body and --body-file cannot be used together");
+    }
+
+    let path = args
+        .path
+        .clone()
+        .unwrap_or_else(|| default_test_path(&args.from));
+    let title = args
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Tests for {}", from_meta.title));
+    let body = match (&args.body, &args.body_file) {
+        (Some(body), _) => body.clone(),
+        (None, Some(body_file)) => fs::read_to_string(body_file)
+            .with_context(|| format!("failed reading --body-file: {body_file}"))?,
+        (None, None) => default_test_body(from_meta, &title),
+    };
+
+    let write_args = WriteArgs {
+        path: Some(path),
+        id: None,
+        node_type: Some("test_spec".to_string()),
+        status: Some(args.status.clone()),
+        priority: None,
+        title: Some(title),
+        body: Some(body),
+        body_file: None,
+        terms: args.terms.clone(),
+        auto_terms: false,
+        append: false,
+        front_matter: Vec::new(),
+        backup: false,
+        format: WriteFormat::Table,
+    };
+    let test_id = super::write::run_write_silent(&write_args)?;
+    if test_id == args.from {
+        anyhow::bail!("derived node id is identical to source id: {}", args.from);
+    }
+
+    let mut lint = LintState::default();
+    let metas = load_all_meta(spec_root, &mut lint)?;
+    let mut by_id = to_meta_map(metas);
+    let (path, test_meta) = by_id
+        .get_mut(&test_id)
+        .with_context(|| format!("derived node not found after write: {test_id}"))?;
+    upsert_edge(
+        test_meta,
+        &args.from,
+        "tests",
+        &args.rationale,
+        1.0,
+        "confirmed",
+    );
+    let test_md_path = test_meta.body_md_path.clone();
+    write_meta_json(path, test_meta)?;
+    let output = DeriveDesignOutput {
+        mode: "test",
+        source: args.from.clone(),
+        derived: DerivedNodeOutput {
+            id: test_id.clone(),
+            path: test_md_path,
+        },
+        edges: vec![DeriveEdgeOutput {
+            from: test_id.clone(),
+            to: args.from.clone(),
+            edge_type: "tests".to_string(),
+            status: "confirmed".to_string(),
+        }],
+    };
+    record_audit(
+        "derive test",
+        "derive",
+        &[test_id.clone(), args.from.clone()],
+    );
     print_design_output(&output, args.format)?;
     Ok(())
 }
@@ -229,6 +333,9 @@ fn run_derive_tasks(args: &DeriveTasksArgs) -> Result<()> {
         });
         write_meta_json(path, task_meta)?;
     }
+    let mut audit_ids = task_ids.clone();
+    audit_ids.push(args.from.clone());
+    record_audit("derive tasks", "derive", &audit_ids);
     let output = DeriveTasksOutput {
         mode: "tasks",
         source: args.from.clone(),
@@ -246,10 +353,16 @@ fn write_task_node(args: &DeriveTasksArgs, path: String, title: String, body: St
         id: None,
         node_type: Some(args.node_type.clone()),
         status: Some(args.status.clone()),
+        priority: None,
         title: Some(title),
         body: Some(body),
         body_file: None,
         terms: args.terms.clone(),
+        auto_terms: false,
+        append: false,
+        front_matter: Vec::new(),
+        backup: false,
+        format: WriteFormat::Table,
     };
     super::write::run_write_silent(&write_args)
 }
@@ -274,6 +387,8 @@ fn upsert_edge(
         edge.rationale = rationale.to_string();
         edge.confidence = confidence;
         edge.status = status.to_string();
+        edge.updated_at = Some(unix_ts());
+        edge.source = Some(EDGE_SOURCE_DERIVE.to_string());
         return;
     }
     meta.edges.push(SpecEdge {
@@ -282,6 +397,9 @@ fn upsert_edge(
         rationale: rationale.to_string(),
         confidence,
         status: status.to_string(),
+        created_at: Some(unix_ts()),
+        updated_at: Some(unix_ts()),
+        source: Some(EDGE_SOURCE_DERIVE.to_string()),
     });
 }
 
@@ -296,6 +414,17 @@ fn default_design_body(source: &SpecNodeMeta, title: &str) -> String {
     )
 }
 
+fn default_test_path(from_id: &str) -> String {
+    format!("spec/test-{}.md", from_id.to_ascii_lowercase())
+}
+
+fn default_test_body(source: &SpecNodeMeta, title: &str) -> String {
+    format!(
+        "# {title}\n\n## Scope\n- Tests: {}\n- Out of scope:\n\n## Test Cases\n1. Given:\n   When:\n   Then:\n\n## Edge Cases\n-\n\n## Fixtures\n- Data:\n- Environment:\n\n## Verification\n- Commands:\n- Expected results:\n",
+        source.id
+    )
+}
+
 fn default_task_path(from_id: &str) -> String {
     format!("tasks/{}/task.md", from_id.to_ascii_lowercase())
 }
@@ -337,8 +466,8 @@ fn print_design_output(output: &DeriveDesignOutput, format: DeriveFormat) -> Res
     match format {
         DeriveFormat::Json => println!("{}", serde_json::to_string_pretty(output)?),
         DeriveFormat::Table => println!(
-            "spec derive design: source={} derived={} edge=refines",
-            output.source, output.derived.id
+            "spec derive {}: source={} derived={} edge={}",
+            output.mode, output.source, output.derived.id, output.edges[0].edge_type
         ),
     }
     Ok(())
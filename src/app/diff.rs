@@ -0,0 +1,102 @@
+use super::*;
+
+#[derive(Debug, Serialize)]
+struct NodeDrift {
+    id: String,
+    path: String,
+    state: String,
+    stored_hash: String,
+    current_hash: Option<String>,
+    stored_title: String,
+    current_title: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffOutput {
+    ok: bool,
+    checked: usize,
+    stale_count: usize,
+    nodes: Vec<NodeDrift>,
+}
+
+pub(super) fn run_diff(args: &DiffArgs) -> Result<i32> {
+    let spec_root = Path::new("spec");
+    let mut metas = load_all_meta(spec_root, &mut LintState::default())?
+        .into_iter()
+        .map(|(_, meta)| meta)
+        .collect::<Vec<_>>();
+    if let Some(node_id) = &args.node_id {
+        metas.retain(|m| &m.id == node_id);
+        if metas.is_empty() {
+            anyhow::bail!("node not found: {node_id}");
+        }
+    }
+    metas.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let nodes = metas.iter().map(node_drift).collect::<Vec<_>>();
+    let stale_count = nodes.iter().filter(|n| n.state != "ok").count();
+    let output = DiffOutput {
+        ok: stale_count == 0,
+        checked: nodes.len(),
+        stale_count,
+        nodes,
+    };
+    match args.format {
+        DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        DiffFormat::Table => print_diff_table(&output),
+    }
+    if output.ok {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
+
+fn node_drift(meta: &SpecNodeMeta) -> NodeDrift {
+    let body = fs::read_to_string(&meta.body_md_path).ok();
+    let current_hash = body.as_ref().map(|b| sha256_hex(b.as_bytes()));
+    let current_title = body
+        .as_ref()
+        .map(|b| extract_title(b, Path::new(&meta.body_md_path)));
+    let state = match &current_hash {
+        None => "missing",
+        Some(hash) if *hash == meta.hash => "ok",
+        Some(_) => "stale",
+    };
+    NodeDrift {
+        id: meta.id.clone(),
+        path: meta.body_md_path.clone(),
+        state: state.to_string(),
+        stored_hash: meta.hash.clone(),
+        current_hash,
+        stored_title: meta.title.clone(),
+        current_title,
+    }
+}
+
+fn print_diff_table(output: &DiffOutput) {
+    if output.ok {
+        println!("spec diff: ok (checked={})", output.checked);
+        return;
+    }
+    for node in output.nodes.iter().filter(|n| n.state != "ok") {
+        println!("{} {} {}", node.id, node.state, node.path);
+        let Some(current_hash) = &node.current_hash else {
+            println!("  markdown file not found");
+            continue;
+        };
+        println!("  hash: {} -> {}", node.stored_hash, current_hash);
+        if let Some(current_title) = &node.current_title
+            && *current_title != node.stored_title
+        {
+            println!("  --- title (meta)");
+            println!("  +++ title (markdown)");
+            println!("  -{}", node.stored_title);
+            println!("  +{current_title}");
+        }
+    }
+    println!(
+        "spec diff summary: checked={} stale={}",
+        output.checked, output.stale_count
+    );
+}
@@ -0,0 +1,139 @@
+use super::*;
+
+#[derive(Debug, Serialize)]
+struct DoctorOutput {
+    ok: bool,
+    lint: DoctorCheck,
+    search: DoctorCheck,
+    agent: DoctorCheck,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorCheck {
+    ok: bool,
+    skipped: bool,
+    issue_count: usize,
+    issues: Vec<String>,
+}
+
+impl DoctorCheck {
+    fn skipped() -> Self {
+        Self {
+            ok: true,
+            skipped: true,
+            issue_count: 0,
+            issues: Vec::new(),
+        }
+    }
+
+    fn from_issues(ok: bool, issues: Vec<String>) -> Self {
+        Self {
+            ok,
+            skipped: false,
+            issue_count: issues.len(),
+            issues,
+        }
+    }
+
+    /// A sub-check that could not run at all (unreadable meta, broken index)
+    /// fails with the error as its only issue instead of aborting the rest.
+    fn from_result(result: Result<Self>) -> Self {
+        result.unwrap_or_else(|err| Self::from_issues(false, vec![format!("{err:#}")]))
+    }
+}
+
+pub(super) fn run_doctor(args: &DoctorArgs) -> Result<i32> {
+    let enabled = |kind: DoctorCheckKind| !args.skip.contains(&kind);
+
+    let lint = if enabled(DoctorCheckKind::Lint) {
+        DoctorCheck::from_result(check_lint())
+    } else {
+        DoctorCheck::skipped()
+    };
+    let search = if enabled(DoctorCheckKind::Search) {
+        DoctorCheck::from_result(
+            collect_search_doctor_issues(args.db.as_deref().map(Path::new))
+                .map(|issues| DoctorCheck::from_issues(issues.is_empty(), issues)),
+        )
+    } else {
+        DoctorCheck::skipped()
+    };
+    let agent = if enabled(DoctorCheckKind::Agent) {
+        let config = super::agent::TemplateConfig {
+            source: args.template_source,
+            repo: args.template_repo.clone(),
+            git_ref: args.template_ref.clone(),
+            output: args.agent_output,
+            codex_home: args.codex_home.clone(),
+            claude_dir: args.claude_dir.clone(),
+            gemini_home: args.gemini_home.clone(),
+            agent_dirs: super::agent::parse_agent_dirs(&args.agent_dirs)?,
+        };
+        let report = super::agent::check_agent_templates(&args.agent, &config);
+        DoctorCheck::from_issues(
+            report.ok,
+            report
+                .issues
+                .iter()
+                .map(super::agent::AgentDoctorIssue::describe)
+                .collect(),
+        )
+    } else {
+        DoctorCheck::skipped()
+    };
+
+    let output = DoctorOutput {
+        ok: lint.ok && search.ok && agent.ok,
+        lint,
+        search,
+        agent,
+    };
+    match args.format {
+        DoctorFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        DoctorFormat::Table => print_doctor_table(&output),
+    }
+    Ok(if output.ok { 0 } else { 1 })
+}
+
+/// Lint findings as issues; only errors fail the check, matching `spec lint`.
+fn check_lint() -> Result<DoctorCheck> {
+    let spec_root = Path::new("spec");
+    if !spec_root.exists() && !Path::new("tasks").exists() {
+        return Ok(DoctorCheck::from_issues(true, Vec::new()));
+    }
+    let findings = collect_lint_findings(spec_root, None)?;
+    let ok = findings.iter().all(|f| f.severity != LintSeverity::Error);
+    let issues = findings
+        .iter()
+        .map(|f| format!("{}: {}", f.severity.label(), f.message))
+        .collect();
+    Ok(DoctorCheck::from_issues(ok, issues))
+}
+
+fn print_doctor_table(output: &DoctorOutput) {
+    let mut failed = Vec::new();
+    for (name, check) in [
+        ("lint", &output.lint),
+        ("search", &output.search),
+        ("agent", &output.agent),
+    ] {
+        if check.skipped {
+            println!("doctor: {name}: skipped");
+            continue;
+        }
+        for issue in &check.issues {
+            println!("doctor: {name}: issue: {issue}");
+        }
+        if check.ok {
+            println!("doctor: {name}: ok");
+        } else {
+            println!("doctor: {name}: failed ({} issue(s))", check.issue_count);
+            failed.push(name);
+        }
+    }
+    if failed.is_empty() {
+        println!("doctor summary: ok");
+    } else {
+        println!("doctor summary: failed={}", failed.join(","));
+    }
+}
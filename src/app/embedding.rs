@@ -0,0 +1,142 @@
+use super::*;
+
+pub(super) const LOCAL_EMBEDDING_MODEL: &str = "local-hash-ngrams-v1";
+
+/// Produces chunk and query vectors for the configured provider. An HTTP
+/// provider that fails once is dropped for the rest of the run and the local
+/// hash-ngram embedding is used instead.
+pub(super) struct Embedder {
+    search: SearchRuntimeConfig,
+    buckets: SemanticBucketCache,
+    #[cfg(feature = "http-embeddings")]
+    http: Option<HttpEmbedder>,
+}
+
+impl Embedder {
+    pub(super) fn from_config(config: &RuntimeConfig) -> Self {
+        let provider = config.embedding.provider.as_str();
+        match provider {
+            "local" => {}
+            "http" if cfg!(feature = "http-embeddings") => {}
+            "http" => log::warn!(
+                "embedding provider `http` requires the `http-embeddings` feature; using local embeddings"
+            ),
+            other => log::warn!("unknown embedding provider `{other}`; using local embeddings"),
+        }
+        Self {
+            search: config.search.clone(),
+            buckets: SemanticBucketCache::default(),
+            #[cfg(feature = "http-embeddings")]
+            http: (provider == "http")
+                .then(|| HttpEmbedder::from_config(&config.embedding))
+                .and_then(|r| match r {
+                    Ok(http) => Some(http),
+                    Err(err) => {
+                        log::warn!("{err:#}; using local embeddings");
+                        None
+                    }
+                }),
+        }
+    }
+
+    /// Local embeddings use `dim` instead of `search.embedding_dim`, e.g. the
+    /// size an existing index was built with.
+    pub(super) fn with_local_dim(mut self, dim: usize) -> Self {
+        self.search.embedding_dim = dim;
+        self
+    }
+
+    /// Returns the model name recorded in `chunk_vectors.model` with the vector.
+    pub(super) fn embed(&mut self, text: &str) -> (String, Vec<f64>) {
+        #[cfg(feature = "http-embeddings")]
+        if let Some(http) = &self.http {
+            match http.embed(text) {
+                Ok(vector) => return (http.model.clone(), vector),
+                Err(err) => {
+                    log::warn!("http embedding failed: {err:#}; falling back to local embeddings");
+                    self.http = None;
+                }
+            }
+        }
+        (
+            LOCAL_EMBEDDING_MODEL.to_string(),
+            semantic_vector(text, &self.search, &mut self.buckets),
+        )
+    }
+}
+
+#[cfg(feature = "http-embeddings")]
+struct HttpEmbedder {
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "http-embeddings")]
+impl HttpEmbedder {
+    fn from_config(config: &EmbeddingRuntimeConfig) -> Result<Self> {
+        let url = config
+            .url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .context("embedding.url is required for the http provider")?;
+        let model = config
+            .model
+            .clone()
+            .filter(|m| !m.trim().is_empty())
+            .context("embedding.model is required for the http provider")?;
+        let url = url.trim_end_matches('/');
+        let endpoint = if url.ends_with("/embeddings") {
+            url.to_string()
+        } else {
+            format!("{url}/embeddings")
+        };
+        let api_key = config
+            .api_key_env
+            .as_deref()
+            .and_then(|name| std::env::var(name).ok())
+            .filter(|key| !key.is_empty());
+        let client = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .context("failed to build embedding http client")?;
+        Ok(Self {
+            endpoint,
+            model,
+            api_key,
+            client,
+        })
+    }
+
+    fn embed(&self, text: &str) -> Result<Vec<f64>> {
+        let body = serde_json::json!({ "model": self.model, "input": text });
+        let mut request = self
+            .client
+            .post(&self.endpoint)
+            .header("content-type", "application/json")
+            .body(serde_json::to_vec(&body)?);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .with_context(|| format!("request to {} failed", self.endpoint))?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("{} returned {status}", self.endpoint);
+        }
+        let payload: serde_json::Value =
+            serde_json::from_slice(&response.bytes()?).context("invalid embeddings response")?;
+        let vector = payload["data"][0]["embedding"]
+            .as_array()
+            .context("embeddings response missing data[0].embedding")?
+            .iter()
+            .map(|v| v.as_f64().context("non-numeric embedding value"))
+            .collect::<Result<Vec<_>>>()?;
+        if vector.is_empty() {
+            anyhow::bail!("embeddings response returned an empty vector");
+        }
+        Ok(vector)
+    }
+}
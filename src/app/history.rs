@@ -0,0 +1,159 @@
+use super::*;
+
+#[derive(Debug, Serialize)]
+struct HistoryOutput {
+    node: String,
+    meta_path: String,
+    md_path: String,
+    /// `false` outside a git work tree; `commits` is then empty.
+    git: bool,
+    commits: Vec<HistoryCommit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<HistoryDiff>,
+}
+
+/// Patch of the newest commit that touched the meta file.
+#[derive(Debug, Serialize)]
+struct HistoryDiff {
+    commit: String,
+    patch: String,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryCommit {
+    commit: String,
+    date: String,
+    subject: String,
+    /// Which of the node's files the commit touched: `meta`, `markdown`.
+    files: Vec<String>,
+    #[serde(skip)]
+    timestamp: i64,
+}
+
+pub(super) fn run_history(args: &HistoryArgs) -> Result<()> {
+    let spec_root = Path::new("spec");
+    let metas = load_all_meta(spec_root, &mut LintState::default())?;
+    let meta = metas
+        .into_iter()
+        .map(|(_, meta)| meta)
+        .find(|meta| meta.id == args.node_id)
+        .with_context(|| format!("node not found: {}", args.node_id))?;
+    let md_path = meta.body_md_path.clone();
+    let meta_path = normalize_path(&md_to_meta_path(Path::new(&md_path))?)
+        .to_string_lossy()
+        .to_string();
+
+    let in_repo = git_output(&["rev-parse", "--is-inside-work-tree"]).is_ok();
+    let mut output = HistoryOutput {
+        node: meta.id,
+        meta_path,
+        md_path,
+        git: in_repo,
+        commits: Vec::new(),
+        diff: None,
+    };
+    if in_repo {
+        for (label, path) in [("meta", &output.meta_path), ("markdown", &output.md_path)] {
+            for commit in git_log_follow(path)? {
+                match output
+                    .commits
+                    .iter_mut()
+                    .find(|c| c.commit == commit.commit)
+                {
+                    Some(seen) => seen.files.push(label.to_string()),
+                    None => output.commits.push(HistoryCommit {
+                        files: vec![label.to_string()],
+                        ..commit
+                    }),
+                }
+            }
+        }
+        output.commits.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
+        let latest_meta = output
+            .commits
+            .iter()
+            .find(|c| c.files.iter().any(|f| f == "meta"));
+        if args.diff
+            && let Some(latest) = latest_meta
+        {
+            output.diff = Some(HistoryDiff {
+                commit: latest.commit.clone(),
+                patch: git_output(&["show", "--format=", &latest.commit, "--", &output.meta_path])?,
+            });
+        }
+    } else {
+        log::warn!(
+            "not inside a git repository; no history for {}",
+            output.node
+        );
+    }
+
+    match args.format {
+        HistoryFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        HistoryFormat::Table => print_history_table(&output),
+    }
+    Ok(())
+}
+
+/// Commits touching `path` across renames, newest first.
+fn git_log_follow(path: &str) -> Result<Vec<HistoryCommit>> {
+    let raw = git_output(&[
+        "log",
+        "--follow",
+        "--date=short",
+        "--format=%h%x09%ct%x09%ad%x09%s",
+        "--",
+        path,
+    ])?;
+    Ok(raw
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\t');
+            Some(HistoryCommit {
+                commit: parts.next()?.to_string(),
+                timestamp: parts.next()?.parse().ok()?,
+                date: parts.next()?.to_string(),
+                subject: parts.next().unwrap_or_default().to_string(),
+                files: Vec::new(),
+            })
+        })
+        .collect())
+}
+
+fn git_output(args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn print_history_table(output: &HistoryOutput) {
+    println!(
+        "history for {} ({}, {}):",
+        output.node, output.meta_path, output.md_path
+    );
+    if output.commits.is_empty() {
+        println!("  (none)");
+    }
+    for c in &output.commits {
+        println!(
+            "  - {} {} [{}] {}",
+            c.commit,
+            c.date,
+            c.files.join(","),
+            c.subject
+        );
+    }
+    if let Some(diff) = &output.diff {
+        println!("meta diff ({}):", diff.commit);
+        print!("{}", diff.patch);
+    }
+}
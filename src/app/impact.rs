@@ -1,206 +1,186 @@
 use super::*;
-use std::collections::VecDeque;
-
-#[derive(Debug, Serialize)]
-struct DirectDependency {
-    to: String,
-    edge_type: String,
-    status: String,
-    confidence: f64,
-    rationale: String,
-}
+use foundry::{DEFAULT_REVERSE_EDGE_TYPES, DirectDependency, ImpactReport, TraversalDirection};
 
+/// `spec impact` output: the graph report plus the review effort estimate.
 #[derive(Debug, Serialize)]
 struct ImpactOutput {
-    node_id: String,
-    depth: usize,
-    direct_dependencies: Vec<DirectDependency>,
-    reverse_dependents: Vec<String>,
-    test_coverage_chain: Vec<String>,
-    conflict_risks: Vec<String>,
-    recommended_review_order: Vec<String>,
+    #[serde(flatten)]
+    report: ImpactReport,
+    /// Sum of [`task_estimate`] over `recommended_review_order`, i.e. the
+    /// node count unless nodes carry an `estimate:<n>` term.
+    effort: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget: Option<f64>,
+    over_budget: bool,
 }
 
-pub(super) fn run_impact(args: &ImpactArgs) -> Result<()> {
+pub(super) fn run_impact(args: &ImpactArgs) -> Result<i32> {
+    if args.budget.is_some_and(|budget| !budget.is_finite() || budget < 0.0) {
+        anyhow::bail!("--budget must be a non-negative number");
+    }
     let node_id = args.node_id.as_str();
     let spec_root = Path::new("spec");
     let metas = load_all_meta(spec_root, &mut LintState::default())?;
-    let mut by_id = HashMap::<String, SpecNodeMeta>::new();
-    for (_, meta) in metas {
-        by_id.insert(meta.id.clone(), meta);
+    let graph = SpecGraph::from_nodes(metas.into_iter().map(|(_, meta)| meta));
+    let filter = EdgeFilter {
+        min_confidence: args.min_confidence,
+        confirmed_only: args.confirmed_only,
+    };
+    let mut reverse_types = DEFAULT_REVERSE_EDGE_TYPES.to_vec();
+    for edge_type in &args.reverse_via {
+        if !EDGE_TYPES.contains(&edge_type.as_str()) {
+            anyhow::bail!("invalid edge type for --reverse-via: {edge_type}");
+        }
+        if !reverse_types.contains(&edge_type.as_str()) {
+            reverse_types.push(edge_type);
+        }
     }
-    if !by_id.contains_key(node_id) {
-        anyhow::bail!("node not found: {node_id}");
+    let direction = match args.direction {
+        ImpactDirection::Forward => TraversalDirection::Forward,
+        ImpactDirection::Reverse => TraversalDirection::Reverse,
+        ImpactDirection::Both => TraversalDirection::Both,
+    };
+    let mut report = graph
+        .impact_directed(node_id, args.depth, &filter, &reverse_types, direction)
+        .with_context(|| format!("node not found: {node_id}"))?;
+    if args.conflicts_first {
+        report.prioritize_conflicts();
     }
-
-    let node = by_id.get(node_id).expect("node existence checked");
-    let mut direct_dependencies: Vec<DirectDependency> = node
-        .edges
+    let by_id = graph.nodes();
+    let effort = report
+        .recommended_review_order
         .iter()
-        .filter(|e| e.edge_type == "depends_on" || e.edge_type == "impacts")
-        .map(|e| DirectDependency {
-            to: e.to.clone(),
-            edge_type: e.edge_type.clone(),
-            status: e.status.clone(),
-            confidence: e.confidence,
-            rationale: e.rationale.clone(),
-        })
-        .collect();
-    direct_dependencies.sort_by(|a, b| a.to.cmp(&b.to).then(a.edge_type.cmp(&b.edge_type)));
-
-    let reverse_dependents = reverse_dependents(node_id, args.depth, &by_id);
-
-    let test_coverage_chain = test_coverage_chain(node_id, args.depth, &by_id);
-
-    let mut conflicts = BTreeSet::<String>::new();
-    for e in &node.edges {
-        if e.edge_type == "conflicts_with" {
-            conflicts.insert(e.to.clone());
-        }
+        .filter_map(|id| by_id.get(id))
+        .map(task_estimate)
+        .sum::<f64>();
+    let over_budget = args.budget.is_some_and(|budget| effort > budget);
+    if let Some(budget) = args.budget
+        && over_budget
+    {
+        log::warn!("impact: estimated effort {effort} exceeds --budget {budget}");
     }
-    for (id, m) in &by_id {
-        if m.edges
-            .iter()
-            .any(|e| e.to == node_id && e.edge_type == "conflicts_with")
-        {
-            conflicts.insert(id.clone());
-        }
-    }
-
-    let review_order = bfs_review_order(node_id, args.depth, &by_id);
     let output = ImpactOutput {
-        node_id: node_id.to_string(),
-        depth: args.depth,
-        direct_dependencies,
-        reverse_dependents,
-        test_coverage_chain,
-        conflict_risks: conflicts.into_iter().collect(),
-        recommended_review_order: review_order,
+        report,
+        effort,
+        budget: args.budget,
+        over_budget,
     };
+    let exit_code = if output.over_budget { 1 } else { 0 };
 
     if args.format == ImpactFormat::Json {
         println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return Ok(exit_code);
     }
+    let paths = if args.link_paths { Some(by_id) } else { None };
+    if args.format == ImpactFormat::Markdown {
+        print!("{}", render_impact_markdown(&output, by_id, paths));
+        return Ok(exit_code);
+    }
+
+    print_impact_table(&output, paths);
+    Ok(exit_code)
+}
 
+fn print_impact_table(impact: &ImpactOutput, paths: Option<&HashMap<String, SpecNodeMeta>>) {
+    let output = &impact.report;
     println!("direct_dependencies:");
-    print_direct_dependencies(&output.direct_dependencies);
+    print_direct_dependencies(&output.direct_dependencies, paths);
     println!("reverse_dependents:");
-    print_string_list(&output.reverse_dependents);
+    print_node_list(&output.reverse_dependents, paths);
     println!("test_coverage_chain:");
-    print_string_list(&output.test_coverage_chain);
+    print_node_list(&output.test_coverage_chain, paths);
     println!("conflict_risks:");
-    print_string_list(&output.conflict_risks);
+    print_node_list(&output.conflict_risks, paths);
     println!("recommended_review_order:");
-    print_string_list(&output.recommended_review_order);
-    Ok(())
+    print_node_list(&output.recommended_review_order, paths);
+    println!("{}", effort_line(impact.effort, impact.budget));
 }
 
-fn reverse_dependents(seed: &str, max_depth: usize, by_id: &HashMap<String, SpecNodeMeta>) -> Vec<String> {
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-    let mut out = BTreeSet::new();
-
-    queue.push_back((seed.to_string(), 0usize));
-    visited.insert(seed.to_string());
-
-    while let Some((current, depth)) = queue.pop_front() {
-        if depth >= max_depth {
-            continue;
-        }
-        for (id, m) in by_id {
-            let connected = m
-                .edges
-                .iter()
-                .any(|e| e.to == current && e.edge_type == "depends_on");
-            if connected && visited.insert(id.clone()) {
-                out.insert(id.clone());
-                queue.push_back((id.clone(), depth + 1));
-            }
-        }
+fn effort_line(effort: f64, budget: Option<f64>) -> String {
+    match budget {
+        Some(budget) if effort > budget => format!("effort: {effort} (budget {budget} exceeded)"),
+        Some(budget) => format!("effort: {effort} (budget {budget})"),
+        None => format!("effort: {effort}"),
     }
-
-    out.into_iter().collect()
 }
 
-fn test_coverage_chain(seed: &str, max_depth: usize, by_id: &HashMap<String, SpecNodeMeta>) -> Vec<String> {
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-    let mut out = BTreeSet::new();
-
-    queue.push_back((seed.to_string(), 0usize));
-    visited.insert(seed.to_string());
-
-    while let Some((current, depth)) = queue.pop_front() {
-        if depth >= max_depth {
-            continue;
-        }
-        if let Some(meta) = by_id.get(&current) {
-            for edge in &meta.edges {
-                if edge.edge_type == "tests" && visited.insert(edge.to.clone()) {
-                    out.insert(edge.to.clone());
-                    queue.push_back((edge.to.clone(), depth + 1));
-                }
-            }
-        }
-        for (id, m) in by_id {
-            let connected = m.edges.iter().any(|e| e.to == current && e.edge_type == "tests");
-            if connected && visited.insert(id.clone()) {
-                out.insert(id.clone());
-                queue.push_back((id.clone(), depth + 1));
-            }
-        }
+/// `id`, or `id -> body_md_path` under `--link-paths` so IDE terminals can
+/// open the node; ids missing from the graph stay bare.
+fn node_ref(id: &str, paths: Option<&HashMap<String, SpecNodeMeta>>) -> String {
+    match paths.and_then(|by_id| by_id.get(id)) {
+        Some(meta) => format!("{id} -> {}", meta.body_md_path),
+        None => id.to_string(),
     }
-
-    out.into_iter().collect()
 }
 
-pub(super) fn bfs_review_order(
-    seed: &str,
-    max_depth: usize,
+fn render_impact_markdown(
+    impact: &ImpactOutput,
     by_id: &HashMap<String, SpecNodeMeta>,
-) -> Vec<String> {
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-    let mut out = Vec::new();
-
-    queue.push_back((seed.to_string(), 0usize));
-    visited.insert(seed.to_string());
-
-    while let Some((current, depth)) = queue.pop_front() {
-        out.push(current.clone());
-        if depth >= max_depth {
-            continue;
+    paths: Option<&HashMap<String, SpecNodeMeta>>,
+) -> String {
+    let output = &impact.report;
+    let link = |id: &str| match by_id.get(id) {
+        Some(meta) if paths.is_some() => {
+            format!("[{id}]({path}) -> {path}", path = meta.body_md_path)
         }
-
-        if let Some(meta) = by_id.get(&current) {
-            for edge in &meta.edges {
-                if (edge.edge_type == "depends_on"
-                    || edge.edge_type == "impacts"
-                    || edge.edge_type == "tests")
-                    && visited.insert(edge.to.clone())
-                {
-                    queue.push_back((edge.to.clone(), depth + 1));
-                }
-            }
+        Some(meta) => format!("[{id}]({})", meta.body_md_path),
+        None => id.to_string(),
+    };
+    let list = |out: &mut String, values: &[String], ordered: bool| {
+        if values.is_empty() {
+            out.push_str("_None_\n");
+            return;
         }
-        for (id, m) in by_id {
-            let connected = m.edges.iter().any(|e| {
-                e.to == current
-                    && (e.edge_type == "depends_on"
-                        || e.edge_type == "impacts"
-                        || e.edge_type == "tests")
-            });
-            if connected && visited.insert(id.clone()) {
-                queue.push_back((id.clone(), depth + 1));
+        for (idx, value) in values.iter().enumerate() {
+            if ordered {
+                out.push_str(&format!("{}. {}\n", idx + 1, link(value)));
+            } else {
+                out.push_str(&format!("- {}\n", link(value)));
             }
         }
+    };
+
+    let mut out = String::new();
+    out.push_str(&format!("# Impact Report: {}\n\n", link(&output.node_id)));
+    out.push_str(&format!("Traversal depth: {}\n\n", output.depth));
+    out.push_str(&format!(
+        "Estimated review {}\n\n",
+        effort_line(impact.effort, impact.budget)
+    ));
+
+    out.push_str("## Direct Dependencies\n\n");
+    if output.direct_dependencies.is_empty() {
+        out.push_str("_None_\n");
+    } else {
+        out.push_str("| To | Type | Status | Confidence | Rationale |\n");
+        out.push_str("| --- | --- | --- | --- | --- |\n");
+        for edge in &output.direct_dependencies {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                link(&edge.to),
+                edge.edge_type,
+                edge.status,
+                edge.confidence,
+                edge.rationale.replace('|', "\\|").replace('\n', " ")
+            ));
+        }
     }
 
+    out.push_str("\n## Reverse Dependents\n\n");
+    list(&mut out, &output.reverse_dependents, false);
+    out.push_str("\n## Test Coverage Chain\n\n");
+    list(&mut out, &output.test_coverage_chain, false);
+    out.push_str("\n## Conflict Risks\n\n");
+    list(&mut out, &output.conflict_risks, false);
+    out.push_str("\n## Recommended Review Order\n\n");
+    list(&mut out, &output.recommended_review_order, true);
     out
 }
 
-fn print_direct_dependencies(edges: &[DirectDependency]) {
+fn print_direct_dependencies(
+    edges: &[DirectDependency],
+    paths: Option<&HashMap<String, SpecNodeMeta>>,
+) {
     if edges.is_empty() {
         println!("  (none)");
         return;
@@ -208,17 +188,21 @@ fn print_direct_dependencies(edges: &[DirectDependency]) {
     for edge in edges {
         println!(
             "  - {} [{}] status={} confidence={} rationale={}",
-            edge.to, edge.edge_type, edge.status, edge.confidence, edge.rationale
+            node_ref(&edge.to, paths),
+            edge.edge_type,
+            edge.status,
+            edge.confidence,
+            edge.rationale
         );
     }
 }
 
-fn print_string_list(values: &[String]) {
-    if values.is_empty() {
+fn print_node_list(ids: &[String], paths: Option<&HashMap<String, SpecNodeMeta>>) {
+    if ids.is_empty() {
         println!("  (none)");
         return;
     }
-    for value in values {
-        println!("  - {value}");
+    for id in ids {
+        println!("  - {}", node_ref(id, paths));
     }
 }
@@ -1,16 +1,8 @@
 use super::*;
 
-pub(super) fn run_init(
-    sync: bool,
-    agents: &[AgentTarget],
-    agent_sync: bool,
-    agent_output: AgentOutput,
-    codex_home: Option<&str>,
-    claude_dir: Option<&str>,
-    template_source: TemplateSource,
-    template_repo: &str,
-    template_ref: &str,
-) -> Result<()> {
+pub(super) fn run_init(args: &InitArgs) -> Result<()> {
+    let sync = args.sync;
+    let json = args.format == InitFormat::Json;
     let spec_root = Path::new("spec");
     let tasks_root = Path::new("tasks");
     let mut summary = InitSummary::default();
@@ -18,124 +10,280 @@ pub(super) fn run_init(
     if spec_root.exists() || tasks_root.exists() {
         let md_files = find_markdown_files(spec_root)?;
         let mut used_ids = load_existing_ids(spec_root)?;
-        let mut next_id = next_available_id(&used_ids);
+        let id_allocation = load_runtime_config().id_allocation;
 
         for md_path in md_files {
-            let md_rel = normalize_path(&md_path);
-            let meta_path = md_to_meta_path(&md_path)?;
-            let body = match fs::read_to_string(&md_path) {
-                Ok(v) => v,
-                Err(err) => {
-                    summary.errors += 1;
-                    eprintln!("error reading {}: {err}", md_rel.display());
-                    continue;
-                }
-            };
-            let title = extract_title(&body, &md_path);
-            let hash = sha256_hex(body.as_bytes());
-
-            if meta_path.exists() {
-                let existing = fs::read_to_string(&meta_path)
-                    .with_context(|| format!("failed reading {}", meta_path.display()));
-                let mut meta: SpecNodeMeta = match existing
-                    .and_then(|s| serde_json::from_str(&s).context("invalid .meta.json"))
-                {
-                    Ok(m) => m,
-                    Err(err) => {
-                        summary.errors += 1;
-                        eprintln!("error parsing {}: {err:#}", meta_path.display());
-                        continue;
-                    }
-                };
-
-                let mut changed = false;
-                if meta.id.trim().is_empty() {
-                    meta.id = format!("SPC-{next_id:03}");
-                    used_ids.insert(meta.id.clone());
-                    next_id += 1;
-                    changed = true;
-                } else {
-                    used_ids.insert(meta.id.clone());
-                }
-                if meta.node_type.trim().is_empty() {
-                    meta.node_type = "feature_requirement".to_string();
-                    changed = true;
-                }
-                if meta.status.trim().is_empty() {
-                    meta.status = "draft".to_string();
-                    changed = true;
-                }
-                if meta.title.trim().is_empty() || sync {
-                    if meta.title != title {
-                        meta.title = title.clone();
-                        changed = true;
-                    }
-                }
-                if meta.body_md_path.trim().is_empty() || sync {
-                    let rel = md_rel.to_string_lossy().to_string();
-                    if meta.body_md_path != rel {
-                        meta.body_md_path = rel;
-                        changed = true;
-                    }
+            match sync_markdown_file(&md_path, sync, &mut used_ids, id_allocation)? {
+                InitOutcome::Created(id) => {
+                    summary.created += 1;
+                    summary.ids.push(id);
                 }
-                if meta.hash != hash {
-                    meta.hash = hash.clone();
-                    changed = true;
-                }
-
-                if changed {
-                    write_meta_json(&meta_path, &meta)?;
+                InitOutcome::Updated(id) => {
                     summary.updated += 1;
-                } else {
-                    summary.skipped += 1;
+                    summary.ids.push(id);
                 }
-            } else {
-                let id = loop {
-                    let candidate = format!("SPC-{next_id:03}");
-                    next_id += 1;
-                    if !used_ids.contains(&candidate) {
-                        used_ids.insert(candidate.clone());
-                        break candidate;
-                    }
-                };
-                let meta = SpecNodeMeta {
-                    id,
-                    node_type: "feature_requirement".to_string(),
-                    status: "draft".to_string(),
-                    title,
-                    body_md_path: md_rel.to_string_lossy().to_string(),
-                    terms: Vec::new(),
-                    hash,
-                    edges: Vec::new(),
-                };
-                write_meta_json(&meta_path, &meta)?;
-                summary.created += 1;
+                InitOutcome::Skipped => summary.skipped += 1,
+                InitOutcome::Failed => summary.errors += 1,
             }
         }
 
-        println!(
-            "init summary: created={} updated={} skipped={} errors={}",
-            summary.created, summary.updated, summary.skipped, summary.errors
-        );
+        if !summary.ids.is_empty() {
+            record_audit("init", "sync", &summary.ids);
+        }
+        if !json {
+            log::info!(
+                "init summary: created={} updated={} skipped={} errors={}",
+                summary.created,
+                summary.updated,
+                summary.skipped,
+                summary.errors
+            );
+        }
     } else {
-        println!("spec/ and tasks/ directories not found. skipping metadata initialization.");
+        log::warn!("spec/ and tasks/ directories not found; skipping metadata initialization");
     }
 
-    if !agents.is_empty() {
+    if !args.agent.is_empty() || !args.agent_dirs.is_empty() {
         let config = super::agent::TemplateConfig {
-            source: template_source,
-            repo: template_repo.to_string(),
-            git_ref: template_ref.to_string(),
-            output: agent_output,
-            codex_home: codex_home.map(ToOwned::to_owned),
-            claude_dir: claude_dir.map(ToOwned::to_owned),
+            source: args.template_source,
+            repo: args.template_repo.clone(),
+            git_ref: args.template_ref.clone(),
+            output: args.agent_output,
+            codex_home: args.codex_home.clone(),
+            claude_dir: args.claude_dir.clone(),
+            gemini_home: args.gemini_home.clone(),
+            agent_dirs: super::agent::parse_agent_dirs(&args.agent_dirs)?,
+        };
+        let agent_summary =
+            super::agent::generate_agent_templates(&args.agent, args.agent_sync, &config);
+        if !json {
+            log::info!(
+                "agent template summary: written={} skipped={} errors={}",
+                agent_summary.written,
+                agent_summary.skipped,
+                agent_summary.errors
+            );
+        }
+        summary.agent_templates = Some(agent_summary);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    }
+
+    if args.watch {
+        watch_markdown(spec_root, sync)?;
+    }
+    Ok(())
+}
+
+enum InitOutcome {
+    Created(String),
+    Updated(String),
+    Skipped,
+    Failed,
+}
+
+/// Creates or refreshes the meta file next to one markdown file.
+fn sync_markdown_file(
+    md_path: &Path,
+    sync: bool,
+    used_ids: &mut HashSet<String>,
+    id_allocation: IdAllocation,
+) -> Result<InitOutcome> {
+    let md_rel = normalize_path(md_path);
+    let meta_path = md_to_meta_path(md_path)?;
+    let body = match fs::read_to_string(md_path) {
+        Ok(v) => v,
+        Err(err) => {
+            log::error!("reading {}: {err}", md_rel.display());
+            return Ok(InitOutcome::Failed);
+        }
+    };
+    let title = extract_title(&body, md_path);
+    let hash = sha256_hex(body.as_bytes());
+
+    if meta_path.exists() {
+        let existing = fs::read_to_string(&meta_path)
+            .with_context(|| format!("failed reading {}", meta_path.display()));
+        let mut meta: SpecNodeMeta = match existing
+            .and_then(|s| serde_json::from_str(&s).context("invalid .meta.json"))
+        {
+            Ok(m) => m,
+            Err(err) => {
+                log::error!("parsing {}: {err:#}", meta_path.display());
+                return Ok(InitOutcome::Failed);
+            }
+        };
+
+        let mut changed = false;
+        if meta.id.trim().is_empty() {
+            meta.id = allocate_id(used_ids, id_allocation);
+            changed = true;
+        } else {
+            used_ids.insert(meta.id.clone());
+        }
+        if meta.node_type.trim().is_empty() {
+            meta.node_type = "feature_requirement".to_string();
+            changed = true;
+        }
+        if meta.status.trim().is_empty() {
+            meta.status = "draft".to_string();
+            changed = true;
+        }
+        if (meta.title.trim().is_empty() || sync) && meta.title != title {
+            meta.title = title.clone();
+            changed = true;
+        }
+        if meta.body_md_path.trim().is_empty() || sync {
+            let rel = md_rel.to_string_lossy().to_string();
+            if meta.body_md_path != rel {
+                meta.body_md_path = rel;
+                changed = true;
+            }
+        }
+        if meta.hash != hash {
+            meta.hash = hash.clone();
+            changed = true;
+        }
+
+        if changed {
+            write_meta_json(&meta_path, &meta)?;
+            Ok(InitOutcome::Updated(meta.id))
+        } else {
+            Ok(InitOutcome::Skipped)
+        }
+    } else {
+        let id = allocate_id(used_ids, id_allocation);
+        let meta = SpecNodeMeta {
+            id: id.clone(),
+            node_type: "feature_requirement".to_string(),
+            status: "draft".to_string(),
+            title,
+            body_md_path: md_rel.to_string_lossy().to_string(),
+            terms: Vec::new(),
+            hash,
+            edges: Vec::new(),
+            owner: String::new(),
+            priority: String::new(),
+            schema_version: META_SCHEMA_VERSION,
         };
-        let agent_summary = super::agent::generate_agent_templates(agents, agent_sync, &config);
-        println!(
-            "agent template summary: written={} skipped={} errors={}",
-            agent_summary.written, agent_summary.skipped, agent_summary.errors
-        );
+        write_meta_json(&meta_path, &meta)?;
+        Ok(InitOutcome::Created(id))
     }
+}
+
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// Re-syncs changed markdown files until Ctrl-C. Meta writes are ignored, so
+/// the watcher never reacts to its own output.
+fn watch_markdown(spec_root: &Path, sync: bool) -> Result<()> {
+    use notify::Watcher;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+    ctrlc::set_handler(move || stop_flag.store(true, Ordering::SeqCst))
+        .context("failed to install Ctrl-C handler")?;
 
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).context("failed to start file watcher")?;
+    let roots = doc_roots(spec_root)
+        .into_iter()
+        .filter(|root| root.is_dir())
+        .collect::<Vec<_>>();
+    if roots.is_empty() {
+        anyhow::bail!("--watch requires spec/ or tasks/ to exist");
+    }
+    for root in &roots {
+        watcher
+            .watch(root, notify::RecursiveMode::Recursive)
+            .with_context(|| format!("failed to watch {}", root.display()))?;
+    }
+    // notify may report canonical paths (symlinked cwd, macOS `/private/var`),
+    // so strip either form to get back to root-relative paths.
+    let cwd = std::env::current_dir()?;
+    let canonical_cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.clone());
+    println!(
+        "init watch: watching {} (Ctrl-C to stop)",
+        roots
+            .iter()
+            .map(|r| r.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut pending = BTreeSet::<PathBuf>::new();
+    // Flushed a fixed time after the first pending change, so a steady
+    // stream of events cannot postpone the sync indefinitely.
+    let mut pending_since: Option<std::time::Instant> = None;
+    while !stop.load(Ordering::SeqCst) {
+        let timeout = match pending_since {
+            Some(since) => WATCH_DEBOUNCE.saturating_sub(since.elapsed()),
+            None => std::time::Duration::from_millis(100),
+        };
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                for path in event.paths {
+                    let path = path
+                        .strip_prefix(&canonical_cwd)
+                        .or_else(|_| path.strip_prefix(&cwd))
+                        .map(Path::to_path_buf)
+                        .unwrap_or(path);
+                    if is_spec_markdown(&path) {
+                        pending.insert(path);
+                        pending_since.get_or_insert_with(std::time::Instant::now);
+                    }
+                }
+            }
+            Ok(Err(err)) => log::warn!("watch error: {err}"),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if pending_since.is_some_and(|since| since.elapsed() >= WATCH_DEBOUNCE) {
+            pending_since = None;
+            let batch = std::mem::take(&mut pending);
+            if let Err(err) = sync_watched_files(spec_root, batch, sync) {
+                log::warn!("init watch: {err:#}");
+            }
+        }
+    }
+    println!("init watch: stopped");
+    Ok(())
+}
+
+/// Syncs one debounced batch; a file that fails is reported and skipped so
+/// the watcher keeps running.
+fn sync_watched_files(spec_root: &Path, batch: BTreeSet<PathBuf>, sync: bool) -> Result<()> {
+    let mut used_ids = load_existing_ids(spec_root)?;
+    let id_allocation = load_runtime_config().id_allocation;
+    // Re-walk so `.foundryignore` applies to watched changes too.
+    let tracked = find_markdown_files(spec_root)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    for md_path in batch {
+        if !tracked.contains(&md_path) {
+            continue;
+        }
+        let (action, id) = match sync_markdown_file(&md_path, sync, &mut used_ids, id_allocation) {
+            Ok(InitOutcome::Created(id)) => ("created", id),
+            Ok(InitOutcome::Updated(id)) => ("updated", id),
+            Ok(InitOutcome::Skipped | InitOutcome::Failed) => continue,
+            Err(err) => {
+                log::warn!("init watch: {} not synced: {err:#}", md_path.display());
+                continue;
+            }
+        };
+        println!("init watch: {action} {} ({id})", md_path.display());
+        record_audit("init watch", action, std::slice::from_ref(&id));
+    }
     Ok(())
 }
+
+fn is_spec_markdown(path: &Path) -> bool {
+    let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    name.ends_with(".md") && !name.ends_with(".meta.md")
+}
@@ -1,6 +1,7 @@
 use super::*;
+use foundry::PAGE_RANK_EDGE_TYPES;
 
-pub(super) fn run_link(link: LinkCommand) -> Result<()> {
+pub(super) fn run_link(link: LinkCommand) -> Result<i32> {
     let spec_root = Path::new("spec");
     let metas = load_all_meta(spec_root, &mut LintState::default())?;
     let mut by_id = HashMap::<String, (PathBuf, SpecNodeMeta)>::new();
@@ -10,19 +11,30 @@ pub(super) fn run_link(link: LinkCommand) -> Result<()> {
 
     match link.command {
         LinkSubcommand::Add(args) => {
-            upsert_edge(
-                &mut by_id,
-                UpsertEdge {
-                    from: &args.from,
-                    to: &args.to,
-                    edge_type: &args.r#type,
-                    rationale: &args.rationale,
-                    confidence: args.confidence,
-                    status: "confirmed",
-                    created_label: "link added",
-                    updated_label: "link updated",
-                },
-            )?;
+            if let Some(bulk_path) = &args.bulk {
+                run_link_add_bulk(&mut by_id, bulk_path, args.continue_on_error)?;
+            } else {
+                let (Some(from), Some(to), Some(edge_type), Some(rationale)) =
+                    (&args.from, &args.to, &args.r#type, &args.rationale)
+                else {
+                    anyhow::bail!("add requires --from, --to, --type and --rationale (or --bulk)");
+                };
+                upsert_edge(
+                    &mut by_id,
+                    UpsertEdge {
+                        from,
+                        to,
+                        edge_type,
+                        rationale,
+                        confidence: args.confidence,
+                        status: "confirmed",
+                        source: EDGE_SOURCE_MANUAL,
+                        created_label: "link added",
+                        updated_label: "link updated",
+                    },
+                )?;
+                record_audit("link add", "add", &[from.clone(), to.clone()]);
+            }
         }
         LinkSubcommand::Remove(args) => {
             let (path, from_meta) = by_id
@@ -37,40 +49,10 @@ pub(super) fn run_link(link: LinkCommand) -> Result<()> {
             } else {
                 write_meta_json(path, from_meta)?;
                 println!("link removed: {} -> {} ({})", args.from, args.to, args.r#type);
+                record_audit("link remove", "remove", &[args.from.clone(), args.to.clone()]);
             }
         }
-        LinkSubcommand::List(args) => {
-            let (_, meta) = by_id
-                .get(&args.node)
-                .with_context(|| format!("node not found: {}", args.node))?;
-            println!("outgoing edges for {}:", args.node);
-            if meta.edges.is_empty() {
-                println!("  (none)");
-            }
-            for e in &meta.edges {
-                println!(
-                    "  -> {} [{}] status={} confidence={} rationale={}",
-                    e.to, e.edge_type, e.status, e.confidence, e.rationale
-                );
-            }
-
-            println!("incoming edges for {}:", args.node);
-            let mut found_incoming = false;
-            for (from_id, (_, from_meta)) in &by_id {
-                for e in &from_meta.edges {
-                    if e.to == args.node {
-                        found_incoming = true;
-                        println!(
-                            "  <- {} [{}] status={} confidence={} rationale={}",
-                            from_id, e.edge_type, e.status, e.confidence, e.rationale
-                        );
-                    }
-                }
-            }
-            if !found_incoming {
-                println!("  (none)");
-            }
-        }
+        LinkSubcommand::List(args) => run_link_list(&by_id, &args)?,
         LinkSubcommand::Propose(args) => {
             if let (Some(from), Some(to)) = (&args.from, &args.to) {
                 let rationale = args
@@ -87,18 +69,438 @@ pub(super) fn run_link(link: LinkCommand) -> Result<()> {
                         rationale: &rationale,
                         confidence: args.confidence,
                         status: "proposed",
+                        source: EDGE_SOURCE_MANUAL,
                         created_label: "proposal added",
                         updated_label: "proposal updated",
                     },
                 )?;
+                record_audit("link propose", "propose", &[from.clone(), to.clone()]);
             } else if let Some(node_id) = args.node.as_deref() {
-                propose_links_for_node(&mut by_id, node_id, args.limit)?;
+                propose_links_for_node(&mut by_id, node_id, &args)?;
             } else {
                 anyhow::bail!(
                     "propose requires either --node <ID> or both --from <ID> and --to <ID>"
                 );
             }
         }
+        LinkSubcommand::Prune(args) => prune_edges(&mut by_id, &args)?,
+        LinkSubcommand::GraphMetrics(args) => run_graph_metrics(by_id, &args)?,
+        LinkSubcommand::Verify(args) => return verify_edges(&mut by_id, &args),
+    }
+    Ok(0)
+}
+
+/// `conflicts_with` is symmetric in meaning, so each one must be stored from
+/// both ends; `--fix` copies the missing reverse edge. `depends_on` without a
+/// matching `impacts` (and vice versa) is only reported. Fails (exit `1`)
+/// while one-sided conflicts remain.
+fn verify_edges(
+    by_id: &mut HashMap<String, (PathBuf, SpecNodeMeta)>,
+    args: &LinkVerifyArgs,
+) -> Result<i32> {
+    let has_edge = |from: &str, to: &str, edge_type: &str| {
+        by_id.get(from).is_some_and(|(_, meta)| {
+            meta.edges
+                .iter()
+                .any(|e| e.to == to && e.edge_type == edge_type)
+        })
+    };
+    let mut ids = by_id.keys().cloned().collect::<Vec<_>>();
+    ids.sort();
+
+    let mut one_sided = Vec::<(String, SpecEdge)>::new();
+    let mut asymmetric = 0usize;
+    for id in &ids {
+        let (_, meta) = &by_id[id];
+        for e in &meta.edges {
+            if e.to == *id || !by_id.contains_key(&e.to) {
+                continue;
+            }
+            let reverse_type = match e.edge_type.as_str() {
+                "conflicts_with" => {
+                    if !has_edge(&e.to, id, "conflicts_with") {
+                        println!(
+                            "link verify: one-sided conflicts_with: {id} -> {} (missing {} -> {id})",
+                            e.to, e.to
+                        );
+                        one_sided.push((id.clone(), e.clone()));
+                    }
+                    continue;
+                }
+                "depends_on" => "impacts",
+                "impacts" => "depends_on",
+                _ => continue,
+            };
+            if !has_edge(&e.to, id, reverse_type) {
+                println!(
+                    "link verify: info: {id} {} {} without {} {reverse_type} {id}",
+                    e.edge_type, e.to, e.to
+                );
+                asymmetric += 1;
+            }
+        }
+    }
+
+    let mut fixed = 0usize;
+    let mut fixed_ids = Vec::new();
+    if args.fix {
+        for (from, edge) in &one_sided {
+            let reverse = UpsertEdge {
+                from: &edge.to,
+                to: from,
+                edge_type: "conflicts_with",
+                rationale: &edge.rationale,
+                confidence: edge.confidence,
+                status: &edge.status,
+                source: edge.source.as_deref().unwrap_or(EDGE_SOURCE_MANUAL),
+                created_label: "link verify: added",
+                updated_label: "link verify: updated",
+            };
+            validate_upsert_edge(by_id, &reverse)?;
+            apply_upsert_edge(by_id, &reverse);
+            let (path, meta) = &by_id[&edge.to];
+            write_meta_json(path, meta)?;
+            fixed += 1;
+            fixed_ids.push(edge.to.clone());
+        }
+    }
+    if !fixed_ids.is_empty() {
+        record_audit("link verify", "fix", &fixed_ids);
+    }
+    println!(
+        "link verify summary: one_sided_conflicts={} fixed={fixed} asymmetric_dependencies={asymmetric}",
+        one_sided.len()
+    );
+    Ok(if one_sided.len() > fixed { 1 } else { 0 })
+}
+
+fn prune_edges(
+    by_id: &mut HashMap<String, (PathBuf, SpecNodeMeta)>,
+    args: &LinkPruneArgs,
+) -> Result<()> {
+    let known_ids = by_id.keys().cloned().collect::<HashSet<_>>();
+    let mut ids = by_id.keys().cloned().collect::<Vec<_>>();
+    ids.sort();
+
+    let verb = if args.dry_run {
+        "would prune"
+    } else {
+        "pruned"
+    };
+    let mut files = 0usize;
+    let mut edges = 0usize;
+    let mut pruned_ids = Vec::new();
+    for id in ids {
+        let (path, meta) = by_id.get_mut(&id).expect("id exists");
+        let (pruned, kept): (Vec<SpecEdge>, Vec<SpecEdge>) = meta.edges.drain(..).partition(|e| {
+            !known_ids.contains(&e.to)
+                || (args.also_proposed && e.status == "proposed")
+                || args
+                    .source
+                    .as_deref()
+                    .is_some_and(|pattern| edge_source_matches(pattern, e))
+        });
+        meta.edges = kept;
+        if pruned.is_empty() {
+            continue;
+        }
+        files += 1;
+        edges += pruned.len();
+        println!(
+            "{verb} {} edge(s) in {} ({id})",
+            pruned.len(),
+            path.display()
+        );
+        for e in &pruned {
+            let reason = if !known_ids.contains(&e.to) {
+                "dangling".to_string()
+            } else if args.also_proposed && e.status == "proposed" {
+                "proposed".to_string()
+            } else {
+                format!("source={}", e.source.as_deref().unwrap_or("-"))
+            };
+            println!("  - -> {} ({}) {reason}", e.to, e.edge_type);
+        }
+        if !args.dry_run {
+            write_meta_json(path, meta)?;
+            pruned_ids.push(id);
+        }
+    }
+    if !pruned_ids.is_empty() {
+        record_audit("link prune", "prune", &pruned_ids);
+    }
+    println!(
+        "link prune summary: files={files} edges={edges} dry_run={}",
+        args.dry_run
+    );
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct LinkListOutput {
+    node: String,
+    outgoing: Vec<SpecEdge>,
+    incoming: Vec<LinkListIncoming>,
+}
+
+#[derive(Debug, Serialize)]
+struct LinkListIncoming {
+    from: String,
+    #[serde(flatten)]
+    edge: SpecEdge,
+}
+
+#[derive(Debug, Serialize)]
+struct LinkDumpRow {
+    from: String,
+    to: String,
+    #[serde(rename = "type")]
+    edge_type: String,
+    status: String,
+    confidence: f64,
+    rationale: String,
+}
+
+fn run_link_list(
+    by_id: &HashMap<String, (PathBuf, SpecNodeMeta)>,
+    args: &LinkListArgs,
+) -> Result<()> {
+    let Some(node) = args.node.as_deref() else {
+        return run_link_list_all(by_id, args);
+    };
+    let (_, meta) = by_id
+        .get(node)
+        .with_context(|| format!("node not found: {node}"))?;
+    let mut outgoing = meta.edges.clone();
+    let mut incoming = by_id
+        .iter()
+        .flat_map(|(from_id, (_, from_meta))| {
+            from_meta
+                .edges
+                .iter()
+                .filter(|e| e.to == node)
+                .map(|e| LinkListIncoming {
+                    from: from_id.clone(),
+                    edge: e.clone(),
+                })
+        })
+        .collect::<Vec<_>>();
+    incoming.sort_by(|a, b| (&a.from, &a.edge.edge_type).cmp(&(&b.from, &b.edge.edge_type)));
+    if let Some(pattern) = args.source.as_deref() {
+        outgoing.retain(|e| edge_source_matches(pattern, e));
+        incoming.retain(|e| edge_source_matches(pattern, &e.edge));
+    }
+    if args.sort == LinkListSort::Age {
+        // Stable sorts: edges without timestamps predate them, so they lead.
+        outgoing.sort_by_key(|e| e.created_at);
+        incoming.sort_by_key(|e| e.edge.created_at);
+    }
+
+    let output = LinkListOutput {
+        node: node.to_string(),
+        outgoing,
+        incoming,
+    };
+    match args.format {
+        LinkFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        LinkFormat::Table => print_link_list_table(&output),
+    }
+    Ok(())
+}
+
+/// `list --all`: every stored edge as one row, ordered by `from`, `to`, `type`
+/// so dumps of two branches diff cleanly.
+fn run_link_list_all(
+    by_id: &HashMap<String, (PathBuf, SpecNodeMeta)>,
+    args: &LinkListArgs,
+) -> Result<()> {
+    let mut edges = by_id
+        .iter()
+        .flat_map(|(from_id, (_, meta))| meta.edges.iter().map(move |e| (from_id, e)))
+        .filter(|(_, e)| {
+            args.source
+                .as_deref()
+                .is_none_or(|pattern| edge_source_matches(pattern, e))
+        })
+        .collect::<Vec<_>>();
+    edges.sort_by(|(a_from, a), (b_from, b)| {
+        (a_from, &a.to, &a.edge_type).cmp(&(b_from, &b.to, &b.edge_type))
+    });
+    if args.sort == LinkListSort::Age {
+        edges.sort_by_key(|(_, e)| e.created_at);
+    }
+
+    match args.format {
+        LinkFormat::Json => {
+            let rows = edges
+                .iter()
+                .map(|(from, e)| LinkDumpRow {
+                    from: (*from).clone(),
+                    to: e.to.clone(),
+                    edge_type: e.edge_type.clone(),
+                    status: e.status.clone(),
+                    confidence: e.confidence,
+                    rationale: e.rationale.clone(),
+                })
+                .collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+        LinkFormat::Table => {
+            if edges.is_empty() {
+                println!("(no edges)");
+            }
+            for (from, e) in &edges {
+                println!(
+                    "{} -> {} [{}] status={} confidence={} rationale={}{}{}",
+                    from,
+                    e.to,
+                    e.edge_type,
+                    e.status,
+                    e.confidence,
+                    e.rationale,
+                    edge_source_label(e),
+                    edge_age_label(e)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_link_list_table(output: &LinkListOutput) {
+    println!("outgoing edges for {}:", output.node);
+    if output.outgoing.is_empty() {
+        println!("  (none)");
+    }
+    for e in &output.outgoing {
+        println!(
+            "  -> {} [{}] status={} confidence={} rationale={}{}{}",
+            e.to,
+            e.edge_type,
+            e.status,
+            e.confidence,
+            e.rationale,
+            edge_source_label(e),
+            edge_age_label(e)
+        );
+    }
+
+    println!("incoming edges for {}:", output.node);
+    if output.incoming.is_empty() {
+        println!("  (none)");
+    }
+    for incoming in &output.incoming {
+        let e = &incoming.edge;
+        println!(
+            "  <- {} [{}] status={} confidence={} rationale={}{}{}",
+            incoming.from,
+            e.edge_type,
+            e.status,
+            e.confidence,
+            e.rationale,
+            edge_source_label(e),
+            edge_age_label(e)
+        );
+    }
+}
+
+/// `--source` filter: an exact source name, or a prefix ending in `*`
+/// (`auto-*`). Edges without a recorded source never match.
+fn edge_source_matches(pattern: &str, edge: &SpecEdge) -> bool {
+    let Some(source) = edge.source.as_deref() else {
+        return false;
+    };
+    match pattern.strip_suffix('*') {
+        Some(prefix) => source.starts_with(prefix),
+        None => source == pattern,
+    }
+}
+
+fn edge_source_label(edge: &SpecEdge) -> String {
+    edge.source
+        .as_deref()
+        .map_or(String::new(), |source| format!(" source={source}"))
+}
+
+fn edge_age_label(edge: &SpecEdge) -> String {
+    match (edge.created_at, edge.updated_at) {
+        (Some(created), Some(updated)) => format!(" created_at={created} updated_at={updated}"),
+        (Some(created), None) => format!(" created_at={created}"),
+        (None, Some(updated)) => format!(" updated_at={updated}"),
+        (None, None) => String::new(),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct GraphMetricsOutput {
+    node_count: usize,
+    page_rank_edge_types: Vec<String>,
+    nodes: Vec<NodeCentrality>,
+}
+
+#[derive(Debug, Serialize)]
+struct NodeCentrality {
+    id: String,
+    title: String,
+    in_degree: usize,
+    out_degree: usize,
+    page_rank: f64,
+}
+
+/// Degrees count every edge between known nodes; PageRank only follows
+/// [`PAGE_RANK_EDGE_TYPES`].
+fn run_graph_metrics(
+    by_id: HashMap<String, (PathBuf, SpecNodeMeta)>,
+    args: &LinkGraphMetricsArgs,
+) -> Result<()> {
+    let graph = SpecGraph::from_nodes(by_id.into_values().map(|(_, meta)| meta));
+    let ranks = graph.page_rank(PAGE_RANK_EDGE_TYPES);
+    let mut in_degree = HashMap::<&str, usize>::new();
+    for meta in graph.nodes().values() {
+        for e in &meta.edges {
+            if graph.get(&e.to).is_some() {
+                *in_degree.entry(e.to.as_str()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut nodes = graph
+        .nodes()
+        .values()
+        .map(|meta| NodeCentrality {
+            id: meta.id.clone(),
+            title: meta.title.clone(),
+            in_degree: in_degree.get(meta.id.as_str()).copied().unwrap_or(0),
+            out_degree: meta
+                .edges
+                .iter()
+                .filter(|e| graph.get(&e.to).is_some())
+                .count(),
+            page_rank: ranks.get(&meta.id).copied().unwrap_or(0.0),
+        })
+        .collect::<Vec<_>>();
+    nodes.sort_by(|a, b| b.page_rank.total_cmp(&a.page_rank).then(a.id.cmp(&b.id)));
+    let output = GraphMetricsOutput {
+        node_count: nodes.len(),
+        page_rank_edge_types: PAGE_RANK_EDGE_TYPES.iter().map(|t| t.to_string()).collect(),
+        nodes: nodes.into_iter().take(args.top).collect(),
+    };
+
+    match args.format {
+        LinkFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        LinkFormat::Table => {
+            println!(
+                "graph metrics: nodes={} (top {} by pagerank)",
+                output.node_count,
+                output.nodes.len()
+            );
+            for node in &output.nodes {
+                println!(
+                    "  - {} pagerank={:.4} in={} out={} {}",
+                    node.id, node.page_rank, node.in_degree, node.out_degree, node.title
+                );
+            }
+        }
     }
     Ok(())
 }
@@ -110,6 +512,7 @@ struct UpsertEdge<'a> {
     rationale: &'a str,
     confidence: f64,
     status: &'a str,
+    source: &'a str,
     created_label: &'a str,
     updated_label: &'a str,
 }
@@ -117,6 +520,17 @@ struct UpsertEdge<'a> {
 fn upsert_edge(
     by_id: &mut HashMap<String, (PathBuf, SpecNodeMeta)>,
     args: UpsertEdge<'_>,
+) -> Result<()> {
+    validate_upsert_edge(by_id, &args)?;
+    apply_upsert_edge(by_id, &args);
+    let (path, from_meta) = by_id.get(args.from).expect("validated above");
+    write_meta_json(path, from_meta)?;
+    Ok(())
+}
+
+fn validate_upsert_edge(
+    by_id: &HashMap<String, (PathBuf, SpecNodeMeta)>,
+    args: &UpsertEdge<'_>,
 ) -> Result<()> {
     if !EDGE_TYPES.contains(&args.edge_type) {
         anyhow::bail!("invalid edge type: {}", args.edge_type);
@@ -130,10 +544,14 @@ fn upsert_edge(
     if !by_id.contains_key(args.to) {
         anyhow::bail!("target node not found: {}", args.to);
     }
-    let (path, from_meta) = by_id
-        .get_mut(args.from)
-        .with_context(|| format!("source node not found: {}", args.from))?;
+    if !by_id.contains_key(args.from) {
+        anyhow::bail!("source node not found: {}", args.from);
+    }
+    Ok(())
+}
 
+fn apply_upsert_edge(by_id: &mut HashMap<String, (PathBuf, SpecNodeMeta)>, args: &UpsertEdge<'_>) {
+    let (_, from_meta) = by_id.get_mut(args.from).expect("validated by caller");
     if let Some(edge) = from_meta
         .edges
         .iter_mut()
@@ -142,6 +560,8 @@ fn upsert_edge(
         edge.rationale = args.rationale.to_string();
         edge.confidence = args.confidence;
         edge.status = args.status.to_string();
+        edge.updated_at = Some(unix_ts());
+        edge.source = Some(args.source.to_string());
         println!(
             "{}: {} -> {} ({})",
             args.updated_label, args.from, args.to, args.edge_type
@@ -153,37 +573,201 @@ fn upsert_edge(
             rationale: args.rationale.to_string(),
             confidence: args.confidence,
             status: args.status.to_string(),
+            created_at: Some(unix_ts()),
+            updated_at: Some(unix_ts()),
+            source: Some(args.source.to_string()),
         });
         println!(
             "{}: {} -> {} ({})",
             args.created_label, args.from, args.to, args.edge_type
         );
     }
-    write_meta_json(path, from_meta)?;
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkEdgeRow {
+    from: String,
+    to: String,
+    #[serde(rename = "type")]
+    edge_type: String,
+    rationale: String,
+    #[serde(default = "default_bulk_confidence")]
+    confidence: f64,
+}
+
+fn default_bulk_confidence() -> f64 {
+    1.0
+}
+
+impl BulkEdgeRow {
+    fn as_upsert(&self) -> UpsertEdge<'_> {
+        UpsertEdge {
+            from: &self.from,
+            to: &self.to,
+            edge_type: &self.edge_type,
+            rationale: &self.rationale,
+            confidence: self.confidence,
+            status: "confirmed",
+            source: EDGE_SOURCE_MANUAL,
+            created_label: "link added",
+            updated_label: "link updated",
+        }
+    }
+}
+
+fn run_link_add_bulk(
+    by_id: &mut HashMap<String, (PathBuf, SpecNodeMeta)>,
+    bulk_path: &str,
+    continue_on_error: bool,
+) -> Result<()> {
+    let raw = fs::read_to_string(bulk_path)
+        .with_context(|| format!("failed reading --bulk file: {bulk_path}"))?;
+    let rows = parse_bulk_edges(bulk_path, &raw)?;
+
+    let mut failures = Vec::<(usize, String)>::new();
+    for (idx, row) in rows.iter().enumerate() {
+        let row_no = idx + 1;
+        let check = if row.rationale.trim().is_empty() {
+            Err(anyhow::anyhow!("rationale cannot be empty"))
+        } else {
+            validate_upsert_edge(by_id, &row.as_upsert())
+        };
+        if let Err(err) = check {
+            println!("bulk row {row_no}: error: {err}");
+            failures.push((row_no, err.to_string()));
+        }
+    }
+    if !failures.is_empty() && !continue_on_error {
+        anyhow::bail!(
+            "bulk link add aborted: {} invalid row(s), nothing written (use --continue-on-error to apply valid rows)",
+            failures.len()
+        );
+    }
+
+    let failed_rows = failures.iter().map(|(row_no, _)| *row_no).collect::<HashSet<_>>();
+    let mut touched = BTreeSet::<String>::new();
+    let mut linked = BTreeSet::<String>::new();
+    for (idx, row) in rows.iter().enumerate() {
+        if failed_rows.contains(&(idx + 1)) {
+            continue;
+        }
+        apply_upsert_edge(by_id, &row.as_upsert());
+        touched.insert(row.from.clone());
+        linked.extend([row.from.clone(), row.to.clone()]);
+    }
+    for id in &touched {
+        let (path, meta) = by_id.get(id).expect("applied rows reference known nodes");
+        write_meta_json(path, meta)?;
+    }
+    if !linked.is_empty() {
+        record_audit("link add", "add", &linked.into_iter().collect::<Vec<_>>());
+    }
+    println!(
+        "bulk summary: rows={} applied={} failed={} files_written={}",
+        rows.len(),
+        rows.len() - failures.len(),
+        failures.len(),
+        touched.len()
+    );
     Ok(())
 }
 
+fn parse_bulk_edges(bulk_path: &str, raw: &str) -> Result<Vec<BulkEdgeRow>> {
+    let is_json = bulk_path.ends_with(".json") || raw.trim_start().starts_with('[');
+    if is_json {
+        return serde_json::from_str::<Vec<BulkEdgeRow>>(raw)
+            .with_context(|| format!("invalid bulk json: {bulk_path}"));
+    }
+
+    // Quoted fields may span lines, so records come from a real CSV reader.
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(raw.as_bytes());
+    let columns = reader
+        .headers()
+        .with_context(|| format!("invalid bulk csv header: {bulk_path}"))?
+        .iter()
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+    if columns.iter().all(String::is_empty) {
+        anyhow::bail!("bulk csv is empty: {bulk_path}");
+    }
+    let column = |name: &str| columns.iter().position(|c| c == name);
+    let (Some(from_col), Some(to_col), Some(type_col), Some(rationale_col)) = (
+        column("from"),
+        column("to"),
+        column("type"),
+        column("rationale"),
+    ) else {
+        anyhow::bail!("bulk csv header must contain from,to,type,rationale: {bulk_path}");
+    };
+    let confidence_col = column("confidence");
+
+    let mut rows = Vec::new();
+    for (idx, record) in reader.records().enumerate() {
+        let record =
+            record.with_context(|| format!("invalid bulk csv row {}: {bulk_path}", idx + 1))?;
+        let field = |col: usize| record.get(col).unwrap_or_default().to_string();
+        let confidence = match confidence_col.map(field).filter(|v| !v.is_empty()) {
+            Some(v) => v
+                .parse::<f64>()
+                .with_context(|| format!("invalid confidence in bulk csv row {}: {v}", idx + 1))?,
+            None => default_bulk_confidence(),
+        };
+        rows.push(BulkEdgeRow {
+            from: field(from_col),
+            to: field(to_col),
+            edge_type: field(type_col),
+            rationale: field(rationale_col),
+            confidence,
+        });
+    }
+    Ok(rows)
+}
+
 fn propose_links_for_node(
     by_id: &mut HashMap<String, (PathBuf, SpecNodeMeta)>,
     node_id: &str,
-    limit: usize,
+    args: &LinkProposeArgs,
 ) -> Result<()> {
     if !by_id.contains_key(node_id) {
         anyhow::bail!("node not found: {node_id}");
     }
+    if !EDGE_TYPES.contains(&args.r#type.as_str()) {
+        anyhow::bail!("invalid edge type: {}", args.r#type);
+    }
     let source = by_id
         .get(node_id)
         .map(|(_, meta)| meta.clone())
         .expect("checked above");
 
-    let source_terms: HashSet<String> = source
-        .terms
-        .iter()
-        .map(|t| normalize_term_key(t))
-        .collect();
+    let source_terms: HashSet<String> =
+        source.terms.iter().map(|t| normalize_term_key(t)).collect();
     let source_title_tokens = tokenize(&source.title);
-
-    let mut candidates: Vec<(String, usize)> = by_id
+    // `{terms}` of a rationale template: shared `terms` in the source's
+    // spelling, then shared title words.
+    let shared_terms = |target: &SpecNodeMeta| -> Vec<String> {
+        let target_terms: HashSet<String> =
+            target.terms.iter().map(|t| normalize_term_key(t)).collect();
+        let mut shared = source
+            .terms
+            .iter()
+            .filter(|t| target_terms.contains(&normalize_term_key(t)))
+            .cloned()
+            .collect::<Vec<_>>();
+        shared.sort();
+        shared.dedup_by_key(|t| normalize_term_key(t));
+        let mut words = source_title_tokens
+            .intersection(&tokenize(&target.title))
+            .filter(|w| !shared.iter().any(|t| normalize_term_key(t) == **w))
+            .cloned()
+            .collect::<Vec<_>>();
+        words.sort();
+        shared.extend(words);
+        shared
+    };
+    let term_scores: HashMap<String, usize> = by_id
         .iter()
         .filter(|(id, _)| id.as_str() != node_id)
         .map(|(id, (_, meta))| {
@@ -195,27 +779,127 @@ fn propose_links_for_node(
         })
         .filter(|(_, score)| *score > 0)
         .collect();
+    let semantic_scores: HashMap<String, f64> = if args.using == ProposeUsing::Term {
+        HashMap::new()
+    } else {
+        let text = fs::read_to_string(&source.body_md_path).unwrap_or_else(|_| source.title.clone());
+        semantic_neighbors(&text, &load_runtime_config())
+            .context("semantic proposals need a search index; run `foundry spec search index`")?
+            .into_iter()
+            .filter(|(id, _)| id != node_id && by_id.contains_key(id))
+            .collect()
+    };
 
-    candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    // (target, rank score, confidence, rationale, `{score}` label)
+    let mut candidates: Vec<(String, f64, f64, String, String)> = match args.using {
+        ProposeUsing::Term => term_scores
+            .into_iter()
+            .map(|(id, score)| {
+                let rationale = format!("auto proposal based on term/title overlap score={score}");
+                let label = score.to_string();
+                (id, score as f64, score_to_confidence(score), rationale, label)
+            })
+            .collect(),
+        ProposeUsing::Semantic => semantic_scores
+            .into_iter()
+            .map(|(id, sim)| {
+                let rationale = format!("auto proposal based on semantic similarity score={sim:.3}");
+                let label = format!("{sim:.3}");
+                (id, sim, similarity_to_confidence(sim), rationale, label)
+            })
+            .collect(),
+        ProposeUsing::Hybrid => {
+            let ids = term_scores
+                .keys()
+                .chain(semantic_scores.keys())
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            ids.into_iter()
+                .map(|id| {
+                    let term = term_scores.get(&id).copied().unwrap_or(0);
+                    let sim = semantic_scores.get(&id).copied().unwrap_or(0.0);
+                    let combined = (term.min(5) as f64 / 5.0 + sim) / 2.0;
+                    let rationale = format!(
+                        "auto proposal based on hybrid score={combined:.3} (term/title overlap={term}, semantic={sim:.3})"
+                    );
+                    let label = format!("{combined:.3}");
+                    (id, combined, similarity_to_confidence(combined), rationale, label)
+                })
+                .collect()
+        }
+    };
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
 
     let mut proposed = 0usize;
-    for (target_id, score) in candidates.into_iter().take(limit) {
-        let rationale = format!("auto proposal based on term/title overlap score={score}");
+    let mut audit_ids = vec![node_id.to_string()];
+    for (target_id, _, confidence, rationale, score) in candidates.into_iter().take(args.limit) {
+        let rationale = match &args.rationale_template {
+            Some(template) => {
+                let terms = by_id
+                    .get(&target_id)
+                    .map(|(_, target)| shared_terms(target))
+                    .unwrap_or_default();
+                render_rationale_template(template, node_id, &target_id, &score, &terms)
+            }
+            None => rationale,
+        };
         upsert_edge(
             by_id,
             UpsertEdge {
                 from: node_id,
                 to: &target_id,
-                edge_type: "impacts",
+                edge_type: &args.r#type,
                 rationale: &rationale,
-                confidence: score_to_confidence(score),
+                confidence,
                 status: "proposed",
+                source: propose_edge_source(args.using),
                 created_label: "proposal added",
                 updated_label: "proposal updated",
             },
         )?;
         proposed += 1;
+        audit_ids.push(target_id);
+    }
+    if proposed > 0 {
+        record_audit("link propose", "propose", &audit_ids);
     }
     println!("propose summary: node={node_id} proposed={proposed}");
     Ok(())
 }
+
+/// Fills `{from}`, `{to}`, `{score}` and `{terms}` (comma-separated, `-`
+/// when none) in a `propose --rationale-template`.
+fn render_rationale_template(
+    template: &str,
+    from: &str,
+    to: &str,
+    score: &str,
+    terms: &[String],
+) -> String {
+    let terms = if terms.is_empty() {
+        "-".to_string()
+    } else {
+        terms.join(", ")
+    };
+    template
+        .replace("{from}", from)
+        .replace("{to}", to)
+        .replace("{score}", score)
+        .replace("{terms}", &terms)
+}
+
+/// `SpecEdge.source` of edges from `propose --node`, per ranking mode.
+fn propose_edge_source(using: ProposeUsing) -> &'static str {
+    match using {
+        ProposeUsing::Term => "auto-term",
+        ProposeUsing::Semantic => "auto-semantic",
+        ProposeUsing::Hybrid => "auto-hybrid",
+    }
+}
+
+/// Maps a `[0, 1]` similarity onto the same `0.5..=0.9` band that
+/// `score_to_confidence` uses for overlap proposals.
+fn similarity_to_confidence(similarity: f64) -> f64 {
+    let confidence = 0.5 + similarity.clamp(0.0, 1.0) * 0.4;
+    (confidence * 100.0).round() / 100.0
+}
@@ -4,17 +4,51 @@ use super::*;
 struct LintOutput {
     ok: bool,
     error_count: usize,
+    warning_count: usize,
     errors: Vec<String>,
+    warnings: Vec<String>,
+    details: Vec<LintError>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixed: Vec<LintFix>,
+}
+
+/// Meta file rewritten by `--fix`, with the fields that changed.
+#[derive(Debug, Serialize)]
+struct LintFix {
+    path: String,
+    id: String,
+    fields: Vec<&'static str>,
+}
+
+/// One lint finding with the meta file and JSON pointer it refers to, when
+/// the finding is tied to a single field, or the line and column where a
+/// meta file failed to parse. Graph-level rules name the node ids involved
+/// in `nodes` instead.
+#[derive(Debug, Serialize)]
+pub(super) struct LintError {
+    pub(super) rule: &'static str,
+    pub(super) severity: LintSeverity,
+    pub(super) path: Option<String>,
+    pub(super) pointer: Option<String>,
+    pub(super) line: Option<usize>,
+    pub(super) column: Option<usize>,
+    pub(super) nodes: Vec<String>,
+    pub(super) message: String,
 }
 
 pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
+    let rules = RuleSelection::from_args(args)?;
     let spec_root = Path::new("spec");
     if !spec_root.exists() && !Path::new("tasks").exists() {
         if args.format == LintFormat::Json {
             let output = LintOutput {
                 ok: true,
                 error_count: 0,
+                warning_count: 0,
                 errors: Vec::new(),
+                warnings: Vec::new(),
+                details: Vec::new(),
+                fixed: Vec::new(),
             };
             println!("{}", serde_json::to_string_pretty(&output)?);
         } else {
@@ -23,6 +57,139 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
         return Ok(0);
     }
 
+    let fixed = if args.fix {
+        apply_lint_fixes(spec_root)?
+    } else {
+        Vec::new()
+    };
+    let findings = collect_lint_findings(spec_root, args.max_depth)?
+        .into_iter()
+        .filter(|finding| rules.enabled(finding.rule))
+        .collect::<Vec<_>>();
+    let messages = |severity: LintSeverity| {
+        findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .map(|f| f.message.clone())
+            .collect::<Vec<_>>()
+    };
+    let errors = messages(LintSeverity::Error);
+    let warnings = messages(LintSeverity::Warning);
+
+    if args.format == LintFormat::Json {
+        let output = LintOutput {
+            ok: errors.is_empty(),
+            error_count: errors.len(),
+            warning_count: warnings.len(),
+            errors,
+            warnings,
+            details: findings,
+            fixed,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(if output.ok { 0 } else { 1 });
+    }
+
+    for fix in &fixed {
+        println!("lint: fixed {} ({}): {}", fix.path, fix.id, fix.fields.join(", "));
+    }
+    for finding in &findings {
+        println!("lint: {}: {}", finding.severity.label(), finding.message);
+    }
+    if errors.is_empty() {
+        if warnings.is_empty() {
+            println!("lint: ok");
+        } else {
+            println!("lint: ok ({} warning(s))", warnings.len());
+        }
+        return Ok(0);
+    }
+    if warnings.is_empty() {
+        println!("lint summary: {} error(s)", errors.len());
+    } else {
+        println!(
+            "lint summary: {} error(s), {} warning(s)",
+            errors.len(),
+            warnings.len()
+        );
+    }
+    Ok(1)
+}
+
+/// Rewrites mechanically fixable meta fields: `body_md_path` separators,
+/// empty `type`/`status`/`title`, and a stale `hash`. Edges and ids are never
+/// touched; unparsable meta files are left for the report.
+fn apply_lint_fixes(spec_root: &Path) -> Result<Vec<LintFix>> {
+    let mut fixed = Vec::new();
+    for path in find_meta_files(spec_root) {
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(mut meta) = serde_json::from_str::<SpecNodeMeta>(&raw) else {
+            continue;
+        };
+        let mut fields = Vec::new();
+        let normalized = meta.body_md_path.replace('\\', "/");
+        if normalized != meta.body_md_path {
+            meta.body_md_path = normalized;
+            fields.push("body_md_path");
+        }
+        if meta.node_type.trim().is_empty() {
+            meta.node_type = "feature_requirement".to_string();
+            fields.push("type");
+        }
+        if meta.status.trim().is_empty() {
+            meta.status = "draft".to_string();
+            fields.push("status");
+        }
+        if let Ok(body) = fs::read_to_string(&meta.body_md_path) {
+            if meta.title.trim().is_empty() {
+                meta.title = extract_title(&body, Path::new(&meta.body_md_path));
+                fields.push("title");
+            }
+            let hash = sha256_hex(body.as_bytes());
+            if meta.hash != hash {
+                meta.hash = hash;
+                fields.push("hash");
+            }
+        }
+        if fields.is_empty() {
+            continue;
+        }
+        write_meta_json(&path, &meta)?;
+        fixed.push(LintFix {
+            path: path.display().to_string(),
+            id: meta.id,
+            fields,
+        });
+    }
+    if !fixed.is_empty() {
+        let ids = fixed.iter().map(|f| f.id.clone()).collect::<Vec<_>>();
+        record_audit("lint", "fix", &ids);
+    }
+    Ok(fixed)
+}
+
+/// Runs every lint rule over `spec_root` (and its sibling `tasks/`) and
+/// returns the findings in report order, before any `--only`/`--ignore`.
+/// `max_refine_depth` (`--max-depth`) overrides `lint.max_refine_depth`.
+pub(super) fn collect_lint_findings(
+    spec_root: &Path,
+    max_refine_depth: Option<usize>,
+) -> Result<Vec<LintError>> {
+    let config = load_runtime_config().lint;
+    let max_refine_depth = max_refine_depth.or(config.max_refine_depth);
+    for rule in config.severity.keys() {
+        if !LINT_RULES.contains(&rule.as_str()) {
+            anyhow::bail!("unknown lint rule in lint.severity: {rule}");
+        }
+    }
+    for node_type in &config.orphan_exempt_types {
+        if !NODE_TYPES.contains(&node_type.as_str()) {
+            anyhow::bail!("unknown node type in lint.orphan_exempt_types: {node_type}");
+        }
+    }
+    let type_levels = node_type_levels(&config);
     let mut lint = LintState::default();
     let metas = load_all_meta(spec_root, &mut lint)?;
     let mut id_to_meta = HashMap::<String, SpecNodeMeta>::new();
@@ -30,6 +197,7 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
     let mut incoming_counts = HashMap::<String, usize>::new();
     let mut outgoing_counts = HashMap::<String, usize>::new();
     let mut normalized_term_variants = BTreeMap::<String, BTreeSet<String>>::new();
+    let mut normalized_term_ids = BTreeMap::<String, BTreeSet<String>>::new();
 
     for (_, meta) in &metas {
         if id_to_meta.insert(meta.id.clone(), meta.clone()).is_some() {
@@ -37,7 +205,11 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
         }
     }
     for id in duplicate_ids {
-        lint.errors.push(format!("duplicate node id: {id}"));
+        lint.push_for(
+            "duplicate-id",
+            vec![id.clone()],
+            format!("duplicate node id: {id}"),
+        );
     }
 
     for (meta_path, meta) in &metas {
@@ -46,13 +218,21 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
         for term in &meta.terms {
             let normalized = normalize_term_key(term);
             if normalized.is_empty() {
-                lint.errors.push(format!(
-                    "empty or non-normalizable term in {} (id={})",
-                    meta_path.display(),
-                    meta.id
-                ));
+                lint.push_for(
+                    "invalid-term",
+                    vec![meta.id.clone()],
+                    format!(
+                        "empty or non-normalizable term in {} (id={})",
+                        meta_path.display(),
+                        meta.id
+                    ),
+                );
                 continue;
             }
+            normalized_term_ids
+                .entry(normalized.clone())
+                .or_default()
+                .insert(meta.id.clone());
             normalized_term_variants
                 .entry(normalized)
                 .or_default()
@@ -60,11 +240,16 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
         }
 
         if !Path::new(&meta.body_md_path).exists() {
-            lint.errors.push(format!(
-                "{} points to missing markdown file: {}",
-                meta_path.display(),
-                meta.body_md_path
-            ));
+            lint.push_at(
+                "missing-markdown",
+                meta_path,
+                "/body_md_path",
+                format!(
+                    "{} points to missing markdown file: {}",
+                    meta_path.display(),
+                    meta.body_md_path
+                ),
+            );
             continue;
         }
 
@@ -72,37 +257,129 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
             .with_context(|| format!("failed reading markdown for lint: {}", meta.body_md_path))?;
         let actual_hash = sha256_hex(body.as_bytes());
         if meta.hash != actual_hash {
-            lint.errors.push(format!(
-                "hash mismatch for {} (id={}): expected {} actual {}",
-                meta.body_md_path, meta.id, meta.hash, actual_hash
-            ));
+            lint.push_at(
+                "hash-mismatch",
+                meta_path,
+                "/hash",
+                format!(
+                    "hash mismatch for {} (id={}): expected {} actual {}",
+                    meta.body_md_path, meta.id, meta.hash, actual_hash
+                ),
+            );
         }
 
-        for edge in &meta.edges {
+        let mut edge_keys = BTreeMap::<(&str, &str), usize>::new();
+        for (idx, edge) in meta.edges.iter().enumerate() {
+            let pointer = |field: &str| format!("/edges/{idx}/{field}");
             *outgoing_counts.entry(meta.id.clone()).or_default() += 1;
             *incoming_counts.entry(edge.to.clone()).or_default() += 1;
+            *edge_keys
+                .entry((edge.to.as_str(), edge.edge_type.as_str()))
+                .or_default() += 1;
+
+            if edge.to == meta.id {
+                lint.push_at(
+                    "self-edge",
+                    meta_path,
+                    pointer("to"),
+                    format!(
+                        "self edge in {}: -> {} ({})",
+                        meta.id, edge.to, edge.edge_type
+                    ),
+                );
+            }
 
             if !id_to_meta.contains_key(&edge.to) {
-                lint.errors
-                    .push(format!("unknown edge target from {} to {}", meta.id, edge.to));
+                lint.push_at(
+                    "unknown-edge-target",
+                    meta_path,
+                    pointer("to"),
+                    format!("unknown edge target from {} to {}", meta.id, edge.to),
+                );
             }
             if !EDGE_TYPES.contains(&edge.edge_type.as_str()) {
-                lint.errors.push(format!(
-                    "invalid edge type from {} to {}: {}",
-                    meta.id, edge.to, edge.edge_type
-                ));
+                lint.push_at(
+                    "invalid-edge-type",
+                    meta_path,
+                    pointer("type"),
+                    format!(
+                        "invalid edge type from {} to {}: {}",
+                        meta.id, edge.to, edge.edge_type
+                    ),
+                );
             }
             if !EDGE_STATUSES.contains(&edge.status.as_str()) {
-                lint.errors.push(format!(
-                    "invalid edge status from {} to {}: {}",
-                    meta.id, edge.to, edge.status
-                ));
+                lint.push_at(
+                    "invalid-edge-status",
+                    meta_path,
+                    pointer("status"),
+                    format!(
+                        "invalid edge status from {} to {}: {}",
+                        meta.id, edge.to, edge.status
+                    ),
+                );
             }
             if edge.confidence < 0.0 || edge.confidence > 1.0 {
-                lint.errors.push(format!(
-                    "invalid edge confidence from {} to {}: {}",
-                    meta.id, edge.to, edge.confidence
-                ));
+                lint.push_at(
+                    "invalid-confidence",
+                    meta_path,
+                    pointer("confidence"),
+                    format!(
+                        "invalid edge confidence from {} to {}: {}",
+                        meta.id, edge.to, edge.confidence
+                    ),
+                );
+            }
+            if edge.edge_type == "tests" && meta.node_type != "test_spec" {
+                lint.push_at(
+                    "tests-source",
+                    meta_path,
+                    pointer("type"),
+                    format!(
+                        "tests edge from non-test_spec node {} ({}) to {}",
+                        meta.id, meta.node_type, edge.to
+                    ),
+                );
+            }
+            if edge.edge_type == "refines"
+                && let Some(target) = id_to_meta.get(&edge.to)
+                && let Some(from_level) = type_levels.get(meta.node_type.as_str())
+                && let Some(to_level) = type_levels.get(target.node_type.as_str())
+                && from_level <= to_level
+            {
+                lint.push_at(
+                    "refines-direction",
+                    meta_path,
+                    pointer("type"),
+                    format!(
+                        "refines edge from {} ({}) to {} ({}) does not point to a more abstract type",
+                        meta.id, meta.node_type, target.id, target.node_type
+                    ),
+                );
+            }
+            if edge.status == "confirmed" {
+                let rationale_len = edge.rationale.trim().chars().count();
+                if rationale_len == 0 {
+                    lint.push_at(
+                        "weak-rationale",
+                        meta_path,
+                        pointer("rationale"),
+                        format!(
+                            "empty rationale on confirmed edge from {} to {} ({})",
+                            meta.id, edge.to, edge.edge_type
+                        ),
+                    );
+                } else if rationale_len < config.min_rationale_len {
+                    lint.push_at(
+                        "weak-rationale",
+                        meta_path,
+                        pointer("rationale"),
+                        format!(
+                            "rationale shorter than {} chars on confirmed edge from {} to {} ({}): {}",
+                            config.min_rationale_len, meta.id, edge.to, edge.edge_type, edge.rationale
+                        ),
+                    );
+                }
             }
             if edge.edge_type == "conflicts_with"
                 && edge.status == "confirmed"
@@ -110,10 +387,27 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
                 && meta.status == "active"
                 && target.status == "active"
             {
-                lint.errors.push(format!(
-                    "unresolved conflict: {} conflicts_with {}",
-                    meta.id, target.id
-                ));
+                lint.push_at(
+                    "unresolved-conflict",
+                    meta_path,
+                    pointer("type"),
+                    format!(
+                        "unresolved conflict: {} conflicts_with {}",
+                        meta.id, target.id
+                    ),
+                );
+            }
+        }
+        for ((to, edge_type), count) in edge_keys {
+            if count > 1 {
+                lint.push_for(
+                    "duplicate-edge",
+                    vec![meta.id.clone()],
+                    format!(
+                        "duplicate edge in {}: -> {to} ({edge_type}) x{count}",
+                        meta.id
+                    ),
+                );
             }
         }
     }
@@ -121,46 +415,295 @@ pub(super) fn run_lint(args: &LintArgs) -> Result<i32> {
     for (_, meta) in &metas {
         let in_count = incoming_counts.get(&meta.id).copied().unwrap_or(0);
         let out_count = outgoing_counts.get(&meta.id).copied().unwrap_or(0);
-        if meta.node_type != "product_goal" && in_count == 0 && out_count == 0 {
-            lint.errors.push(format!("orphan node: {}", meta.id));
+        if in_count > 0 || out_count > 0 {
+            continue;
+        }
+        // A task with no edges cannot be traced to anything it implements,
+        // so it is reported separately and never exempt.
+        if meta.node_type == "implementation_task" {
+            lint.push_for(
+                "orphan-task",
+                vec![meta.id.clone()],
+                format!(
+                    "orphan implementation task: {} is not linked to any spec",
+                    meta.id
+                ),
+            );
+        } else if !config.orphan_exempt_types.contains(&meta.node_type) {
+            lint.push_for(
+                "orphan",
+                vec![meta.id.clone()],
+                format!("orphan node: {}", meta.id),
+            );
+        }
+    }
+
+    for members in goalless_components(&id_to_meta) {
+        let message = format!(
+            "component of {} nodes not connected to any product_goal: {}",
+            members.len(),
+            members.join(", ")
+        );
+        lint.push_for("disconnected-component", members, message);
+    }
+
+    if let Some(max_depth) = max_refine_depth {
+        for chain in deep_refine_chains(&id_to_meta, max_depth) {
+            let message = format!(
+                "refines chain of depth {} exceeds max depth {max_depth}: {}",
+                chain.len() - 1,
+                chain.join(" -> ")
+            );
+            lint.push_for("refine-depth", chain, message);
         }
     }
 
     for (normalized, variants) in normalized_term_variants {
         if variants.len() > 1 {
             let joined = variants.into_iter().collect::<Vec<_>>().join(", ");
-            lint.errors.push(format!(
-                "term key drift detected for normalized key '{normalized}': {joined}"
-            ));
+            let ids = normalized_term_ids
+                .remove(&normalized)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            lint.push_for(
+                "term-drift",
+                ids,
+                format!("term key drift detected for normalized key '{normalized}': {joined}"),
+            );
         }
     }
 
-    if lint.errors.is_empty() {
-        if args.format == LintFormat::Json {
-            let output = LintOutput {
-                ok: true,
-                error_count: 0,
-                errors: Vec::new(),
-            };
-            println!("{}", serde_json::to_string_pretty(&output)?);
-        } else {
-            println!("lint: ok");
+    let findings = lint
+        .findings
+        .into_iter()
+        .map(|finding| {
+            let (rule, nodes) = finding
+                .tag
+                .map_or((UNTAGGED_RULE, Vec::new()), |t| (t.rule, t.nodes));
+            let location = finding.location;
+            let position = location.as_ref().and_then(|l| l.position);
+            LintError {
+                rule,
+                severity: rule_severity(&config, rule),
+                path: location.as_ref().map(|l| l.path.clone()),
+                pointer: location.and_then(|l| l.pointer),
+                line: position.map(|(line, _)| line),
+                column: position.map(|(_, column)| column),
+                nodes,
+                message: finding.message,
+            }
+        })
+        .collect();
+    Ok(findings)
+}
+
+/// Weakly connected components (any edge type, either direction) of two or
+/// more nodes that contain no `product_goal`, each as sorted member ids.
+/// Single unlinked nodes are left to the orphan rules, and a graph without
+/// any product goal has no main component to be disconnected from.
+fn goalless_components(by_id: &HashMap<String, SpecNodeMeta>) -> Vec<Vec<String>> {
+    if !by_id.values().any(|m| m.node_type == "product_goal") {
+        return Vec::new();
+    }
+    let mut neighbors = HashMap::<&str, Vec<&str>>::new();
+    for meta in by_id.values() {
+        for edge in &meta.edges {
+            if edge.to != meta.id && by_id.contains_key(&edge.to) {
+                neighbors.entry(&meta.id).or_default().push(&edge.to);
+                neighbors.entry(&edge.to).or_default().push(&meta.id);
+            }
+        }
+    }
+    let mut ids = by_id.keys().map(String::as_str).collect::<Vec<_>>();
+    ids.sort();
+    let mut seen = HashSet::<&str>::new();
+    let mut components = Vec::new();
+    for start in ids {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut members = vec![start];
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            for next in neighbors.get(current).into_iter().flatten() {
+                if seen.insert(next) {
+                    members.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        if members.len() > 1
+            && !members
+                .iter()
+                .any(|id| by_id[*id].node_type == "product_goal")
+        {
+            members.sort();
+            components.push(members.into_iter().map(str::to_string).collect());
         }
-        return Ok(0);
     }
+    components
+}
 
-    if args.format == LintFormat::Json {
-        let output = LintOutput {
-            ok: false,
-            error_count: lint.errors.len(),
-            errors: lint.errors,
-        };
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        for err in &lint.errors {
-            println!("lint: error: {err}");
+/// Longest `refines` chain starting at each leaf (a node nothing refines),
+/// for leaves whose chain is more than `max_depth` edges long. Each chain is
+/// the leaf followed by the ancestors it refines, most abstract last; an
+/// edge that would close a cycle is not followed.
+fn deep_refine_chains(by_id: &HashMap<String, SpecNodeMeta>, max_depth: usize) -> Vec<Vec<String>> {
+    let refined = by_id
+        .values()
+        .flat_map(|meta| &meta.edges)
+        .filter(|edge| edge.edge_type == "refines")
+        .map(|edge| edge.to.as_str())
+        .collect::<HashSet<_>>();
+    let mut leaves = by_id
+        .keys()
+        .map(String::as_str)
+        .filter(|id| !refined.contains(id))
+        .collect::<Vec<_>>();
+    leaves.sort();
+    let mut memo = HashMap::new();
+    leaves
+        .into_iter()
+        .map(|leaf| longest_refine_chain(by_id, leaf, &mut memo, &mut HashSet::new()))
+        .filter(|chain| chain.len() > max_depth + 1)
+        .map(|chain| chain.into_iter().map(str::to_string).collect())
+        .collect()
+}
+
+fn longest_refine_chain<'a>(
+    by_id: &'a HashMap<String, SpecNodeMeta>,
+    id: &'a str,
+    memo: &mut HashMap<&'a str, Vec<&'a str>>,
+    visiting: &mut HashSet<&'a str>,
+) -> Vec<&'a str> {
+    if let Some(chain) = memo.get(id) {
+        return chain.clone();
+    }
+    visiting.insert(id);
+    let mut parents = by_id[id]
+        .edges
+        .iter()
+        .filter(|edge| edge.edge_type == "refines" && by_id.contains_key(&edge.to))
+        .map(|edge| edge.to.as_str())
+        .collect::<Vec<_>>();
+    parents.sort();
+    let mut longest = Vec::new();
+    for parent in parents {
+        if visiting.contains(parent) {
+            continue;
+        }
+        let chain = longest_refine_chain(by_id, parent, memo, visiting);
+        if chain.len() > longest.len() {
+            longest = chain;
         }
-        println!("lint summary: {} error(s)", lint.errors.len());
     }
-    Ok(1)
+    visiting.remove(id);
+    let mut chain = vec![id];
+    chain.extend(longest);
+    memo.insert(id, chain.clone());
+    chain
+}
+
+/// Rule names accepted by `--only` / `--ignore`.
+const LINT_RULES: &[&str] = &[
+    "invalid-meta",
+    "duplicate-id",
+    "invalid-field",
+    "invalid-term",
+    "missing-markdown",
+    "hash-mismatch",
+    "self-edge",
+    "unknown-edge-target",
+    "invalid-edge-type",
+    "invalid-edge-status",
+    "invalid-confidence",
+    "weak-rationale",
+    "tests-source",
+    "refines-direction",
+    "unresolved-conflict",
+    "duplicate-edge",
+    "orphan",
+    "orphan-task",
+    "disconnected-component",
+    "refine-depth",
+    "term-drift",
+];
+
+/// Rules reported as warnings unless `lint.severity` says otherwise.
+const WARNING_RULES: &[&str] = &[
+    "tests-source",
+    "refines-direction",
+    "disconnected-component",
+    "refine-depth",
+];
+
+fn rule_severity(config: &LintRuntimeConfig, rule: &str) -> LintSeverity {
+    match config.severity.get(rule) {
+        Some(severity) => *severity,
+        None if WARNING_RULES.contains(&rule) => LintSeverity::Warning,
+        None => LintSeverity::Error,
+    }
+}
+
+/// Abstraction level per node type (lower is more abstract); a `refines`
+/// edge must go from a higher level to a lower one.
+const DEFAULT_TYPE_LEVELS: &[(&str, u32)] = &[
+    ("product_goal", 0),
+    ("feature_requirement", 1),
+    ("non_functional_requirement", 1),
+    ("constraint", 1),
+    ("domain_concept", 1),
+    ("workflow", 1),
+    ("decision", 2),
+    ("adr", 2),
+    ("api_contract", 2),
+    ("data_contract", 2),
+    ("test_spec", 2),
+    ("architecture", 2),
+    ("component_design", 3),
+    ("api_design", 3),
+    ("data_design", 3),
+    ("implementation_task", 4),
+    ("test_task", 4),
+    ("migration_task", 4),
+];
+
+fn node_type_levels(config: &LintRuntimeConfig) -> HashMap<String, u32> {
+    let mut levels = DEFAULT_TYPE_LEVELS
+        .iter()
+        .map(|(node_type, level)| (node_type.to_string(), *level))
+        .collect::<HashMap<_, _>>();
+    levels.extend(config.type_levels.clone());
+    levels
+}
+
+/// Meta files that could not be read or parsed are reported before any rule
+/// runs, so their errors carry no tag.
+const UNTAGGED_RULE: &str = "invalid-meta";
+
+struct RuleSelection {
+    only: HashSet<String>,
+    ignore: HashSet<String>,
+}
+
+impl RuleSelection {
+    fn from_args(args: &LintArgs) -> Result<Self> {
+        for name in args.only.iter().chain(&args.ignore) {
+            if !LINT_RULES.contains(&name.as_str()) {
+                anyhow::bail!(
+                    "unknown lint rule: {name} (known: {})",
+                    LINT_RULES.join(", ")
+                );
+            }
+        }
+        Ok(Self {
+            only: args.only.iter().cloned().collect(),
+            ignore: args.ignore.iter().cloned().collect(),
+        })
+    }
+
+    fn enabled(&self, rule: &str) -> bool {
+        (self.only.is_empty() || self.only.contains(rule)) && !self.ignore.contains(rule)
+    }
 }
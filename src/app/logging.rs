@@ -0,0 +1,49 @@
+use std::io::Write;
+
+/// Routes `log` records for the CLI: `info` lines are the human status lines
+/// commands used to print on stdout, so they stay there; warnings, errors and
+/// `--verbose` debug output go to stderr with a level prefix.
+struct CliLogger;
+
+impl log::Log for CliLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args();
+        // A closed pipe must not turn a diagnostic into a panic.
+        let _ = match record.level() {
+            log::Level::Info => writeln!(std::io::stdout(), "{message}"),
+            log::Level::Error => writeln!(std::io::stderr(), "error: {message}"),
+            log::Level::Warn => writeln!(std::io::stderr(), "warning: {message}"),
+            log::Level::Debug | log::Level::Trace => {
+                writeln!(std::io::stderr(), "debug: {message}")
+            }
+        };
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+    }
+}
+
+static LOGGER: CliLogger = CliLogger;
+
+/// `--quiet` keeps only warnings and errors, `--verbose` adds debug output.
+pub(super) fn init_logging(verbose: bool, quiet: bool) {
+    let level = if quiet {
+        log::LevelFilter::Warn
+    } else if verbose {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Info
+    };
+    // Only fails when a logger is already installed (e.g. in tests).
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+pub(super) fn run_migrate() -> Result<()> {
+    let mut migrated = Vec::new();
+    let mut unchanged = 0;
+    for path in find_meta_files(Path::new("spec")) {
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed reading {}", path.display()))?;
+        let before: serde_json::Value = serde_json::from_str(&raw)
+            .with_context(|| format!("invalid meta file: {}", path.display()))?;
+        let mut meta: SpecNodeMeta = serde_json::from_value(before.clone())
+            .with_context(|| format!("invalid meta file: {}", path.display()))?;
+        migrate_meta(&mut meta).with_context(|| format!("cannot migrate {}", path.display()))?;
+
+        // Serde already filled defaults for absent fields; compare the JSON
+        // shape so backfilled fields count as a change too.
+        if serde_json::to_value(&meta)? == before {
+            unchanged += 1;
+            continue;
+        }
+        write_meta_json(&path, &meta)?;
+        println!("migrate: updated {} ({})", path.display(), meta.id);
+        migrated.push(meta.id);
+    }
+    if !migrated.is_empty() {
+        record_audit("migrate", "migrate", &migrated);
+    }
+    println!(
+        "migrate summary: migrated={} unchanged={unchanged}",
+        migrated.len()
+    );
+    Ok(())
+}
+
+/// Upgrades `meta` step by step from its recorded version to
+/// [`META_SCHEMA_VERSION`].
+fn migrate_meta(meta: &mut SpecNodeMeta) -> Result<()> {
+    if meta.schema_version > META_SCHEMA_VERSION {
+        anyhow::bail!(
+            "schema_version {} is newer than supported {META_SCHEMA_VERSION}",
+            meta.schema_version
+        );
+    }
+    if meta.schema_version < 1 {
+        meta.body_md_path = meta.body_md_path.replace('\\', "/");
+    }
+    meta.schema_version = META_SCHEMA_VERSION;
+    Ok(())
+}
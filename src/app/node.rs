@@ -0,0 +1,169 @@
+use super::*;
+use foundry::ImpactReport;
+
+#[derive(Debug, Serialize)]
+struct NodeShowOutput {
+    meta: SpecNodeMeta,
+    meta_path: String,
+    body_preview: Vec<String>,
+    body_line_count: usize,
+    edges: NodeEdges,
+    impact: ImpactReport,
+    lint_errors: Vec<LintError>,
+}
+
+#[derive(Debug, Serialize)]
+struct NodeEdges {
+    outgoing: Vec<SpecEdge>,
+    incoming: Vec<IncomingEdge>,
+}
+
+#[derive(Debug, Serialize)]
+struct IncomingEdge {
+    from: String,
+    #[serde(rename = "type")]
+    edge_type: String,
+    rationale: String,
+    confidence: f64,
+    status: String,
+}
+
+pub(super) fn run_node(node: NodeCommand) -> Result<()> {
+    match node.command {
+        NodeSubcommand::Show(args) => run_node_show(&args),
+    }
+}
+
+fn run_node_show(args: &NodeShowArgs) -> Result<()> {
+    let spec_root = Path::new("spec");
+    let metas = load_all_meta(spec_root, &mut LintState::default())?;
+    let (meta_path, meta) = metas
+        .iter()
+        .find(|(_, meta)| meta.id == args.node_id)
+        .cloned()
+        .with_context(|| format!("node not found: {}", args.node_id))?;
+
+    let mut incoming = metas
+        .iter()
+        .flat_map(|(_, from)| {
+            from.edges
+                .iter()
+                .filter(|e| e.to == meta.id)
+                .map(|e| IncomingEdge {
+                    from: from.id.clone(),
+                    edge_type: e.edge_type.clone(),
+                    rationale: e.rationale.clone(),
+                    confidence: e.confidence,
+                    status: e.status.clone(),
+                })
+        })
+        .collect::<Vec<_>>();
+    incoming.sort_by(|a, b| (&a.from, &a.edge_type).cmp(&(&b.from, &b.edge_type)));
+
+    let graph = SpecGraph::from_nodes(metas.into_iter().map(|(_, meta)| meta));
+    let impact = graph
+        .impact(&meta.id, 1)
+        .with_context(|| format!("node not found: {}", meta.id))?;
+
+    let (body_preview, body_line_count) = match fs::read_to_string(&meta.body_md_path) {
+        Ok(body) => (
+            body.lines()
+                .take(args.body_lines)
+                .map(str::to_string)
+                .collect(),
+            body.lines().count(),
+        ),
+        Err(_) => (Vec::new(), 0),
+    };
+
+    let meta_path = meta_path.display().to_string();
+    let lint_errors = collect_lint_findings(spec_root, None)?
+        .into_iter()
+        .filter(|finding| finding_concerns_node(finding, &meta_path, &meta.id))
+        .collect();
+
+    let output = NodeShowOutput {
+        edges: NodeEdges {
+            outgoing: meta.edges.clone(),
+            incoming,
+        },
+        meta,
+        meta_path,
+        body_preview,
+        body_line_count,
+        impact,
+        lint_errors,
+    };
+    match args.format {
+        NodeFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        NodeFormat::Table => print_node_show(&output),
+    }
+    Ok(())
+}
+
+/// Findings tied to a field belong to the node owning that meta file; the
+/// rest (orphan, duplicate-edge, ...) name the nodes they concern.
+fn finding_concerns_node(finding: &LintError, meta_path: &str, id: &str) -> bool {
+    finding.path.as_deref() == Some(meta_path) || finding.nodes.iter().any(|node| node == id)
+}
+
+fn print_node_show(output: &NodeShowOutput) {
+    let meta = &output.meta;
+    println!("node: {} {}", meta.id, meta.title);
+    println!("  type={} status={}", meta.node_type, meta.status);
+    println!("  body={} meta={}", meta.body_md_path, output.meta_path);
+    if !meta.terms.is_empty() {
+        println!("  terms={}", meta.terms.join(", "));
+    }
+
+    println!(
+        "body (first {} of {} line(s)):",
+        output.body_preview.len(),
+        output.body_line_count
+    );
+    for line in &output.body_preview {
+        println!("  | {line}");
+    }
+
+    println!("outgoing edges:");
+    if output.edges.outgoing.is_empty() {
+        println!("  (none)");
+    }
+    for e in &output.edges.outgoing {
+        println!(
+            "  -> {} [{}] status={} confidence={} rationale={}",
+            e.to, e.edge_type, e.status, e.confidence, e.rationale
+        );
+    }
+    println!("incoming edges:");
+    if output.edges.incoming.is_empty() {
+        println!("  (none)");
+    }
+    for e in &output.edges.incoming {
+        println!(
+            "  <- {} [{}] status={} confidence={} rationale={}",
+            e.from, e.edge_type, e.status, e.confidence, e.rationale
+        );
+    }
+
+    let impact = &output.impact;
+    println!(
+        "impact (depth 1): direct_dependencies={} reverse_dependents={} tests={} conflicts={}",
+        impact.direct_dependencies.len(),
+        impact.reverse_dependents.len(),
+        impact.test_coverage_chain.len(),
+        impact.conflict_risks.len()
+    );
+    println!("lint:");
+    if output.lint_errors.is_empty() {
+        println!("  ok");
+    }
+    for finding in &output.lint_errors {
+        println!(
+            "  {}: [{}] {}",
+            finding.severity.label(),
+            finding.rule,
+            finding.message
+        );
+    }
+}
@@ -6,6 +6,8 @@ struct TaskSummary {
     title: String,
     path: String,
     status: String,
+    owner: String,
+    priority: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -14,6 +16,8 @@ struct BlockedTask {
     title: String,
     path: String,
     status: String,
+    owner: String,
+    priority: String,
     blocked_by: Vec<String>,
 }
 
@@ -37,14 +41,34 @@ struct PlanBatchesOutput {
     blocked_or_cyclic_tasks: Vec<TaskSummary>,
 }
 
-pub(super) fn run_plan(plan: PlanCommand) -> Result<()> {
+#[derive(Debug, Serialize)]
+struct CriticalPathStep {
+    id: String,
+    title: String,
+    path: String,
+    status: String,
+    weight: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct PlanCriticalPathOutput {
+    ok: bool,
+    length: f64,
+    task_count: usize,
+    path: Vec<CriticalPathStep>,
+    cyclic: Vec<String>,
+}
+
+pub(super) fn run_plan(plan: PlanCommand) -> Result<i32> {
     match plan.command {
-        PlanSubcommand::Ready(args) => run_plan_ready(args.format),
-        PlanSubcommand::Batches(args) => run_plan_batches(args.format),
+        PlanSubcommand::Ready(args) => run_plan_ready(&args).map(|_| 0),
+        PlanSubcommand::Batches(args) => run_plan_batches(&args).map(|_| 0),
+        PlanSubcommand::CriticalPath(args) => run_plan_critical_path(&args),
+        PlanSubcommand::Assign(args) => run_plan_assign(&args).map(|_| 0),
     }
 }
 
-fn run_plan_ready(format: PlanFormat) -> Result<()> {
+fn run_plan_ready(args: &PlanReadyArgs) -> Result<()> {
     let by_id = load_meta_by_id()?;
     let mut ready = Vec::new();
     let mut blocked = Vec::new();
@@ -53,130 +77,312 @@ fn run_plan_ready(format: PlanFormat) -> Result<()> {
         if !is_task_node(meta) || is_done_status(&meta.status) {
             continue;
         }
-        let blockers = unresolved_task_dependencies(meta, &by_id);
+        if args.owner.as_ref().is_some_and(|owner| &meta.owner != owner) {
+            continue;
+        }
+        let blockers = unresolved_task_dependencies(meta, &by_id, args.include_proposed);
         if blockers.is_empty() {
-            ready.push(TaskSummary {
-                id: meta.id.clone(),
-                title: meta.title.clone(),
-                path: meta.body_md_path.clone(),
-                status: meta.status.clone(),
-            });
+            ready.push(task_summary(meta));
         } else {
             blocked.push(BlockedTask {
                 id: meta.id.clone(),
                 title: meta.title.clone(),
                 path: meta.body_md_path.clone(),
                 status: meta.status.clone(),
+                owner: meta.owner.clone(),
+                priority: meta.priority.clone(),
                 blocked_by: blockers,
             });
         }
     }
 
-    ready.sort_by(|a, b| a.id.cmp(&b.id));
+    ready.sort_by(|a, b| {
+        priority_rank(&a.priority)
+            .cmp(&priority_rank(&b.priority))
+            .then_with(|| a.id.cmp(&b.id))
+    });
     blocked.sort_by(|a, b| a.id.cmp(&b.id));
     let output = PlanReadyOutput { ready, blocked };
-    match format {
+    match args.format {
+        PlanReadyFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        PlanReadyFormat::Table => print_plan_ready_table(&output),
+        PlanReadyFormat::Csv => print_plan_ready_csv(&output)?,
+    }
+    Ok(())
+}
+
+fn run_plan_assign(args: &PlanAssignArgs) -> Result<()> {
+    let owner = args.owner.trim();
+    let metas = load_all_meta(Path::new("spec"), &mut LintState::default())?;
+    let (meta_path, mut meta) = metas
+        .into_iter()
+        .find(|(_, meta)| meta.id == args.id)
+        .with_context(|| format!("node not found: {}", args.id))?;
+    if !is_task_node(&meta) {
+        anyhow::bail!("not a task node: {} ({})", meta.id, meta.node_type);
+    }
+    meta.owner = owner.to_string();
+    write_meta_json(&meta_path, &meta)?;
+    record_audit("plan assign", "assign", std::slice::from_ref(&meta.id));
+    if owner.is_empty() {
+        println!("plan assign: {} unassigned", meta.id);
+    } else {
+        println!("plan assign: {} owner={owner}", meta.id);
+    }
+    Ok(())
+}
+
+fn run_plan_batches(args: &PlanBatchesArgs) -> Result<()> {
+    if args.max_parallel == Some(0) {
+        anyhow::bail!("--max-parallel must be at least 1");
+    }
+    let by_id = load_meta_by_id()?;
+    let pending_ids = by_id
+        .values()
+        .filter(|m| is_task_node(m) && !is_done_status(&m.status))
+        .map(|m| m.id.clone())
+        .collect::<HashSet<_>>();
+
+    let limits = BatchLimits {
+        max_parallel: args.max_parallel,
+        owner: args.owner.as_deref(),
+    };
+    let (layers, blocked_or_cyclic) =
+        layered_task_order(&pending_ids, &by_id, &limits, args.include_proposed);
+    let batches = layers
+        .into_iter()
+        .enumerate()
+        .map(|(idx, task_ids)| PlanBatch {
+            batch: idx + 1,
+            tasks: task_ids
+                .iter()
+                .filter_map(|id| by_id.get(id))
+                .map(task_summary)
+                .collect(),
+            task_ids,
+        })
+        .collect::<Vec<_>>();
+
+    let blocked_or_cyclic_tasks = blocked_or_cyclic
+        .iter()
+        .filter_map(|id| by_id.get(id))
+        .map(task_summary)
+        .collect::<Vec<_>>();
+
+    let output = PlanBatchesOutput {
+        batches,
+        blocked_or_cyclic,
+        blocked_or_cyclic_tasks,
+    };
+    match args.format {
         PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
-        PlanFormat::Table => print_plan_ready_table(&output),
+        PlanFormat::Table => print_plan_batches_table(&output),
     }
     Ok(())
 }
 
-fn run_plan_batches(format: PlanFormat) -> Result<()> {
+fn run_plan_critical_path(args: &PlanCriticalPathArgs) -> Result<i32> {
     let by_id = load_meta_by_id()?;
     let pending_ids = by_id
         .values()
         .filter(|m| is_task_node(m) && !is_done_status(&m.status))
         .map(|m| m.id.clone())
         .collect::<HashSet<_>>();
+    let (layers, cyclic) = layered_task_order(
+        &pending_ids,
+        &by_id,
+        &BatchLimits::default(),
+        args.include_proposed,
+    );
 
+    // Longest weighted path over the acyclic part, in topological order.
+    let mut best = HashMap::<String, (f64, Option<String>)>::new();
+    for id in layers.iter().flatten() {
+        let meta = by_id.get(id).expect("pending id exists");
+        let mut prev: Option<(f64, String)> = None;
+        for dep in unresolved_task_dependencies(meta, &by_id, args.include_proposed) {
+            let Some((dist, _)) = best.get(&dep) else {
+                continue;
+            };
+            let better = match &prev {
+                None => true,
+                Some((d, prev_id)) => *dist > *d || (*dist == *d && dep < *prev_id),
+            };
+            if better {
+                prev = Some((*dist, dep));
+            }
+        }
+        let base = prev.as_ref().map(|(d, _)| *d).unwrap_or(0.0);
+        best.insert(
+            id.clone(),
+            (base + task_estimate(meta), prev.map(|(_, p)| p)),
+        );
+    }
+
+    let mut tail: Option<(&String, f64)> = None;
+    for (id, (dist, _)) in &best {
+        let better = match tail {
+            None => true,
+            Some((tail_id, d)) => *dist > d || (*dist == d && id < tail_id),
+        };
+        if better {
+            tail = Some((id, *dist));
+        }
+    }
+
+    let mut chain = Vec::<String>::new();
+    let mut cursor = tail.map(|(id, _)| id.clone());
+    while let Some(id) = cursor {
+        cursor = best.get(&id).and_then(|(_, prev)| prev.clone());
+        chain.push(id);
+    }
+    chain.reverse();
+
+    let path = chain
+        .iter()
+        .filter_map(|id| by_id.get(id))
+        .map(|meta| CriticalPathStep {
+            id: meta.id.clone(),
+            title: meta.title.clone(),
+            path: meta.body_md_path.clone(),
+            status: meta.status.clone(),
+            weight: task_estimate(meta),
+        })
+        .collect::<Vec<_>>();
+    let output = PlanCriticalPathOutput {
+        ok: cyclic.is_empty(),
+        length: tail.map(|(_, d)| d).unwrap_or(0.0),
+        task_count: path.len(),
+        path,
+        cyclic,
+    };
+    match args.format {
+        PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        PlanFormat::Table => print_plan_critical_path_table(&output),
+    }
+    Ok(if output.ok { 0 } else { 1 })
+}
+
+/// `plan batches --max-parallel` / `--owner`; the default places every
+/// pending task as soon as its dependencies are.
+#[derive(Debug, Default)]
+struct BatchLimits<'a> {
+    max_parallel: Option<usize>,
+    owner: Option<&'a str>,
+}
+
+/// Kahn layering over pending task dependencies, counted with the same
+/// confirmed-only rule as `plan ready` (see [`unresolved_task_dependencies`]),
+/// so a task `ready` lists lands in the first batch. A batch holds at most
+/// `max_parallel` ready tasks (by id); the rest spill into the next batch
+/// ahead of tasks unblocked later. With an owner, only that owner's tasks
+/// are placed, so a task waiting on someone else's pending task never
+/// becomes ready. Tasks left over are blocked by, or part of, a dependency
+/// cycle.
+fn layered_task_order(
+    pending_ids: &HashSet<String>,
+    by_id: &HashMap<String, SpecNodeMeta>,
+    limits: &BatchLimits<'_>,
+    include_proposed: bool,
+) -> (Vec<Vec<String>>, Vec<String>) {
     let mut indegree = HashMap::<String, usize>::new();
     let mut dependents = HashMap::<String, Vec<String>>::new();
-    for id in &pending_ids {
+    for id in pending_ids {
         indegree.insert(id.clone(), 0);
     }
 
-    for id in &pending_ids {
+    for id in pending_ids {
         let meta = by_id.get(id).expect("pending id exists");
-        for edge in &meta.edges {
-            if edge.edge_type != "depends_on" {
-                continue;
-            }
-            if !pending_ids.contains(&edge.to) {
+        for dep in unresolved_task_dependencies(meta, by_id, include_proposed) {
+            if !pending_ids.contains(&dep) {
                 continue;
             }
             *indegree.entry(id.clone()).or_default() += 1;
-            dependents.entry(edge.to.clone()).or_default().push(id.clone());
+            dependents.entry(dep).or_default().push(id.clone());
         }
     }
 
-    let mut batches = Vec::<PlanBatch>::new();
+    let schedulable = |id: &String| {
+        limits
+            .owner
+            .is_none_or(|owner| by_id.get(id).is_some_and(|m| m.owner == owner))
+    };
+    let mut ready = indegree
+        .iter()
+        .filter(|(id, degree)| **degree == 0 && schedulable(id))
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+    ready.sort();
+
+    let mut layers = Vec::<Vec<String>>::new();
     let mut processed = HashSet::<String>::new();
-    let mut batch_no = 1usize;
-    loop {
-        let mut current = indegree
-            .iter()
-            .filter(|(id, degree)| **degree == 0 && !processed.contains(*id))
-            .map(|(id, _)| id.clone())
-            .collect::<Vec<_>>();
-        if current.is_empty() {
-            break;
-        }
-        current.sort();
+    while !ready.is_empty() {
+        let take = limits.max_parallel.unwrap_or(usize::MAX).min(ready.len());
+        let current = ready.drain(..take).collect::<Vec<_>>();
+        let mut unblocked = Vec::new();
         for id in &current {
             processed.insert(id.clone());
             if let Some(ds) = dependents.get(id) {
                 for dep in ds {
                     if let Some(v) = indegree.get_mut(dep) {
                         *v = v.saturating_sub(1);
+                        if *v == 0 && schedulable(dep) {
+                            unblocked.push(dep.clone());
+                        }
                     }
                 }
             }
         }
-        batches.push(PlanBatch {
-            batch: batch_no,
-            task_ids: current.clone(),
-            tasks: current
-                .iter()
-                .filter_map(|id| by_id.get(id))
-                .map(|meta| TaskSummary {
-                    id: meta.id.clone(),
-                    title: meta.title.clone(),
-                    path: meta.body_md_path.clone(),
-                    status: meta.status.clone(),
-                })
-                .collect(),
-        });
-        batch_no += 1;
+        unblocked.sort();
+        ready.extend(unblocked);
+        layers.push(current);
     }
 
-    let mut blocked_or_cyclic = pending_ids
-        .into_iter()
-        .filter(|id| !processed.contains(id))
+    let mut remaining = pending_ids
+        .iter()
+        .filter(|id| !processed.contains(*id) && schedulable(id))
+        .cloned()
         .collect::<Vec<_>>();
-    blocked_or_cyclic.sort();
+    remaining.sort();
+    (layers, remaining)
+}
 
-    let blocked_or_cyclic_tasks = blocked_or_cyclic
+/// Task weight for critical-path math: an `estimate:<n>` (or `estimate=<n>`)
+/// term when present and positive, otherwise 1.
+pub(super) fn task_estimate(meta: &SpecNodeMeta) -> f64 {
+    meta.terms
         .iter()
-        .filter_map(|id| by_id.get(id))
-        .map(|meta| TaskSummary {
-            id: meta.id.clone(),
-            title: meta.title.clone(),
-            path: meta.body_md_path.clone(),
-            status: meta.status.clone(),
+        .filter_map(|term| {
+            let lower = term.trim().to_ascii_lowercase();
+            let value = lower
+                .strip_prefix("estimate:")
+                .or_else(|| lower.strip_prefix("estimate="))?
+                .trim()
+                .parse::<f64>()
+                .ok()?;
+            (value.is_finite() && value > 0.0).then_some(value)
         })
-        .collect::<Vec<_>>();
+        .next()
+        .unwrap_or(1.0)
+}
 
-    let output = PlanBatchesOutput {
-        batches,
-        blocked_or_cyclic,
-        blocked_or_cyclic_tasks,
-    };
-    match format {
-        PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
-        PlanFormat::Table => print_plan_batches_table(&output),
+fn task_summary(meta: &SpecNodeMeta) -> TaskSummary {
+    TaskSummary {
+        id: meta.id.clone(),
+        title: meta.title.clone(),
+        path: meta.body_md_path.clone(),
+        status: meta.status.clone(),
+        owner: meta.owner.clone(),
+        priority: meta.priority.clone(),
     }
-    Ok(())
+}
+
+/// Position in [`NODE_PRIORITIES`]; unset (or unknown) priorities sort last.
+fn priority_rank(priority: &str) -> usize {
+    NODE_PRIORITIES
+        .iter()
+        .position(|p| *p == priority)
+        .unwrap_or(NODE_PRIORITIES.len())
 }
 
 fn load_meta_by_id() -> Result<HashMap<String, SpecNodeMeta>> {
@@ -193,15 +399,23 @@ fn load_meta_by_id() -> Result<HashMap<String, SpecNodeMeta>> {
     Ok(by_id)
 }
 
+/// Pending task dependencies of `meta`. Only `confirmed` edges block unless
+/// `include_proposed` also counts `proposed` ones; other statuses never do.
 fn unresolved_task_dependencies(
     meta: &SpecNodeMeta,
     by_id: &HashMap<String, SpecNodeMeta>,
+    include_proposed: bool,
 ) -> Vec<String> {
     let mut blocked_by = Vec::new();
     for edge in &meta.edges {
         if edge.edge_type != "depends_on" {
             continue;
         }
+        let blocking = edge.status == "confirmed"
+            || (include_proposed && edge.status == "proposed");
+        if !blocking {
+            continue;
+        }
         let Some(dep) = by_id.get(&edge.to) else {
             continue;
         };
@@ -223,7 +437,7 @@ fn is_task_node(meta: &SpecNodeMeta) -> bool {
     )
 }
 
-fn is_done_status(status: &str) -> bool {
+pub(super) fn is_done_status(status: &str) -> bool {
     matches!(status, "done" | "archived" | "deprecated")
 }
 
@@ -234,8 +448,13 @@ fn print_plan_ready_table(output: &PlanReadyOutput) {
     } else {
         for task in &output.ready {
             println!(
-                "  - {} [{}] {} ({})",
-                task.id, task.status, task.title, task.path
+                "  - {} [{}]{}{} {} ({})",
+                task.id,
+                task.status,
+                priority_label(&task.priority),
+                owner_label(&task.owner),
+                task.title,
+                task.path
             );
         }
     }
@@ -245,9 +464,10 @@ fn print_plan_ready_table(output: &PlanReadyOutput) {
     } else {
         for task in &output.blocked {
             println!(
-                "  - {} [{}] blocked_by={} {} ({})",
+                "  - {} [{}]{} blocked_by={} {} ({})",
                 task.id,
                 task.status,
+                owner_label(&task.owner),
                 task.blocked_by.join(","),
                 task.title,
                 task.path
@@ -256,6 +476,63 @@ fn print_plan_ready_table(output: &PlanReadyOutput) {
     }
 }
 
+/// One row per ready or blocked task; `blocked_by` ids are joined by `;` so
+/// the column stays a single field.
+#[derive(Serialize)]
+struct PlanReadyCsvRow<'a> {
+    id: &'a str,
+    title: &'a str,
+    status: &'a str,
+    owner: &'a str,
+    blocked_by: String,
+}
+
+/// RFC 4180 with CRLF line endings: ready tasks, then blocked ones. The
+/// header is written up front so an empty plan still has one.
+fn print_plan_ready_csv(output: &PlanReadyOutput) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(std::io::stdout().lock());
+    writer.write_record(["id", "title", "status", "owner", "blocked_by"])?;
+    for task in &output.ready {
+        writer.serialize(PlanReadyCsvRow {
+            id: &task.id,
+            title: &task.title,
+            status: &task.status,
+            owner: &task.owner,
+            blocked_by: String::new(),
+        })?;
+    }
+    for task in &output.blocked {
+        writer.serialize(PlanReadyCsvRow {
+            id: &task.id,
+            title: &task.title,
+            status: &task.status,
+            owner: &task.owner,
+            blocked_by: task.blocked_by.join(";"),
+        })?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn owner_label(owner: &str) -> String {
+    if owner.is_empty() {
+        String::new()
+    } else {
+        format!(" owner={owner}")
+    }
+}
+
+fn priority_label(priority: &str) -> String {
+    if priority.is_empty() {
+        String::new()
+    } else {
+        format!(" priority={priority}")
+    }
+}
+
 fn print_plan_batches_table(output: &PlanBatchesOutput) {
     println!("parallel_batches:");
     if output.batches.is_empty() {
@@ -274,3 +551,26 @@ fn print_plan_batches_table(output: &PlanBatchesOutput) {
         }
     }
 }
+
+fn print_plan_critical_path_table(output: &PlanCriticalPathOutput) {
+    println!("critical_path:");
+    if output.path.is_empty() {
+        println!("  (none)");
+    } else {
+        for (idx, step) in output.path.iter().enumerate() {
+            println!(
+                "  {}. {} [{}] weight={} {} ({})",
+                idx + 1,
+                step.id,
+                step.status,
+                step.weight,
+                step.title,
+                step.path
+            );
+        }
+    }
+    println!("length: {} ({} tasks)", output.length, output.task_count);
+    if !output.cyclic.is_empty() {
+        println!("cyclic: {}", output.cyclic.join(", "));
+    }
+}
@@ -1,16 +1,244 @@
 use super::*;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
 pub(super) struct RuntimeConfig {
     pub(super) ask: AskRuntimeConfig,
+    pub(super) search: SearchRuntimeConfig,
+    pub(super) embedding: EmbeddingRuntimeConfig,
+    pub(super) id_allocation: IdAllocation,
+    pub(super) write: WriteRuntimeConfig,
+    pub(super) lint: LintRuntimeConfig,
+    pub(super) audit: AuditRuntimeConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(super) struct AuditRuntimeConfig {
+    /// Append a line to `.foundry/audit.jsonl` for each mutating command.
+    pub(super) enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(super) struct LintRuntimeConfig {
+    /// Minimum rationale length for confirmed edges; `0` only rejects empty ones.
+    pub(super) min_rationale_len: usize,
+    /// Per-rule severity overrides, keyed by lint rule name.
+    pub(super) severity: BTreeMap<String, LintSeverity>,
+    /// Abstraction level per node type for `refines-direction`; merged over
+    /// the built-in levels (lower is more abstract).
+    pub(super) type_levels: BTreeMap<String, u32>,
+    /// Node types allowed to have no edges at all (`orphan` rule).
+    pub(super) orphan_exempt_types: Vec<String>,
+    /// Longest allowed `refines` chain (in edges) for `refine-depth`; unset
+    /// disables the rule.
+    pub(super) max_refine_depth: Option<usize>,
+}
+
+impl Default for LintRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            min_rationale_len: 0,
+            severity: BTreeMap::new(),
+            type_levels: BTreeMap::new(),
+            orphan_exempt_types: vec!["product_goal".to_string()],
+            max_refine_depth: None,
+        }
+    }
+}
+
+/// Errors fail `spec lint` (exit `1`); warnings are reported only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum LintSeverity {
+    Error,
+    Warning,
+}
+
+impl LintSeverity {
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(super) struct WriteRuntimeConfig {
+    pub(super) auto_terms_limit: usize,
+}
+
+impl Default for WriteRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            auto_terms_limit: 10,
+        }
+    }
+}
+
+/// How new `SPC-NNN` ids are picked: `append` continues after the highest
+/// id, `dense` reuses the lowest free number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum IdAllocation {
+    #[default]
+    Append,
+    Dense,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+#[cfg_attr(not(feature = "http-embeddings"), allow(dead_code))]
+pub(super) struct EmbeddingRuntimeConfig {
+    pub(super) provider: String,
+    pub(super) url: Option<String>,
+    pub(super) model: Option<String>,
+    pub(super) api_key_env: Option<String>,
+    pub(super) timeout_secs: u64,
+}
+
+impl Default for EmbeddingRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            provider: "local".to_string(),
+            url: None,
+            model: None,
+            api_key_env: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub(super) struct SearchRuntimeConfig {
+    pub(super) stopwords: bool,
+    pub(super) stemming: bool,
+    pub(super) chunk_target_len: usize,
+    /// Derived from `chunk_target_len` when unset.
+    pub(super) chunk_overlap: Option<usize>,
+    /// Run the semantic side of hybrid queries on a second connection.
+    pub(super) parallel_hybrid: bool,
+    /// RRF constant `k` in `1 / (k + rank)` for hybrid fusion.
+    pub(super) rrf_k: f64,
+    pub(super) lexical_weight: f64,
+    pub(super) semantic_weight: f64,
+    /// FTS5 tokenizer used when `fts_chunks` is (re)created.
+    pub(super) tokenizer: FtsTokenizer,
+    /// Size of local embeddings and of the `vec_chunks` column.
+    pub(super) embedding_dim: usize,
+    /// `PRAGMA wal_autocheckpoint` pages for `search index`; `0` leaves
+    /// only the checkpoint after each commit.
+    pub(super) wal_autocheckpoint: u32,
+}
+
+/// Supported FTS5 `tokenize` arguments; the config value is the argument.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub(super) enum FtsTokenizer {
+    #[default]
+    #[serde(rename = "unicode61")]
+    Unicode61,
+    /// Substring matching; works for CJK text without word boundaries.
+    #[serde(rename = "trigram")]
+    Trigram,
+    /// Keeps `_` inside tokens so identifiers like `user_id` stay whole.
+    #[serde(rename = "unicode61 tokenchars '_'")]
+    Unicode61Identifiers,
+}
+
+impl FtsTokenizer {
+    pub(super) const ALL: [FtsTokenizer; 3] = [
+        FtsTokenizer::Unicode61,
+        FtsTokenizer::Trigram,
+        FtsTokenizer::Unicode61Identifiers,
+    ];
+
+    pub(super) fn spec(self) -> &'static str {
+        match self {
+            FtsTokenizer::Unicode61 => "unicode61",
+            FtsTokenizer::Trigram => "trigram",
+            FtsTokenizer::Unicode61Identifiers => "unicode61 tokenchars '_'",
+        }
+    }
 }
 
-impl Default for RuntimeConfig {
+impl Default for SearchRuntimeConfig {
     fn default() -> Self {
         Self {
-            ask: AskRuntimeConfig::default(),
+            stopwords: false,
+            stemming: false,
+            chunk_target_len: 800,
+            chunk_overlap: None,
+            parallel_hybrid: true,
+            rrf_k: 60.0,
+            lexical_weight: 1.0,
+            semantic_weight: 1.0,
+            tokenizer: FtsTokenizer::default(),
+            embedding_dim: DEFAULT_EMBEDDING_DIM,
+            wal_autocheckpoint: 1000,
+        }
+    }
+}
+
+/// Upper bound on `search.embedding_dim`; `vec0` rejects wider columns.
+const MAX_EMBEDDING_DIM: usize = 8192;
+
+impl SearchRuntimeConfig {
+    /// `stopwords`/`stemming` as recorded in `index_meta` (`ranking_tokens`);
+    /// local embeddings are built from these tokens, so an index is only
+    /// consistent with query vectors made under the same settings.
+    pub(super) fn ranking_token_settings(&self) -> String {
+        let on_off = |flag: bool| if flag { "on" } else { "off" };
+        format!(
+            "stopwords={},stemming={}",
+            on_off(self.stopwords),
+            on_off(self.stemming)
+        )
+    }
+
+    /// Validated `(target_len, overlap)` used to split bodies into chunks.
+    pub(super) fn chunk_settings(&self) -> Result<(usize, usize)> {
+        let target_len = self.chunk_target_len;
+        if target_len == 0 {
+            anyhow::bail!("search.chunk_target_len must be positive");
+        }
+        let overlap = match self.chunk_overlap {
+            Some(0) => anyhow::bail!("search.chunk_overlap must be positive"),
+            Some(overlap) => overlap,
+            None => default_chunk_overlap(target_len),
+        };
+        if overlap >= target_len {
+            anyhow::bail!(
+                "search.chunk_overlap ({overlap}) must be smaller than search.chunk_target_len ({target_len})"
+            );
+        }
+        Ok((target_len, overlap))
+    }
+
+    /// Validated `search.embedding_dim`.
+    pub(super) fn embedding_dim(&self) -> Result<usize> {
+        if !(1..=MAX_EMBEDDING_DIM).contains(&self.embedding_dim) {
+            anyhow::bail!("search.embedding_dim must be between 1 and {MAX_EMBEDDING_DIM}");
+        }
+        Ok(self.embedding_dim)
+    }
+
+    pub(super) fn validate_fusion(&self) -> Result<()> {
+        if !(self.rrf_k.is_finite() && self.rrf_k >= 0.0) {
+            anyhow::bail!("search.rrf_k must be a non-negative number");
+        }
+        for (name, weight) in [
+            ("lexical_weight", self.lexical_weight),
+            ("semantic_weight", self.semantic_weight),
+        ] {
+            if !(weight.is_finite() && weight >= 0.0) {
+                anyhow::bail!("search.{name} must be a non-negative number");
+            }
         }
+        Ok(())
     }
 }
 
@@ -19,6 +247,7 @@ impl Default for RuntimeConfig {
 pub(super) struct AskRuntimeConfig {
     pub(super) neighbor_limit: usize,
     pub(super) snippet_count_in_answer: usize,
+    pub(super) max_context_chars: usize,
     pub(super) edge_weight: AskEdgeWeightConfig,
 }
 
@@ -27,6 +256,7 @@ impl Default for AskRuntimeConfig {
         Self {
             neighbor_limit: 5,
             snippet_count_in_answer: 2,
+            max_context_chars: 8000,
             edge_weight: AskEdgeWeightConfig::default(),
         }
     }
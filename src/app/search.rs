@@ -6,14 +6,89 @@ pub(super) struct SearchHit {
     pub(super) title: String,
     pub(super) path: String,
     pub(super) score: f64,
+    /// `score` rescaled to `[0,1]` within the result set, filled only by
+    /// `search query --normalize`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) normalized_score: Option<f64>,
     pub(super) matched_terms: Vec<String>,
     pub(super) snippet: String,
+    /// Byte ranges `[start, end)` of matched query tokens within `snippet`.
+    pub(super) highlights: Vec<[usize; 2]>,
+    /// Found by the typo-tolerant fallback rather than an FTS match.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(super) fuzzy: bool,
+    /// Score breakdown, kept only for `search query --explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) explanation: Option<SearchExplanation>,
+    /// `chunks.ord` of the chunk the snippet came from.
+    #[serde(skip)]
+    pub(super) chunk_ord: Option<i64>,
+    /// Neighboring chunks of the same node, filled only by `search query --context`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) context: Vec<SearchContextChunk>,
+    /// `nodes.updated_at`: when the node's content was last (re)indexed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) updated_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct SearchContextChunk {
+    ord: i64,
+    /// This is the chunk the hit's snippet was taken from.
+    matched: bool,
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct SearchExplanation {
+    /// `-bm25` of the best-matching chunk; `None` unless FTS matched.
+    bm25_base: Option<f64>,
+    /// Weighted `ranking_boost` parts added to `bm25_base`.
+    boost: Option<RankingBoost>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hybrid: Option<HybridExplanation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rerank: Option<RerankExplanation>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub(super) struct RankingBoost {
+    title_overlap: f64,
+    term_overlap: f64,
+    exact_phrase: f64,
+}
+
+impl RankingBoost {
+    fn total(&self) -> f64 {
+        self.title_overlap + self.term_overlap + self.exact_phrase
+    }
+}
+
+/// Where a `rerank` hit stood in the lexical candidate list; its `score` is
+/// the semantic similarity it was reordered by.
+#[derive(Debug, Clone, Copy, Serialize)]
+struct RerankExplanation {
+    lexical_rank: usize,
+    lexical_score: f64,
+}
+
+/// 1-based list positions and their RRF terms; `strength` is the small
+/// raw-score tie-breaker on top.
+#[derive(Debug, Clone, Serialize)]
+struct HybridExplanation {
+    lexical_rank: Option<usize>,
+    lexical_rrf: f64,
+    semantic_rank: Option<usize>,
+    semantic_rrf: f64,
+    strength: f64,
 }
 
 #[derive(Debug, Serialize)]
-struct SearchQueryOutput {
+pub(super) struct SearchQueryOutput {
     query: String,
     mode: String,
+    /// The index has no nodes, typically because `search index` never ran.
+    index_empty: bool,
     hits: Vec<SearchHit>,
 }
 
@@ -31,7 +106,23 @@ struct SearchCandidate {
     path: String,
     terms: Vec<String>,
     snippet: String,
+    highlights: Vec<[usize; 2]>,
     lexical_score: f64,
+    fuzzy: bool,
+    bm25_base: Option<f64>,
+    boost: Option<RankingBoost>,
+    chunk_ord: Option<i64>,
+}
+
+impl SearchCandidate {
+    fn explanation(&self) -> SearchExplanation {
+        SearchExplanation {
+            bm25_base: self.bm25_base,
+            boost: self.boost,
+            hybrid: None,
+            rerank: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,29 +133,161 @@ struct SemanticCandidate {
     terms: Vec<String>,
     snippet: String,
     semantic_score: f64,
+    chunk_ord: i64,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct SearchFilter {
+    pub(super) node_types: Vec<String>,
+    pub(super) statuses: Vec<String>,
+    /// Keep only nodes whose `updated_at` is later than this (unix seconds).
+    pub(super) modified_after: Option<i64>,
+    /// What lexical matching runs against; `Body` is the FTS chunk index.
+    pub(super) field: SearchField,
+}
+
+impl SearchFilter {
+    fn validate(&self) -> Result<()> {
+        for node_type in &self.node_types {
+            if !NODE_TYPES.contains(&node_type.as_str()) {
+                anyhow::bail!("invalid --node-type filter: {node_type}");
+            }
+        }
+        for status in &self.statuses {
+            if !NODE_STATUSES.contains(&status.as_str()) {
+                anyhow::bail!("invalid --status filter: {status}");
+            }
+        }
+        Ok(())
+    }
+
+    fn node_types_json(&self) -> Option<String> {
+        filter_values_json(&self.node_types)
+    }
+
+    fn statuses_json(&self) -> Option<String> {
+        filter_values_json(&self.statuses)
+    }
+}
+
+fn filter_values_json(values: &[String]) -> Option<String> {
+    if values.is_empty() {
+        None
+    } else {
+        serde_json::to_string(values).ok()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SearchDoctorOutput {
+    ok: bool,
+    issue_count: usize,
+    issues: Vec<String>,
 }
 
-pub(super) fn run_search(search: SearchCommand) -> Result<()> {
+pub(super) fn run_search(search: SearchCommand) -> Result<i32> {
+    let db = search.db.as_deref().map(Path::new);
     match search.command {
-        SearchSubcommand::Index(args) => run_search_index(args.rebuild),
-        SearchSubcommand::Query(args) => run_search_query(&args),
-        SearchSubcommand::Doctor => run_search_doctor(),
+        SearchSubcommand::Index(args) => {
+            run_search_index(&args, db, &load_runtime_config())?;
+            Ok(0)
+        }
+        SearchSubcommand::Query(args) => {
+            run_search_query(&args, db)?;
+            Ok(0)
+        }
+        SearchSubcommand::Doctor(args) => run_search_doctor(&args, db),
+        SearchSubcommand::ReindexVectors => {
+            run_search_reindex_vectors(db, &load_runtime_config())?;
+            Ok(0)
+        }
     }
 }
 
-pub(super) fn run_search_index(rebuild: bool) -> Result<()> {
+pub(super) fn run_search_index(
+    args: &SearchIndexArgs,
+    db: Option<&Path>,
+    runtime: &RuntimeConfig,
+) -> Result<()> {
+    let (rebuild, since) = (args.rebuild, args.since.as_deref());
     let spec_root = Path::new("spec");
     if !spec_root.exists() {
         println!("search index: spec/ directory not found");
         return Ok(());
     }
+    let (chunk_target_len, chunk_overlap) = runtime.search.chunk_settings()?;
     let mut lint = LintState::default();
     let metas = load_all_meta(spec_root, &mut lint)?;
-    let mut conn = open_search_db()?;
-    ensure_search_schema(&mut conn)?;
-    let vec_available = ensure_sqlite_vec_ready(&conn)?;
+    let changed_paths = since.and_then(|git_ref| match git_changed_paths(git_ref) {
+        Ok(paths) => Some(paths),
+        Err(err) => {
+            log::warn!("--since {git_ref} unavailable ({err:#}); running full index");
+            None
+        }
+    });
+    let configured_dim = runtime.search.embedding_dim()?;
+    let mut conn = open_search_db(db)?;
+    let tokenizer = runtime.search.tokenizer;
+    ensure_search_schema(&mut conn, tokenizer)?;
+    conn.execute_batch(&format!(
+        "PRAGMA wal_autocheckpoint={};",
+        runtime.search.wal_autocheckpoint
+    ))?;
+    let indexed_nodes: i64 = conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))?;
+    // Like the tokenizer, a new embedding size needs every vector rewritten.
+    let embedding_dim = match indexed_embedding_dim(&conn)? {
+        Some(indexed) if !rebuild && indexed_nodes > 0 => indexed,
+        _ => configured_dim,
+    };
+    if embedding_dim != configured_dim {
+        log::warn!(
+            "index was built with embedding dim {embedding_dim}, search.embedding_dim is {configured_dim}; run `foundry spec search index --rebuild`"
+        );
+    }
+    let vec_available = ensure_sqlite_vec_ready(&conn, embedding_dim)?;
+    let mut embedder = Embedder::from_config(runtime).with_local_dim(embedding_dim);
+    // The tokenizer is fixed when the FTS table is created, so a new one
+    // takes effect only when the table can be refilled from scratch.
+    let recreate_fts = indexed_fts_tokenizer(&conn)? != Some(tokenizer);
+    if recreate_fts && !rebuild && indexed_nodes > 0 {
+        log::warn!(
+            "index was built with a different FTS tokenizer than search.tokenizer ({}); run `foundry spec search index --rebuild`",
+            tokenizer.spec()
+        );
+    }
+    let chunk_settings = format!("{chunk_target_len}/{chunk_overlap}");
+    let indexed_settings = read_index_meta(&conn, "chunk_settings")?;
+    if !rebuild
+        && let Some(indexed) = &indexed_settings
+        && *indexed != chunk_settings
+    {
+        log::warn!(
+            "index was built with chunk target/overlap {indexed}, config is now {chunk_settings}; run `foundry spec search index --rebuild`"
+        );
+    }
+    let ranking_settings = runtime.search.ranking_token_settings();
+    let indexed_ranking = indexed_ranking_token_settings(&conn)?;
+    if !rebuild && indexed_nodes > 0 && indexed_ranking != ranking_settings {
+        log::warn!(
+            "index was built with {indexed_ranking}, config is now {ranking_settings}; run `foundry spec search index --rebuild`"
+        );
+    }
+    // Remembered by the index so incremental runs keep adding the chunk
+    // until a `--rebuild` without the flag drops it.
+    let title_terms_indexed =
+        read_index_meta(&conn, "title_terms_chunk")?.as_deref() == Some("1");
+    let include_title_terms = args.include_title_terms || (title_terms_indexed && !rebuild);
+    if include_title_terms && !title_terms_indexed && !rebuild && indexed_nodes > 0 {
+        log::warn!(
+            "unchanged nodes get a title/terms chunk only after `foundry spec search index --include-title-terms --rebuild`"
+        );
+    }
     let tx = conn.transaction()?;
 
+    if recreate_fts && (rebuild || indexed_nodes == 0) {
+        tx.execute_batch("DROP TABLE fts_chunks;")?;
+        tx.execute_batch(&fts_table_sql(tokenizer))?;
+    }
     if rebuild {
         tx.execute("DELETE FROM fts_chunks;", [])?;
         tx.execute("DELETE FROM chunks;", [])?;
@@ -77,9 +300,15 @@ pub(super) fn run_search_index(rebuild: bool) -> Result<()> {
 
     let mut summary = SearchIndexSummary::default();
     let mut current_ids = HashSet::new();
+    let mut progress = IndexProgress::new(metas.len(), log::log_enabled!(log::Level::Info));
 
     for (meta_path, meta) in metas {
+        progress.tick(&meta.id);
         current_ids.insert(meta.id.clone());
+        let changed = changed_paths.as_ref().map(|paths| {
+            paths.contains(&normalize_path(&meta_path).to_string_lossy().to_string())
+                || paths.contains(&meta.body_md_path)
+        });
         let existing_hash: Option<String> = tx
             .query_row(
                 "SELECT hash FROM nodes WHERE id = ?1",
@@ -87,14 +316,25 @@ pub(super) fn run_search_index(rebuild: bool) -> Result<()> {
                 |row| row.get(0),
             )
             .optional()?;
-        if !rebuild && existing_hash.as_deref() == Some(meta.hash.as_str()) {
+        // Nodes untouched since the ref are trusted as-is once indexed.
+        let unchanged = match changed {
+            Some(changed) => !changed && existing_hash.is_some(),
+            None => !rebuild && existing_hash.as_deref() == Some(meta.hash.as_str()),
+        };
+        // Skipped rows still pick up type/status edits, which also fills in
+        // rows from indexes that predate the filter columns.
+        if unchanged {
+            tx.execute(
+                "UPDATE nodes SET node_type = ?2, status = ?3 WHERE id = ?1 AND (node_type != ?2 OR status != ?3)",
+                params![meta.id, meta.node_type, meta.status],
+            )?;
             summary.skipped += 1;
             continue;
         }
 
         let body = fs::read_to_string(&meta.body_md_path)
             .with_context(|| format!("failed reading {}", meta.body_md_path))?;
-        let chunks = split_into_chunks(&body, 800);
+        let chunks = split_into_chunks(&body, chunk_target_len, chunk_overlap);
         let terms_json = serde_json::to_string(&meta.terms)?;
         let md_path = meta.body_md_path.clone();
         let now = unix_ts();
@@ -106,9 +346,9 @@ pub(super) fn run_search_index(rebuild: bool) -> Result<()> {
             tx.execute("DELETE FROM vec_chunks WHERE chunk_id LIKE ?1", params![format!("{}:%", meta.id)])?;
         }
         tx.execute(
-            "INSERT INTO nodes (id, title, md_path, meta_path, hash, terms_json, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-             ON CONFLICT(id) DO UPDATE SET title=excluded.title, md_path=excluded.md_path, meta_path=excluded.meta_path, hash=excluded.hash, terms_json=excluded.terms_json, updated_at=excluded.updated_at",
-            params![meta.id, meta.title, md_path, meta_path.to_string_lossy().to_string(), meta.hash, terms_json, now],
+            "INSERT INTO nodes (id, title, md_path, meta_path, hash, terms_json, updated_at, node_type, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(id) DO UPDATE SET title=excluded.title, md_path=excluded.md_path, meta_path=excluded.meta_path, hash=excluded.hash, terms_json=excluded.terms_json, updated_at=excluded.updated_at, node_type=excluded.node_type, status=excluded.status",
+            params![meta.id, meta.title, md_path, meta_path.to_string_lossy().to_string(), meta.hash, terms_json, now, meta.node_type, meta.status],
         )?;
 
         for (idx, chunk) in chunks.iter().enumerate() {
@@ -122,23 +362,33 @@ pub(super) fn run_search_index(rebuild: bool) -> Result<()> {
                 "INSERT INTO fts_chunks (chunk_id, node_id, text) VALUES (?1, ?2, ?3)",
                 params![format!("{}:{idx}", meta.id), meta.id, chunk],
             )?;
-            let embedding = semantic_vector(chunk);
+            let (model, embedding) = embedder.embed(chunk);
             tx.execute(
                 "INSERT INTO chunk_vectors (chunk_id, model, dim, embedding) VALUES (?1, ?2, ?3, ?4)",
                 params![
                     format!("{}:{idx}", meta.id),
-                    "local-hash-ngrams-v1",
+                    model,
                     embedding.len() as i64,
                     vector_to_blob(&embedding)
                 ],
             )?;
-            if vec_available {
+            if vec_available && model == LOCAL_EMBEDDING_MODEL {
                 tx.execute(
                     "INSERT INTO vec_chunks (chunk_id, embedding) VALUES (?1, ?2)",
                     params![format!("{}:{idx}", meta.id), vector_to_json(&embedding)],
                 )?;
             }
         }
+        if include_title_terms {
+            tx.execute(
+                "INSERT INTO fts_chunks (chunk_id, node_id, text) VALUES (?1, ?2, ?3)",
+                params![
+                    format!("{}:{TITLE_TERMS_CHUNK}", meta.id),
+                    meta.id,
+                    title_terms_text(&meta)
+                ],
+            )?;
+        }
         summary.indexed += 1;
     }
 
@@ -164,34 +414,270 @@ pub(super) fn run_search_index(rebuild: bool) -> Result<()> {
         summary.deleted += 1;
     }
 
+    tx.execute(
+        "INSERT INTO index_meta (key, value) VALUES ('embedding_dim', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![embedding_dim.to_string()],
+    )?;
+    tx.execute(
+        "INSERT INTO index_meta (key, value) VALUES ('title_terms_chunk', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![if include_title_terms { "1" } else { "0" }],
+    )?;
+    // Like the chunk size below: only vectors embedded from scratch all
+    // share the configured ranking tokens.
+    if rebuild || indexed_nodes == 0 {
+        set_index_meta(&tx, "ranking_tokens", &ranking_settings)?;
+    }
+    // Only a full rebuild makes every chunk match the configured size.
+    if rebuild || indexed_settings.is_none() {
+        tx.execute(
+            "INSERT INTO index_meta (key, value) VALUES ('chunk_settings', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![chunk_settings],
+        )?;
+    }
     tx.commit()?;
+    progress.finish();
+    // VACUUM cannot run inside a transaction; the checkpoint then folds the
+    // rewritten pages back into the main file and truncates the WAL.
+    if args.vacuum {
+        conn.execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")?;
+    } else {
+        // Lets the next writer restart the WAL from the top instead of
+        // appending, without blocking concurrent readers.
+        conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE);")?;
+    }
     println!(
-        "search index summary: indexed={} skipped={} deleted={}",
-        summary.indexed, summary.skipped, summary.deleted
+        "search index summary: indexed={} skipped={} deleted={} db_bytes={}",
+        summary.indexed,
+        summary.skipped,
+        summary.deleted,
+        search_db_size(&search_db_path(db))
     );
     Ok(())
 }
 
-pub(super) fn run_search_query(args: &SearchQueryArgs) -> Result<()> {
-    let conn = open_search_db()?;
+/// Bytes on disk of the index file plus its WAL, if any.
+fn search_db_size(db_path: &Path) -> u64 {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    [db_path.to_path_buf(), PathBuf::from(wal)]
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Nodes between two progress lines of `search index`.
+const INDEX_PROGRESS_EVERY: usize = 25;
+
+/// `search index` progress on stderr: every [`INDEX_PROGRESS_EVERY`] nodes
+/// and on the last one. A terminal gets one line rewritten in place; pipes
+/// and logs get one line per update.
+struct IndexProgress {
+    total: usize,
+    done: usize,
+    enabled: bool,
+    in_place: bool,
+}
+
+impl IndexProgress {
+    fn new(total: usize, enabled: bool) -> Self {
+        use std::io::IsTerminal;
+
+        Self {
+            total,
+            done: 0,
+            enabled,
+            in_place: std::io::stderr().is_terminal(),
+        }
+    }
+
+    fn tick(&mut self, id: &str) {
+        self.done += 1;
+        if !self.enabled
+            || (!self.done.is_multiple_of(INDEX_PROGRESS_EVERY) && self.done != self.total)
+        {
+            return;
+        }
+        let line = format!("search index: progress {}/{} {id}", self.done, self.total);
+        if self.in_place {
+            eprint!("\r{line}\x1b[K");
+        } else {
+            eprintln!("{line}");
+        }
+    }
+
+    fn finish(&self) {
+        if self.enabled && self.in_place && self.total > 0 {
+            eprintln!();
+        }
+    }
+}
+
+/// Re-embeds every indexed chunk with the configured provider, leaving
+/// `chunks` and `fts_chunks` untouched.
+pub(super) fn run_search_reindex_vectors(db: Option<&Path>, runtime: &RuntimeConfig) -> Result<()> {
+    let embedding_dim = runtime.search.embedding_dim()?;
+    let mut embedder = Embedder::from_config(runtime);
+    let mut conn = open_search_db(db)?;
+    ensure_search_schema(&mut conn, runtime.search.tokenizer)?;
+    let vec_available = ensure_sqlite_vec_ready(&conn, embedding_dim)?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO index_meta (key, value) VALUES ('embedding_dim', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![embedding_dim.to_string()],
+    )?;
+    set_index_meta(&tx, "ranking_tokens", &runtime.search.ranking_token_settings())?;
+
+    let chunks = {
+        let mut stmt = tx.prepare("SELECT chunk_id, text FROM chunks ORDER BY chunk_id")?;
+        stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    tx.execute("DELETE FROM chunk_vectors;", [])?;
+    if vec_available {
+        tx.execute("DELETE FROM vec_chunks;", [])?;
+    }
+    let mut models = BTreeMap::<String, usize>::new();
+    for (chunk_id, text) in &chunks {
+        let (model, embedding) = embedder.embed(text);
+        tx.execute(
+            "INSERT INTO chunk_vectors (chunk_id, model, dim, embedding) VALUES (?1, ?2, ?3, ?4)",
+            params![chunk_id, model, embedding.len() as i64, vector_to_blob(&embedding)],
+        )?;
+        if vec_available && model == LOCAL_EMBEDDING_MODEL {
+            tx.execute(
+                "INSERT INTO vec_chunks (chunk_id, embedding) VALUES (?1, ?2)",
+                params![chunk_id, vector_to_json(&embedding)],
+            )?;
+        }
+        *models.entry(model).or_default() += 1;
+    }
+    tx.commit()?;
+
+    let models = models
+        .iter()
+        .map(|(model, count)| format!("{model}:{count}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    println!(
+        "search reindex-vectors summary: chunks={} models={}",
+        chunks.len(),
+        if models.is_empty() { "-" } else { &models }
+    );
+    Ok(())
+}
+
+/// Spec/task files changed in the working tree relative to `git_ref`,
+/// including untracked files, as cwd-relative paths.
+fn git_changed_paths(git_ref: &str) -> Result<HashSet<String>> {
+    let git = |args: &[&str]| -> Result<Vec<String>> {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .output()
+            .context("failed to run git")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    };
+    let mut paths = git(&[
+        "diff", "--name-only", "--relative", git_ref, "--", "spec", "tasks",
+    ])?;
+    paths.extend(git(&[
+        "ls-files", "--others", "--exclude-standard", "--", "spec", "tasks",
+    ])?);
+    Ok(paths.into_iter().collect())
+}
+
+pub(super) fn run_search_query(args: &SearchQueryArgs, db: Option<&Path>) -> Result<()> {
+    let output = build_search_query_output(args, db)?;
+    if output.index_empty {
+        // Table output is for people: fail loudly. Machine formats still get
+        // parseable output (`index_empty: true` in JSON) plus a warning.
+        if args.format == SearchFormat::Table {
+            anyhow::bail!("{EMPTY_INDEX_HINT}");
+        }
+        log::warn!("{EMPTY_INDEX_HINT}");
+    }
+    match args.format {
+        SearchFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        SearchFormat::Ndjson => print_search_ndjson(&output)?,
+        SearchFormat::Table => print_search_table(&output),
+    }
+    Ok(())
+}
+
+const EMPTY_INDEX_HINT: &str =
+    "search index is empty; run `foundry spec search index` before querying";
+
+pub(super) fn build_search_query_output(
+    args: &SearchQueryArgs,
+    db: Option<&Path>,
+) -> Result<SearchQueryOutput> {
+    let conn = open_search_db(db)?;
     ensure_search_schema_readonly(&conn)?;
-    let hits = build_search_hits(&conn, &args.query, args.top_k, args.mode)?;
+    let indexed_nodes: i64 = conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))?;
+    let modified_after = match &args.modified_after {
+        Some(raw) => Some(parse_timestamp(raw).with_context(|| {
+            format!("invalid --modified-after: {raw} (expected unix seconds or RFC 3339)")
+        })?),
+        None => None,
+    };
+    let filter = SearchFilter {
+        node_types: args.node_types.clone(),
+        statuses: args.statuses.clone(),
+        modified_after,
+        field: args.field,
+    };
+    let config = load_runtime_config();
+    if args.mode != SearchMode::Lexical && indexed_nodes > 0 {
+        let configured = config.search.ranking_token_settings();
+        let indexed = indexed_ranking_token_settings(&conn)?;
+        if indexed != configured {
+            log::warn!(
+                "index vectors were built with {indexed}, config is now {configured}; run `foundry spec search index --rebuild`"
+            );
+        }
+    }
+    let mut hits = build_search_hits(&conn, &args.query, args.top_k, args.mode, &filter, &config)?;
+    if !args.explain {
+        for hit in &mut hits {
+            hit.explanation = None;
+        }
+    }
+    if args.context > 0 {
+        for hit in &mut hits {
+            hit.context = collect_context_chunks(&conn, hit, args.context)?;
+        }
+    }
+    if let Some(normalization) = args.normalize {
+        normalize_hit_scores(&mut hits, normalization);
+    }
 
     let mode = match args.mode {
         SearchMode::Lexical => "lexical",
         SearchMode::Hybrid => "hybrid",
+        SearchMode::Rerank => "rerank",
     }
     .to_string();
-    let output = SearchQueryOutput {
+    Ok(SearchQueryOutput {
         query: args.query.clone(),
         mode,
+        index_empty: indexed_nodes == 0,
         hits,
-    };
-    match args.format {
-        SearchFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
-        SearchFormat::Table => print_search_table(&output),
-    }
-    Ok(())
+    })
 }
 
 pub(super) fn build_search_hits(
@@ -199,110 +685,630 @@ pub(super) fn build_search_hits(
     query: &str,
     top_k: usize,
     mode: SearchMode,
+    filter: &SearchFilter,
+    runtime: &RuntimeConfig,
 ) -> Result<Vec<SearchHit>> {
+    let config = &runtime.search;
     let normalized = normalize_query_for_fts(query);
     if normalized.trim().is_empty() {
         anyhow::bail!("query is empty after normalization");
     }
+    filter.validate()?;
 
-    let lexical = collect_lexical_candidates(conn, query, top_k.max(1) * 8)?;
-    let hits = match mode {
-        SearchMode::Lexical => lexical
-            .into_iter()
-            .take(top_k)
-            .map(|c| SearchHit {
+    let lexical_limit = top_k.max(1) * 8;
+    let mut hits = match mode {
+        SearchMode::Lexical => {
+            collect_lexical_candidates(conn, query, lexical_limit, filter, config)?
+                .into_iter()
+                .take(top_k)
+                .map(|c| SearchHit {
+                    explanation: Some(c.explanation()),
+                    id: c.id,
+                    title: c.title,
+                    path: c.path,
+                    score: c.lexical_score,
+                    normalized_score: None,
+                    matched_terms: matched_terms(query, &c.terms, config),
+                    snippet: c.snippet,
+                    highlights: c.highlights,
+                    fuzzy: c.fuzzy,
+                    chunk_ord: c.chunk_ord,
+                    context: Vec::new(),
+                    updated_at: None,
+                })
+                .collect::<Vec<_>>()
+        }
+        SearchMode::Hybrid => {
+            if filter.field != SearchField::Body {
+                anyhow::bail!("--field title|terms cannot be combined with --mode hybrid");
+            }
+            config.validate_fusion()?;
+            let semantic_side = &|conn: &Connection| {
+                let (model, query_vec) = query_embedder(conn, runtime)?.embed(query);
+                collect_semantic_candidates(conn, &query_vec, &model, filter)
+            };
+            let reader = if config.parallel_hybrid {
+                open_parallel_reader(conn)
+            } else {
+                None
+            };
+            let (lexical, semantic) = match reader {
+                Some(reader) => std::thread::scope(|scope| {
+                    let semantic = scope.spawn(move || semantic_side(&reader));
+                    let lexical =
+                        collect_lexical_candidates(conn, query, lexical_limit, filter, config);
+                    // A reader that opened but cannot query (e.g. no write
+                    // access to the WAL index) falls back to the main connection.
+                    let semantic = match semantic.join() {
+                        Ok(Ok(candidates)) => Ok(candidates),
+                        _ => semantic_side(conn),
+                    };
+                    (lexical, semantic)
+                }),
+                None => (
+                    collect_lexical_candidates(conn, query, lexical_limit, filter, config),
+                    semantic_side(conn),
+                ),
+            };
+            merge_hybrid_results(query, lexical?, semantic?, top_k, config)
+        }
+        SearchMode::Rerank => {
+            let lexical = collect_lexical_candidates(conn, query, lexical_limit, filter, config)?;
+            let (model, query_vec) = query_embedder(conn, runtime)?.embed(query);
+            let ids = lexical.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+            let similarity = best_chunk_similarities(conn, &ids, &query_vec, &model)?;
+            rerank_lexical_results(query, lexical, &similarity, top_k, config)
+        }
+    };
+    let mut stmt = conn.prepare_cached("SELECT updated_at FROM nodes WHERE id = ?1")?;
+    for hit in &mut hits {
+        hit.updated_at = stmt
+            .query_row(params![hit.id], |row| row.get(0))
+            .optional()?;
+    }
+    Ok(hits)
+}
+
+/// Best cosine similarity between `query_vec` and any stored `model` chunk
+/// embedding of each node in `ids`; nodes without vectors are absent.
+fn best_chunk_similarities(
+    conn: &Connection,
+    ids: &[String],
+    query_vec: &[f64],
+    model: &str,
+) -> Result<HashMap<String, f64>> {
+    let mut stmt = conn.prepare(
+        "
+        SELECT c.node_id, cv.embedding
+        FROM chunk_vectors cv
+        JOIN chunks c ON c.chunk_id = cv.chunk_id
+        WHERE cv.model = ?1
+          AND c.node_id IN (SELECT value FROM json_each(?2))
+        ",
+    )?;
+    let mut rows = stmt.query(params![model, serde_json::to_string(ids)?])?;
+    let mut best = HashMap::<String, f64>::new();
+    while let Some(row) = rows.next()? {
+        let id: String = row.get(0)?;
+        let embedding_blob: Vec<u8> = row.get(1)?;
+        let chunk_vec = blob_to_vector(&embedding_blob)?;
+        if chunk_vec.is_empty() {
+            continue;
+        }
+        let score = cosine_similarity(query_vec, &chunk_vec);
+        let entry = best.entry(id).or_insert(score);
+        *entry = entry.max(score);
+    }
+    Ok(best)
+}
+
+/// Lexical candidates reordered by semantic similarity (`0` without stored
+/// vectors); ties keep their lexical order.
+fn rerank_lexical_results(
+    query: &str,
+    lexical: Vec<SearchCandidate>,
+    similarity: &HashMap<String, f64>,
+    top_k: usize,
+    config: &SearchRuntimeConfig,
+) -> Vec<SearchHit> {
+    let mut hits = lexical
+        .into_iter()
+        .enumerate()
+        .map(|(idx, c)| {
+            let mut explanation = c.explanation();
+            explanation.rerank = Some(RerankExplanation {
+                lexical_rank: idx + 1,
+                lexical_score: c.lexical_score,
+            });
+            SearchHit {
+                explanation: Some(explanation),
+                score: similarity.get(&c.id).copied().unwrap_or(0.0),
+                normalized_score: None,
+                matched_terms: matched_terms(query, &c.terms, config),
                 id: c.id,
                 title: c.title,
                 path: c.path,
-                score: c.lexical_score,
-                matched_terms: matched_terms(query, &c.terms),
                 snippet: c.snippet,
-            })
-            .collect::<Vec<_>>(),
-        SearchMode::Hybrid => {
-            let semantic = collect_semantic_candidates(conn, query)?;
-            merge_hybrid_results(query, lexical, semantic, top_k)
-        }
+                highlights: c.highlights,
+                fuzzy: c.fuzzy,
+                chunk_ord: c.chunk_ord,
+                context: Vec::new(),
+                updated_at: None,
+            }
+        })
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(top_k);
+    hits
+}
+
+/// Rescales hit scores into `[0,1]` relative to the rest of the result set.
+/// Min-max maps the best hit to `1.0` (every hit when all scores tie);
+/// softmax yields weights that sum to `1.0`.
+pub(super) fn normalize_hit_scores(hits: &mut [SearchHit], normalization: ScoreNormalization) {
+    let max = hits
+        .iter()
+        .map(|h| h.score)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let min = hits.iter().map(|h| h.score).fold(f64::INFINITY, f64::min);
+    let exp_sum = hits.iter().map(|h| (h.score - max).exp()).sum::<f64>();
+    for hit in hits.iter_mut() {
+        let normalized = match normalization {
+            ScoreNormalization::Minmax if max > min => (hit.score - min) / (max - min),
+            ScoreNormalization::Minmax => 1.0,
+            ScoreNormalization::Softmax => (hit.score - max).exp() / exp_sum,
+        };
+        hit.normalized_score = Some(normalized);
+    }
+}
+
+/// Upper bound on the characters of context returned per hit; the nearest
+/// neighbors are kept first so a few huge chunks cannot bloat the output.
+const CONTEXT_MAX_CHARS: usize = 4000;
+
+/// The matched chunk plus up to `radius` chunks before and after it (by
+/// `chunks.ord`) for `search query --context`, in document order.
+fn collect_context_chunks(
+    conn: &Connection,
+    hit: &SearchHit,
+    radius: usize,
+) -> Result<Vec<SearchContextChunk>> {
+    let Some(center) = hit.chunk_ord else {
+        return Ok(Vec::new());
     };
-    Ok(hits)
+    let radius = radius as i64;
+    let mut stmt = conn.prepare(
+        "SELECT ord, text FROM chunks WHERE node_id = ?1 AND ord BETWEEN ?2 AND ?3 ORDER BY ord",
+    )?;
+    let mut chunks = stmt
+        .query_map(params![hit.id, center - radius, center + radius], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    chunks.sort_by_key(|(ord, _)| ((ord - center).abs(), *ord));
+    let mut budget = CONTEXT_MAX_CHARS;
+    let mut out = Vec::new();
+    for (ord, text) in chunks {
+        let len = text.chars().count();
+        if len > budget {
+            break;
+        }
+        budget -= len;
+        out.push(SearchContextChunk {
+            ord,
+            matched: ord == center,
+            text,
+        });
+    }
+    out.sort_by_key(|c| c.ord);
+    Ok(out)
+}
+
+/// Second read-only connection to the same index file for the semantic side
+/// of a hybrid query. Returns `None` (run sequentially) for in-memory
+/// databases or when sqlite-vec is loaded on `conn` but cannot be loaded on
+/// the reader, since that would change which semantic path answers.
+fn open_parallel_reader(conn: &Connection) -> Option<Connection> {
+    let path = conn.path().filter(|p| !p.is_empty())?;
+    let reader = Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .ok()?;
+    if sqlite_vec_available(conn) {
+        let _ = try_load_sqlite_vec_extension(&reader);
+        if !sqlite_vec_available(&reader) {
+            return None;
+        }
+    }
+    Some(reader)
 }
 
 fn collect_lexical_candidates(
     conn: &Connection,
     query: &str,
     limit: usize,
+    filter: &SearchFilter,
+    config: &SearchRuntimeConfig,
 ) -> Result<Vec<SearchCandidate>> {
-    let normalized = normalize_query_for_fts(query);
+    if filter.field != SearchField::Body {
+        return collect_field_candidates(conn, query, limit, filter, config);
+    }
+    let tokenizer = indexed_fts_tokenizer(conn)?.unwrap_or_default();
+    let normalized = fts_match_query(query, tokenizer);
+    // Rank chunks within each node and keep only the best one, so `LIMIT`
+    // counts nodes: a node whose best chunk ranks below many chunks of
+    // another node is still returned, with that chunk's snippet. The
+    // title/terms chunk has no `chunks` row (`ord` is NULL) and is picked
+    // only when no body chunk of the node matched.
     let sql = "
+        WITH matches AS (
+            SELECT
+                fts_chunks.node_id,
+                bm25(fts_chunks) AS bm25_score,
+                snippet(fts_chunks, 2, char(2), char(3), '...', 32) AS snippet,
+                c.ord
+            FROM fts_chunks
+            LEFT JOIN chunks c ON c.chunk_id = fts_chunks.chunk_id
+            WHERE fts_chunks MATCH ?1
+        ),
+        ranked AS (
+            SELECT
+                node_id,
+                bm25_score,
+                snippet,
+                ord,
+                ROW_NUMBER() OVER (
+                    PARTITION BY node_id ORDER BY ord IS NULL, bm25_score ASC, ord ASC
+                ) AS node_rank
+            FROM matches
+        )
         SELECT
             n.id,
             n.title,
             n.md_path,
-            bm25(fts_chunks) AS bm25_score,
-            SUBSTR(c.text, 1, 220) AS snippet,
-            n.terms_json
-        FROM fts_chunks
-        JOIN chunks c ON c.chunk_id = fts_chunks.chunk_id
-        JOIN nodes n ON n.id = fts_chunks.node_id
-        WHERE fts_chunks MATCH ?1
-        ORDER BY bm25_score ASC
+            r.bm25_score,
+            r.snippet,
+            n.terms_json,
+            r.ord
+        FROM ranked r
+        JOIN nodes n ON n.id = r.node_id
+        WHERE r.node_rank = 1
+          AND (?3 IS NULL OR n.node_type IN (SELECT value FROM json_each(?3)))
+          AND (?4 IS NULL OR n.status IN (SELECT value FROM json_each(?4)))
+          AND (?5 IS NULL OR n.updated_at > ?5)
+        ORDER BY r.bm25_score ASC, n.id ASC
         LIMIT ?2
     ";
     let mut stmt = conn.prepare(sql)?;
-    let mut rows = stmt.query(params![normalized, limit as i64])?;
-    let mut by_node = HashMap::<String, SearchCandidate>::new();
+    let mut rows = stmt.query(params![
+        normalized,
+        limit as i64,
+        filter.node_types_json(),
+        filter.statuses_json(),
+        filter.modified_after
+    ])?;
+    let mut out = Vec::new();
     while let Some(row) = rows.next()? {
-        let id: String = row.get(0)?;
         let title: String = row.get(1)?;
-        let path: String = row.get(2)?;
         let bm25_score: f64 = row.get(3)?;
         let snippet: String = row.get(4)?;
         let terms_json: String = row.get(5)?;
         let terms: Vec<String> = serde_json::from_str(&terms_json).unwrap_or_default();
 
         let lexical_base = -bm25_score;
-        let boost = ranking_boost(query, &title, &terms);
-        let score = lexical_base + boost;
-        let candidate = SearchCandidate {
-            id: id.clone(),
+        let boost = ranking_boost_parts(query, &title, &terms, config);
+        let score = lexical_base + boost.total();
+        let (snippet, highlights) = parse_marked_snippet(&snippet.replace('\n', " "));
+        out.push(SearchCandidate {
+            id: row.get(0)?,
             title,
-            path,
+            path: row.get(2)?,
             terms,
-            snippet: snippet.replace('\n', " "),
+            snippet,
+            highlights,
             lexical_score: score,
+            fuzzy: false,
+            bm25_base: Some(lexical_base),
+            boost: Some(boost),
+            chunk_ord: row.get(6)?,
+        });
+    }
+    if out.is_empty() {
+        return collect_fuzzy_candidates(conn, query, limit, filter);
+    }
+    out.sort_by(|a, b| {
+        b.lexical_score
+            .total_cmp(&a.lexical_score)
+            .then(a.id.cmp(&b.id))
+    });
+    Ok(out)
+}
+
+/// `--field title|terms`: scans `nodes` and scores each node by the title
+/// (overlap plus exact phrase) or term part of [`ranking_boost_parts`] alone,
+/// so body text never matches. The snippet is the title or the matched terms.
+fn collect_field_candidates(
+    conn: &Connection,
+    query: &str,
+    limit: usize,
+    filter: &SearchFilter,
+    config: &SearchRuntimeConfig,
+) -> Result<Vec<SearchCandidate>> {
+    let sql = "
+        SELECT n.id, n.title, n.md_path, n.terms_json
+        FROM nodes n
+        WHERE (?1 IS NULL OR n.node_type IN (SELECT value FROM json_each(?1)))
+          AND (?2 IS NULL OR n.status IN (SELECT value FROM json_each(?2)))
+          AND (?3 IS NULL OR n.updated_at > ?3)
+    ";
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query(params![
+        filter.node_types_json(),
+        filter.statuses_json(),
+        filter.modified_after
+    ])?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let title: String = row.get(1)?;
+        let terms_json: String = row.get(3)?;
+        let terms: Vec<String> = serde_json::from_str(&terms_json).unwrap_or_default();
+        let mut boost = ranking_boost_parts(query, &title, &terms, config);
+        let snippet = if filter.field == SearchField::Title {
+            boost.term_overlap = 0.0;
+            title.clone()
+        } else {
+            boost.title_overlap = 0.0;
+            boost.exact_phrase = 0.0;
+            matched_terms(query, &terms, config).join(", ")
         };
-        match by_node.get(&id) {
-            Some(existing) if existing.lexical_score >= candidate.lexical_score => {}
-            _ => {
-                by_node.insert(id, candidate);
-            }
+        if boost.total() <= 0.0 {
+            continue;
         }
+        let highlights = token_highlights(&snippet, query, config);
+        out.push(SearchCandidate {
+            id: row.get(0)?,
+            title,
+            path: row.get(2)?,
+            terms,
+            snippet,
+            highlights,
+            lexical_score: boost.total(),
+            fuzzy: false,
+            bm25_base: None,
+            boost: Some(boost),
+            chunk_ord: None,
+        });
     }
-    let mut out = by_node.into_values().collect::<Vec<_>>();
     out.sort_by(|a, b| {
         b.lexical_score
             .total_cmp(&a.lexical_score)
             .then(a.id.cmp(&b.id))
     });
+    out.truncate(limit);
     Ok(out)
 }
 
-fn collect_semantic_candidates(conn: &Connection, query: &str) -> Result<Vec<SemanticCandidate>> {
-    if sqlite_vec_available(conn) {
-        if let Ok(from_vec) = collect_semantic_candidates_with_sqlite_vec(conn, query) {
-            if !from_vec.is_empty() {
-                return Ok(from_vec);
+/// Byte ranges of the words in `text` whose ranking token is also a ranking
+/// token of `query`.
+fn token_highlights(text: &str, query: &str, config: &SearchRuntimeConfig) -> Vec<[usize; 2]> {
+    let query_tokens = ranking_tokens(query, config);
+    let mut highlights = Vec::new();
+    let mut start = None;
+    for (idx, ch) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (ch.is_alphanumeric(), start) {
+            (true, None) => start = Some(idx),
+            (false, Some(word_start)) => {
+                start = None;
+                if ranking_tokens(&text[word_start..idx], config)
+                    .iter()
+                    .any(|token| query_tokens.contains(token))
+                {
+                    highlights.push([word_start, idx]);
+                }
             }
+            _ => {}
         }
     }
-    collect_semantic_candidates_from_local_store(conn, query)
+    highlights
 }
 
-fn collect_semantic_candidates_with_sqlite_vec(
+/// Query tokens shorter than this never match fuzzily; one edit turns most
+/// short words into other real words.
+const FUZZY_MIN_TOKEN_LEN: usize = 4;
+
+/// Fuzzy scores are scaled down so a typo match never outranks what an exact
+/// match of the same strength would score.
+const FUZZY_SCORE_SCALE: f64 = 0.5;
+
+/// Typo-tolerant fallback for when FTS finds nothing: compares query tokens
+/// against each node's title and terms by edit distance. Score is the mean
+/// per-token similarity (`1 - distance / len`) times [`FUZZY_SCORE_SCALE`].
+fn collect_fuzzy_candidates(
     conn: &Connection,
     query: &str,
+    limit: usize,
+    filter: &SearchFilter,
+) -> Result<Vec<SearchCandidate>> {
+    let query_tokens = query_terms_for_fts(query)
+        .into_iter()
+        .filter(|t| t.chars().count() >= FUZZY_MIN_TOKEN_LEN)
+        .collect::<Vec<_>>();
+    if query_tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+    let sql = "
+        SELECT
+            n.id,
+            n.title,
+            n.md_path,
+            n.terms_json,
+            (SELECT c.text FROM chunks c WHERE c.node_id = n.id ORDER BY c.ord LIMIT 1),
+            (SELECT MIN(c.ord) FROM chunks c WHERE c.node_id = n.id)
+        FROM nodes n
+        WHERE (?1 IS NULL OR n.node_type IN (SELECT value FROM json_each(?1)))
+          AND (?2 IS NULL OR n.status IN (SELECT value FROM json_each(?2)))
+          AND (?3 IS NULL OR n.updated_at > ?3)
+    ";
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query(params![
+        filter.node_types_json(),
+        filter.statuses_json(),
+        filter.modified_after
+    ])?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let title: String = row.get(1)?;
+        let terms_json: String = row.get(3)?;
+        let terms: Vec<String> = serde_json::from_str(&terms_json).unwrap_or_default();
+        let node_tokens = std::iter::once(title.as_str())
+            .chain(terms.iter().map(String::as_str))
+            .flat_map(query_terms_for_fts)
+            .collect::<HashSet<_>>();
+        let similarity = query_tokens
+            .iter()
+            .map(|q| {
+                node_tokens
+                    .iter()
+                    .filter_map(|t| fuzzy_similarity(q, t))
+                    .fold(0.0, f64::max)
+            })
+            .sum::<f64>()
+            / query_tokens.len() as f64;
+        if similarity <= 0.0 {
+            continue;
+        }
+        let first_chunk: Option<String> = row.get(4)?;
+        out.push(SearchCandidate {
+            id: row.get(0)?,
+            title,
+            path: row.get(2)?,
+            terms,
+            snippet: snippet_head(&first_chunk.unwrap_or_default()),
+            highlights: Vec::new(),
+            lexical_score: similarity * FUZZY_SCORE_SCALE,
+            fuzzy: true,
+            bm25_base: None,
+            boost: None,
+            chunk_ord: row.get(5)?,
+        });
+    }
+    out.sort_by(|a, b| {
+        b.lexical_score
+            .total_cmp(&a.lexical_score)
+            .then(a.id.cmp(&b.id))
+    });
+    out.truncate(limit);
+    Ok(out)
+}
+
+/// `1 - distance / len` when `candidate` is within the edit budget for
+/// `token` (one edit per five characters, at least one), else `None`.
+fn fuzzy_similarity(token: &str, candidate: &str) -> Option<f64> {
+    let len = token.chars().count().max(candidate.chars().count());
+    let budget = (token.chars().count() / 5).max(1);
+    let distance = levenshtein(token, candidate);
+    (distance <= budget).then(|| 1.0 - distance as f64 / len as f64)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// First 32 words of `text` on one line, the same window size as FTS snippets.
+fn snippet_head(text: &str) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let mut head = words.iter().take(32).copied().collect::<Vec<_>>().join(" ");
+    if words.len() > 32 {
+        head.push_str("...");
+    }
+    head
+}
+
+/// Embedder for query text, producing local vectors at the size the index
+/// was built with rather than the configured one.
+fn query_embedder(conn: &Connection, runtime: &RuntimeConfig) -> Result<Embedder> {
+    let dim = match indexed_embedding_dim(conn)? {
+        Some(dim) => dim,
+        None => runtime.search.embedding_dim()?,
+    };
+    Ok(Embedder::from_config(runtime).with_local_dim(dim))
+}
+
+/// Indexed nodes ranked by embedding similarity to `text`, best first.
+pub(super) fn semantic_neighbors(
+    text: &str,
+    runtime: &RuntimeConfig,
+) -> Result<Vec<(String, f64)>> {
+    let conn = open_search_db(None)?;
+    ensure_search_schema_readonly(&conn)?;
+    let (model, vector) = query_embedder(&conn, runtime)?.embed(text);
+    let candidates =
+        collect_semantic_candidates(&conn, &vector, &model, &SearchFilter::default())?;
+    Ok(candidates
+        .into_iter()
+        .map(|c| (c.id, c.semantic_score))
+        .collect())
+}
+
+fn collect_semantic_candidates(
+    conn: &Connection,
+    query_vec: &[f64],
+    model: &str,
+    filter: &SearchFilter,
 ) -> Result<Vec<SemanticCandidate>> {
-    let query_vec_json = vector_to_json(&semantic_vector(query));
+    if model == LOCAL_EMBEDDING_MODEL
+        && sqlite_vec_available(conn)
+        && let Ok(from_vec) = collect_semantic_candidates_with_sqlite_vec(conn, query_vec, filter)
+        && !from_vec.is_empty()
+    {
+        return Ok(from_vec);
+    }
+    collect_semantic_candidates_from_local_store(conn, query_vec, model, filter)
+}
+
+fn collect_semantic_candidates_with_sqlite_vec(
+    conn: &Connection,
+    query_vec: &[f64],
+    filter: &SearchFilter,
+) -> Result<Vec<SemanticCandidate>> {
+    let query_vec_json = vector_to_json(query_vec);
+    // `k` bounds the KNN scan before the node filters run, so a selective
+    // filter can leave few rows; widen it until enough rows pass or every
+    // vector has been considered.
+    let total: i64 = conn.query_row("SELECT COUNT(*) FROM vec_chunks", [], |row| row.get(0))?;
+    let mut k = SEMANTIC_KNN_K;
+    loop {
+        let rows = query_sqlite_vec_rows(conn, &query_vec_json, k, filter)?;
+        if rows.len() as i64 >= SEMANTIC_KNN_K || k >= total {
+            return Ok(semantic_candidates_from_vec_rows(rows));
+        }
+        k = (k * 4).min(total);
+    }
+}
+
+/// Chunk rows the unfiltered semantic side asks `vec0` for.
+const SEMANTIC_KNN_K: i64 = 60;
+
+/// `(id, title, md_path, terms_json, snippet, distance, ord)` per matched chunk.
+type VecRow = (String, String, String, String, String, f64, i64);
+
+fn query_sqlite_vec_rows(
+    conn: &Connection,
+    query_vec_json: &str,
+    k: i64,
+    filter: &SearchFilter,
+) -> Result<Vec<VecRow>> {
     let mut stmt = conn.prepare(
         "
         SELECT
@@ -311,22 +1317,45 @@ fn collect_semantic_candidates_with_sqlite_vec(
             n.md_path,
             n.terms_json,
             SUBSTR(c.text, 1, 220) AS snippet,
-            vc.distance
+            vc.distance,
+            c.ord
         FROM vec_chunks vc
         JOIN chunks c ON c.chunk_id = vc.chunk_id
         JOIN nodes n ON n.id = c.node_id
         WHERE embedding MATCH ?1 AND k = ?2
+          AND (?3 IS NULL OR n.node_type IN (SELECT value FROM json_each(?3)))
+          AND (?4 IS NULL OR n.status IN (SELECT value FROM json_each(?4)))
+          AND (?5 IS NULL OR n.updated_at > ?5)
         ",
     )?;
-    let mut rows = stmt.query(params![query_vec_json, 60_i64])?;
+    let rows = stmt
+        .query_map(
+            params![
+                query_vec_json,
+                k,
+                filter.node_types_json(),
+                filter.statuses_json(),
+                filter.modified_after
+            ],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<VecRow>>>()?;
+    Ok(rows)
+}
+
+fn semantic_candidates_from_vec_rows(rows: Vec<VecRow>) -> Vec<SemanticCandidate> {
     let mut by_node = HashMap::<String, SemanticCandidate>::new();
-    while let Some(row) = rows.next()? {
-        let id: String = row.get(0)?;
-        let title: String = row.get(1)?;
-        let path: String = row.get(2)?;
-        let terms_json: String = row.get(3)?;
-        let snippet: String = row.get(4)?;
-        let distance: f64 = row.get(5)?;
+    for (id, title, path, terms_json, snippet, distance, chunk_ord) in rows {
         let score = 1.0 / (1.0 + distance.max(0.0));
         if score < 0.2 {
             continue;
@@ -339,6 +1368,7 @@ fn collect_semantic_candidates_with_sqlite_vec(
             terms,
             snippet: snippet.replace('\n', " "),
             semantic_score: score,
+            chunk_ord,
         };
         match by_node.get(&id) {
             Some(existing) if existing.semantic_score >= candidate.semantic_score => {}
@@ -353,14 +1383,15 @@ fn collect_semantic_candidates_with_sqlite_vec(
             .total_cmp(&a.semantic_score)
             .then(a.id.cmp(&b.id))
     });
-    Ok(out)
+    out
 }
 
 fn collect_semantic_candidates_from_local_store(
     conn: &Connection,
-    query: &str,
+    query_vec: &[f64],
+    model: &str,
+    filter: &SearchFilter,
 ) -> Result<Vec<SemanticCandidate>> {
-    let query_vec = semantic_vector(query);
     let mut stmt = conn.prepare(
         "
         SELECT
@@ -369,14 +1400,23 @@ fn collect_semantic_candidates_from_local_store(
             n.md_path,
             n.terms_json,
             SUBSTR(c.text, 1, 220) AS snippet,
-            cv.embedding
+            cv.embedding,
+            c.ord
         FROM chunk_vectors cv
         JOIN chunks c ON c.chunk_id = cv.chunk_id
         JOIN nodes n ON n.id = c.node_id
-        WHERE cv.model = 'local-hash-ngrams-v1'
+        WHERE cv.model = ?1
+          AND (?2 IS NULL OR n.node_type IN (SELECT value FROM json_each(?2)))
+          AND (?3 IS NULL OR n.status IN (SELECT value FROM json_each(?3)))
+          AND (?4 IS NULL OR n.updated_at > ?4)
         ",
     )?;
-    let mut rows = stmt.query([])?;
+    let mut rows = stmt.query(params![
+        model,
+        filter.node_types_json(),
+        filter.statuses_json(),
+        filter.modified_after
+    ])?;
     let mut by_node = HashMap::<String, SemanticCandidate>::new();
     while let Some(row) = rows.next()? {
         let id: String = row.get(0)?;
@@ -385,11 +1425,12 @@ fn collect_semantic_candidates_from_local_store(
         let terms_json: String = row.get(3)?;
         let snippet: String = row.get(4)?;
         let embedding_blob: Vec<u8> = row.get(5)?;
+        let chunk_ord: i64 = row.get(6)?;
         let chunk_vec = blob_to_vector(&embedding_blob)?;
         if chunk_vec.is_empty() {
             continue;
         }
-        let score = cosine_similarity(&query_vec, &chunk_vec);
+        let score = cosine_similarity(query_vec, &chunk_vec);
         if score < 0.2 {
             continue;
         }
@@ -401,6 +1442,7 @@ fn collect_semantic_candidates_from_local_store(
             terms,
             snippet: snippet.replace('\n', " "),
             semantic_score: score,
+            chunk_ord,
         };
         match by_node.get(&id) {
             Some(existing) if existing.semantic_score >= candidate.semantic_score => {}
@@ -423,25 +1465,33 @@ fn merge_hybrid_results(
     lexical: Vec<SearchCandidate>,
     semantic: Vec<SemanticCandidate>,
     top_k: usize,
+    config: &SearchRuntimeConfig,
 ) -> Vec<SearchHit> {
-    let mut lexical_rank = HashMap::<String, usize>::new();
+    let mut lexical_rank = HashMap::<String, (usize, f64)>::new();
     for (idx, c) in lexical.iter().enumerate() {
-        lexical_rank.insert(c.id.clone(), idx + 1);
+        lexical_rank.insert(c.id.clone(), (idx + 1, c.lexical_score));
     }
-    let mut semantic_rank = HashMap::<String, usize>::new();
+    let mut semantic_rank = HashMap::<String, (usize, f64)>::new();
     for (idx, c) in semantic.iter().enumerate() {
-        semantic_rank.insert(c.id.clone(), idx + 1);
+        semantic_rank.insert(c.id.clone(), (idx + 1, c.semantic_score));
     }
 
     let mut merged = HashMap::<String, SearchHit>::new();
     for c in lexical {
         merged.entry(c.id.clone()).or_insert(SearchHit {
+            explanation: Some(c.explanation()),
             id: c.id.clone(),
             title: c.title,
             path: c.path,
             score: 0.0,
-            matched_terms: matched_terms(query, &c.terms),
+            normalized_score: None,
+            matched_terms: matched_terms(query, &c.terms, config),
             snippet: c.snippet,
+            highlights: c.highlights,
+            fuzzy: c.fuzzy,
+            chunk_ord: c.chunk_ord,
+            context: Vec::new(),
+            updated_at: None,
         });
     }
     for c in semantic {
@@ -450,15 +1500,43 @@ fn merge_hybrid_results(
             title: c.title,
             path: c.path,
             score: 0.0,
-            matched_terms: matched_terms(query, &c.terms),
+            normalized_score: None,
+            matched_terms: matched_terms(query, &c.terms, config),
             snippet: c.snippet,
+            highlights: Vec::new(),
+            fuzzy: false,
+            explanation: Some(SearchExplanation {
+                bm25_base: None,
+                boost: None,
+                hybrid: None,
+                rerank: None,
+            }),
+            chunk_ord: Some(c.chunk_ord),
+            context: Vec::new(),
+            updated_at: None,
         });
     }
 
     for hit in merged.values_mut() {
-        let l_rank = lexical_rank.get(&hit.id).copied().unwrap_or(10_000);
-        let s_rank = semantic_rank.get(&hit.id).copied().unwrap_or(10_000);
-        hit.score = reciprocal_rank_fusion(l_rank) + reciprocal_rank_fusion(s_rank);
+        let lexical = lexical_rank.get(&hit.id).copied();
+        let semantic = semantic_rank.get(&hit.id).copied();
+        hit.score = hybrid_fused_score(lexical, semantic, config);
+        let rrf = |entry: Option<(usize, f64)>, weight: f64| {
+            entry.map_or(0.0, |(rank, _)| {
+                weight * reciprocal_rank_fusion(rank, config.rrf_k)
+            })
+        };
+        let lexical_rrf = rrf(lexical, config.lexical_weight);
+        let semantic_rrf = rrf(semantic, config.semantic_weight);
+        if let Some(explanation) = &mut hit.explanation {
+            explanation.hybrid = Some(HybridExplanation {
+                lexical_rank: lexical.map(|(rank, _)| rank),
+                lexical_rrf,
+                semantic_rank: semantic.map(|(rank, _)| rank),
+                semantic_rrf,
+                strength: hit.score - lexical_rrf - semantic_rrf,
+            });
+        }
     }
 
     let mut hits = merged.into_values().collect::<Vec<_>>();
@@ -467,15 +1545,65 @@ fn merge_hybrid_results(
     hits
 }
 
-pub(super) fn reciprocal_rank_fusion(rank: usize) -> f64 {
+/// Weight of the raw-score strength term. It adds at most `0.001`, far less
+/// than one list's rank-1 RRF contribution at the default weights (`1/61`),
+/// so RRF stays primary.
+const HYBRID_STRENGTH_WEIGHT: f64 = 0.0005;
+
+/// Weighted RRF over the lexical and semantic `(rank, score)` pairs
+/// (`search.rrf_k`, `search.lexical_weight`, `search.semantic_weight`), plus
+/// a small strength term so that hits with equal rank positions are ordered
+/// by how well they actually matched rather than by id.
+pub(super) fn hybrid_fused_score(
+    lexical: Option<(usize, f64)>,
+    semantic: Option<(usize, f64)>,
+    config: &SearchRuntimeConfig,
+) -> f64 {
+    let rrf = |entry: Option<(usize, f64)>, weight: f64| {
+        entry.map_or(0.0, |(rank, _)| {
+            weight * reciprocal_rank_fusion(rank, config.rrf_k)
+        })
+    };
+    // bm25-based lexical scores are unbounded; squash them into [0, 1).
+    let lexical_strength = lexical.map_or(0.0, |(_, score)| {
+        let score = score.max(0.0);
+        score / (1.0 + score)
+    });
+    let semantic_strength = semantic.map_or(0.0, |(_, score)| score.clamp(0.0, 1.0));
+    rrf(lexical, config.lexical_weight)
+        + rrf(semantic, config.semantic_weight)
+        + HYBRID_STRENGTH_WEIGHT * (lexical_strength + semantic_strength)
+}
+
+pub(super) fn reciprocal_rank_fusion(rank: usize, k: f64) -> f64 {
     if rank >= 10_000 {
         0.0
     } else {
-        1.0 / (60.0 + rank as f64)
+        1.0 / (k + rank as f64)
     }
 }
 
-pub(super) fn run_search_doctor() -> Result<()> {
+pub(super) fn run_search_doctor(args: &SearchDoctorArgs, db: Option<&Path>) -> Result<i32> {
+    let issues = collect_search_doctor_issues(db)?;
+    let output = SearchDoctorOutput {
+        ok: issues.is_empty(),
+        issue_count: issues.len(),
+        issues,
+    };
+    match args.format {
+        SearchDoctorFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        SearchDoctorFormat::Table => print_search_doctor_table(&output),
+    }
+    if output.ok {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
+
+/// Index/meta consistency problems: hash drift, stale or missing nodes,
+/// orphan chunks, and mixed embedding models.
+pub(super) fn collect_search_doctor_issues(db: Option<&Path>) -> Result<Vec<String>> {
     let spec_root = Path::new("spec");
     let mut lint = LintState::default();
     let metas = load_all_meta(spec_root, &mut lint)?;
@@ -484,7 +1612,7 @@ pub(super) fn run_search_doctor() -> Result<()> {
         expected.insert(meta.id, meta.hash);
     }
 
-    let conn = open_search_db()?;
+    let conn = open_search_db(db)?;
     ensure_search_schema_readonly(&conn)?;
 
     let mut issues = Vec::new();
@@ -517,21 +1645,71 @@ pub(super) fn run_search_doctor() -> Result<()> {
         issues.push(format!("orphan chunks: {orphan_chunks}"));
     }
 
-    if issues.is_empty() {
+    let configured = load_runtime_config().search.tokenizer;
+    let indexed = indexed_fts_tokenizer(&conn)?;
+    if !indexed_ids.is_empty() && indexed != Some(configured) {
+        issues.push(format!(
+            "fts tokenizer mismatch: index uses {}, search.tokenizer is {}; run `foundry spec search index --rebuild`",
+            indexed.map_or("an unknown tokenizer", FtsTokenizer::spec),
+            configured.spec()
+        ));
+    }
+
+    let configured_dim = load_runtime_config().search.embedding_dim;
+    if !indexed_ids.is_empty()
+        && let Some(indexed_dim) = indexed_embedding_dim(&conn)?
+        && indexed_dim != configured_dim
+    {
+        issues.push(format!(
+            "embedding dim mismatch: index uses {indexed_dim}, search.embedding_dim is {configured_dim}; run `foundry spec search index --rebuild`"
+        ));
+    }
+
+    let configured_ranking = load_runtime_config().search.ranking_token_settings();
+    let indexed_ranking = indexed_ranking_token_settings(&conn)?;
+    if !indexed_ids.is_empty() && indexed_ranking != configured_ranking {
+        issues.push(format!(
+            "ranking token mismatch: index uses {indexed_ranking}, config is {configured_ranking}; run `foundry spec search index --rebuild`"
+        ));
+    }
+
+    let mut stmt =
+        conn.prepare("SELECT model, COUNT(*) FROM chunk_vectors GROUP BY model ORDER BY model")?;
+    let models = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if models.len() > 1 {
+        let joined = models
+            .iter()
+            .map(|(model, count)| format!("{model} ({count})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        issues.push(format!(
+            "mixed embedding models in index: {joined}; run `foundry spec search reindex-vectors`"
+        ));
+    }
+    Ok(issues)
+}
+
+fn print_search_doctor_table(output: &SearchDoctorOutput) {
+    if output.ok {
         println!("search doctor: ok");
-    } else {
-        for issue in &issues {
-            println!("search doctor: issue: {issue}");
-        }
-        println!("search doctor summary: {} issue(s)", issues.len());
+        return;
     }
-    Ok(())
+    for issue in &output.issues {
+        println!("search doctor: issue: {issue}");
+    }
+    println!("search doctor summary: {} issue(s)", output.issue_count);
 }
 
-pub(super) fn ensure_search_schema(conn: &mut Connection) -> Result<()> {
+pub(super) fn ensure_search_schema(conn: &mut Connection, tokenizer: FtsTokenizer) -> Result<()> {
+    // Switching modes checkpoints, so skip it once the file is already WAL.
+    let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        conn.execute_batch("PRAGMA journal_mode=WAL;")?;
+    }
     conn.execute_batch(
         "
-        PRAGMA journal_mode=WAL;
         CREATE TABLE IF NOT EXISTS nodes (
             id TEXT PRIMARY KEY,
             title TEXT NOT NULL,
@@ -539,7 +1717,9 @@ pub(super) fn ensure_search_schema(conn: &mut Connection) -> Result<()> {
             meta_path TEXT NOT NULL,
             hash TEXT NOT NULL,
             terms_json TEXT NOT NULL,
-            updated_at INTEGER NOT NULL
+            updated_at INTEGER NOT NULL,
+            node_type TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT ''
         );
         CREATE TABLE IF NOT EXISTS chunks (
             chunk_id TEXT PRIMARY KEY,
@@ -549,43 +1729,153 @@ pub(super) fn ensure_search_schema(conn: &mut Connection) -> Result<()> {
             token_len INTEGER NOT NULL,
             FOREIGN KEY(node_id) REFERENCES nodes(id) ON DELETE CASCADE
         );
-        CREATE VIRTUAL TABLE IF NOT EXISTS fts_chunks USING fts5(
-            chunk_id UNINDEXED,
-            node_id UNINDEXED,
-            text,
-            tokenize = 'unicode61'
-        );
         CREATE TABLE IF NOT EXISTS chunk_vectors (
             chunk_id TEXT PRIMARY KEY,
             model TEXT NOT NULL,
             dim INTEGER NOT NULL,
             embedding BLOB
         );
+        CREATE TABLE IF NOT EXISTS index_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
         ",
     )?;
+    conn.execute_batch(&fts_table_sql(tokenizer))?;
+    ensure_node_filter_columns(conn)?;
+    Ok(())
+}
+
+fn fts_table_sql(tokenizer: FtsTokenizer) -> String {
+    format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS fts_chunks USING fts5(
+            chunk_id UNINDEXED,
+            node_id UNINDEXED,
+            text,
+            {}
+        );",
+        fts_tokenize_clause(tokenizer)
+    )
+}
+
+fn fts_tokenize_clause(tokenizer: FtsTokenizer) -> String {
+    format!("tokenize = '{}'", tokenizer.spec().replace('\'', "''"))
+}
+
+/// Tokenizer `fts_chunks` was created with, read back from its stored
+/// `CREATE` statement; `None` when the table is missing or unrecognized.
+fn indexed_fts_tokenizer(conn: &Connection) -> Result<Option<FtsTokenizer>> {
+    let sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE name = 'fts_chunks'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(sql.and_then(|sql| {
+        FtsTokenizer::ALL
+            .into_iter()
+            .find(|t| sql.contains(&fts_tokenize_clause(*t)))
+    }))
+}
+
+fn read_index_meta(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM index_meta WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn set_index_meta(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO index_meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Ranking token settings the index vectors were built with; indexes that
+/// predate the key were built before stopwords/stemming existed.
+fn indexed_ranking_token_settings(conn: &Connection) -> Result<String> {
+    Ok(read_index_meta(conn, "ranking_tokens")?
+        .unwrap_or_else(|| SearchRuntimeConfig::default().ranking_token_settings()))
+}
+
+// Indexes built before type/status filtering lack these columns; add them in
+// place. Old rows hold `''` and so match no `--node-type`/`--status` filter
+// until the next `search index` run fills them in.
+fn ensure_node_filter_columns(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('nodes')")?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<HashSet<_>>>()?;
+    for column in ["node_type", "status"] {
+        if !columns.contains(column) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE nodes ADD COLUMN {column} TEXT NOT NULL DEFAULT '';"
+            ))?;
+        }
+    }
     Ok(())
 }
 
-pub(super) fn ensure_sqlite_vec_ready(conn: &Connection) -> Result<bool> {
+/// Loads `sqlite-vec` when configured and makes sure `vec_chunks` holds
+/// `dim`-wide vectors, recreating it (empty) when its width differs.
+pub(super) fn ensure_sqlite_vec_ready(conn: &Connection, dim: usize) -> Result<bool> {
     if !sqlite_vec_available(conn) {
         let _ = try_load_sqlite_vec_extension(conn);
     }
     if !sqlite_vec_available(conn) {
         return Ok(false);
     }
-    conn.execute_batch(
-        &format!(
-            "
+    if vec_table_dim(conn)?.is_some_and(|existing| existing != dim) {
+        conn.execute_batch("DROP TABLE vec_chunks;")?;
+    }
+    conn.execute_batch(&format!(
+        "
             CREATE VIRTUAL TABLE IF NOT EXISTS vec_chunks USING vec0(
                 chunk_id TEXT,
-                embedding FLOAT[{EMBEDDING_DIM}]
+                embedding FLOAT[{dim}]
             );
             "
-        ),
-    )?;
+    ))?;
     Ok(true)
 }
 
+/// Column width `vec_chunks` was created with, read back from its `CREATE`
+/// statement.
+fn vec_table_dim(conn: &Connection) -> Result<Option<usize>> {
+    let sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE name = 'vec_chunks'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(sql.and_then(|sql| {
+        let (_, rest) = sql.split_once("FLOAT[")?;
+        rest.split_once(']')?.0.trim().parse().ok()
+    }))
+}
+
+/// Local embedding size recorded by the last `index`/`reindex-vectors`;
+/// indexes from before it was recorded used the default. `None` for an
+/// index that has never held nodes.
+fn indexed_embedding_dim(conn: &Connection) -> Result<Option<usize>> {
+    if let Some(dim) = read_index_meta(conn, "embedding_dim")? {
+        let dim = dim
+            .parse()
+            .with_context(|| format!("invalid embedding_dim in index_meta: {dim}"))?;
+        return Ok(Some(dim));
+    }
+    let nodes: i64 = conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))?;
+    Ok((nodes > 0).then_some(DEFAULT_EMBEDDING_DIM))
+}
+
 pub(super) fn sqlite_vec_available(conn: &Connection) -> bool {
     conn.query_row(
         "SELECT COUNT(*) FROM pragma_module_list WHERE name = 'vec0'",
@@ -620,7 +1910,9 @@ pub(super) fn ensure_search_schema_readonly(conn: &Connection) -> Result<()> {
             meta_path TEXT NOT NULL,
             hash TEXT NOT NULL,
             terms_json TEXT NOT NULL,
-            updated_at INTEGER NOT NULL
+            updated_at INTEGER NOT NULL,
+            node_type TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL DEFAULT ''
         );
         CREATE TABLE IF NOT EXISTS chunks (
             chunk_id TEXT PRIMARY KEY,
@@ -629,48 +1921,95 @@ pub(super) fn ensure_search_schema_readonly(conn: &Connection) -> Result<()> {
             text TEXT NOT NULL,
             token_len INTEGER NOT NULL
         );
-        CREATE VIRTUAL TABLE IF NOT EXISTS fts_chunks USING fts5(
-            chunk_id UNINDEXED,
-            node_id UNINDEXED,
-            text,
-            tokenize = 'unicode61'
-        );
         CREATE TABLE IF NOT EXISTS chunk_vectors (
             chunk_id TEXT PRIMARY KEY,
             model TEXT NOT NULL,
             dim INTEGER NOT NULL,
             embedding BLOB
         );
+        CREATE TABLE IF NOT EXISTS index_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
         ",
     )?;
-    let _ = ensure_sqlite_vec_ready(conn);
+    conn.execute_batch(&fts_table_sql(FtsTokenizer::default()))?;
+    ensure_node_filter_columns(conn)?;
+    let dim = indexed_embedding_dim(conn)?.unwrap_or(DEFAULT_EMBEDDING_DIM);
+    let _ = ensure_sqlite_vec_ready(conn, dim);
     Ok(())
 }
 
-pub(super) fn open_search_db() -> Result<Connection> {
-    let db_path = search_db_path();
-    if let Some(parent) = db_path.parent() {
-        fs::create_dir_all(parent)?;
+pub(super) fn open_search_db(db: Option<&Path>) -> Result<Connection> {
+    let db_path = search_db_path(db);
+    if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("cannot create search index directory {}", parent.display())
+        })?;
     }
-    let conn = Connection::open(db_path)?;
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("cannot open search index {}", db_path.display()))?;
     Ok(conn)
 }
 
-pub(super) fn search_db_path() -> PathBuf {
-    PathBuf::from(".foundry/search/index.db")
+/// Environment variable overriding the search index location when no
+/// `--db` flag is given.
+const SEARCH_DB_ENV: &str = "FOUNDRY_SEARCH_DB";
+
+/// `FOUNDRY_SEARCH_DB` made absolute against the invocation directory, set
+/// when the process moved to the project root before reading it.
+static SEARCH_DB_ENV_RESOLVED: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// Pins a relative `FOUNDRY_SEARCH_DB` to `base`, the directory the command
+/// was run from, like the relative path flags.
+pub(super) fn resolve_search_db_env(base: &Path) {
+    if let Some(path) = std::env::var_os(SEARCH_DB_ENV).filter(|p| !p.is_empty()) {
+        let _ = SEARCH_DB_ENV_RESOLVED.set(base.join(path));
+    }
 }
 
-pub(super) fn split_into_chunks(text: &str, target_len: usize) -> Vec<String> {
+/// `--db` if given, else `FOUNDRY_SEARCH_DB`, else `.foundry/search/index.db`.
+pub(super) fn search_db_path(db: Option<&Path>) -> PathBuf {
+    if let Some(db) = db {
+        return db.to_path_buf();
+    }
+    if let Some(path) = SEARCH_DB_ENV_RESOLVED.get() {
+        return path.clone();
+    }
+    match std::env::var_os(SEARCH_DB_ENV) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => PathBuf::from(".foundry/search/index.db"),
+    }
+}
+
+/// Overlap used when `search.chunk_overlap` is not configured.
+pub(super) fn default_chunk_overlap(target_len: usize) -> usize {
+    (target_len / 6).clamp(80, 180).min(target_len / 2)
+}
+
+/// `chunk_id` suffix of the synthetic title/terms chunk written by
+/// `search index --include-title-terms`; it lives only in `fts_chunks`.
+const TITLE_TERMS_CHUNK: &str = "title";
+
+fn title_terms_text(meta: &SpecNodeMeta) -> String {
+    if meta.terms.is_empty() {
+        meta.title.clone()
+    } else {
+        format!("{}\n\n{}", meta.title, meta.terms.join(", "))
+    }
+}
+
+pub(super) fn split_into_chunks(text: &str, target_len: usize, overlap: usize) -> Vec<String> {
     let mut out = Vec::new();
     let mut current = String::new();
-    let overlap = (target_len / 6).clamp(80, 180);
 
     for part in text.split("\n\n") {
         let p = part.trim();
         if p.is_empty() {
             continue;
         }
-        if p.len() > target_len {
+        let p_len = p.chars().count();
+        if p_len > target_len {
             if !current.is_empty() {
                 out.push(current.trim().to_string());
                 current.clear();
@@ -678,7 +2017,7 @@ pub(super) fn split_into_chunks(text: &str, target_len: usize) -> Vec<String> {
             out.extend(split_long_text_with_overlap(p, target_len, overlap));
             continue;
         }
-        if !current.is_empty() && current.len() + p.len() + 2 > target_len {
+        if !current.is_empty() && current.chars().count() + p_len + 2 > target_len {
             out.push(current.trim().to_string());
             current.clear();
         }
@@ -706,6 +2045,38 @@ pub(super) fn normalize_query_for_fts(query: &str) -> String {
     query_terms_for_fts(query).join(" ")
 }
 
+/// FTS5 `MATCH` expression for `query` under the index's tokenizer. The
+/// default keeps the plain bareword form; the other tokenizers get quoted
+/// terms so `_` survives and trigram terms match as substrings (terms under
+/// three characters can never match a trigram index, so they are dropped).
+pub(super) fn fts_match_query(query: &str, tokenizer: FtsTokenizer) -> String {
+    let terms = match tokenizer {
+        FtsTokenizer::Unicode61 => return normalize_query_for_fts(query),
+        FtsTokenizer::Trigram => {
+            let terms = query_terms_for_fts(query);
+            let long = terms
+                .iter()
+                .filter(|t| t.chars().count() >= 3)
+                .cloned()
+                .collect::<Vec<_>>();
+            if long.is_empty() { terms } else { long }
+        }
+        FtsTokenizer::Unicode61Identifiers => {
+            let mut seen = HashSet::new();
+            query
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map(str::to_lowercase)
+                .filter(|t| !t.trim_matches('_').is_empty() && seen.insert(t.clone()))
+                .collect()
+        }
+    };
+    terms
+        .iter()
+        .map(|t| format!("\"{t}\""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 pub(super) fn query_terms_for_fts(query: &str) -> Vec<String> {
     let mut out = Vec::new();
     let mut seen = HashSet::new();
@@ -727,7 +2098,8 @@ pub(super) fn split_long_text_with_overlap(text: &str, target_len: usize, overla
     let mut current = String::new();
 
     for sentence in sentences {
-        if sentence.len() > target_len {
+        let sentence_len = sentence.chars().count();
+        if sentence_len > target_len {
             if !current.trim().is_empty() {
                 chunks.push(current.trim().to_string());
                 current.clear();
@@ -735,17 +2107,16 @@ pub(super) fn split_long_text_with_overlap(text: &str, target_len: usize, overla
             chunks.extend(split_by_char_window(&sentence, target_len, overlap));
             continue;
         }
-        if !current.is_empty() && current.len() + sentence.len() + 1 > target_len {
+        if !current.is_empty() && current.chars().count() + sentence_len + 1 > target_len {
             let finalized = current.trim().to_string();
             if !finalized.is_empty() {
                 chunks.push(finalized.clone());
             }
-            let carry = tail_overlap(&finalized, overlap);
+            // Keep the carried overlap small enough that carry + sentence still fits.
+            let room = target_len.saturating_sub(sentence_len + 1);
+            let carry = tail_overlap(&finalized, overlap.min(room));
             current.clear();
-            if !carry.is_empty() {
-                current.push_str(&carry);
-                current.push(' ');
-            }
+            current.push_str(carry.trim_start());
         }
         if !current.is_empty() {
             current.push(' ');
@@ -806,6 +2177,9 @@ pub(super) fn split_by_char_window(text: &str, target_len: usize, overlap: usize
 }
 
 pub(super) fn tail_overlap(text: &str, overlap: usize) -> String {
+    if overlap == 0 {
+        return String::new();
+    }
     let chars: Vec<char> = text.chars().collect();
     if chars.len() <= overlap {
         text.to_string()
@@ -814,22 +2188,42 @@ pub(super) fn tail_overlap(text: &str, overlap: usize) -> String {
     }
 }
 
-pub(super) fn matched_terms(query: &str, terms: &[String]) -> Vec<String> {
-    let query_tokens = tokenize(query);
+pub(super) fn matched_terms(
+    query: &str,
+    terms: &[String],
+    config: &SearchRuntimeConfig,
+) -> Vec<String> {
+    let query_tokens = ranking_tokens(query, config);
     terms
         .iter()
-        .filter(|t| query_tokens.contains(&normalize_term_key(t)))
+        .filter(|t| query_tokens.contains(&ranking_key(&normalize_term_key(t), config)))
         .cloned()
         .collect()
 }
 
-pub(super) fn ranking_boost(query: &str, title: &str, terms: &[String]) -> f64 {
-    let q_tokens = tokenize(query);
-    let title_tokens = tokenize(title);
+#[cfg(test)]
+pub(super) fn ranking_boost(
+    query: &str,
+    title: &str,
+    terms: &[String],
+    config: &SearchRuntimeConfig,
+) -> f64 {
+    ranking_boost_parts(query, title, terms, config).total()
+}
+
+fn ranking_boost_parts(
+    query: &str,
+    title: &str,
+    terms: &[String],
+    config: &SearchRuntimeConfig,
+) -> RankingBoost {
+    let q_tokens = ranking_tokens(query, config);
+    let title_tokens = ranking_tokens(title, config);
     let q_norm_tokens = query
         .split_whitespace()
         .map(normalize_term_key)
         .filter(|s| !s.is_empty())
+        .map(|s| ranking_key(&s, config))
         .collect::<HashSet<_>>();
     let title_overlap = q_tokens.intersection(&title_tokens).count() as f64;
     let exact_phrase = title
@@ -838,30 +2232,66 @@ pub(super) fn ranking_boost(query: &str, title: &str, terms: &[String]) -> f64 {
     let term_overlap = terms
         .iter()
         .filter(|t| {
-            let n = normalize_term_key(t);
+            let n = ranking_key(&normalize_term_key(t), config);
             q_tokens.contains(&n) || q_norm_tokens.contains(&n)
         })
         .count() as f64;
-    (title_overlap * 3.0) + (term_overlap * 2.5) + (exact_phrase * 4.0)
+    RankingBoost {
+        title_overlap: title_overlap * 3.0,
+        term_overlap: term_overlap * 2.5,
+        exact_phrase: exact_phrase * 4.0,
+    }
+}
+
+const SEMANTIC_BUCKET_CACHE_LIMIT: usize = 200_000;
+
+/// Hash of each token and 3-gram seen so far. Chunks share most of their
+/// vocabulary, so the [`Embedder`] keeps one of these for a whole index run
+/// instead of rehashing every token per chunk. Cleared once it holds
+/// `SEMANTIC_BUCKET_CACHE_LIMIT` keys.
+#[derive(Debug, Default)]
+pub(super) struct SemanticBucketCache {
+    hashes: HashMap<String, usize>,
 }
 
-pub(super) fn semantic_vector(text: &str) -> Vec<f64> {
-    let mut vec = vec![0.0_f64; EMBEDDING_DIM];
+impl SemanticBucketCache {
+    fn bucket(&mut self, key: &str, dim: usize) -> usize {
+        if let Some(hash) = self.hashes.get(key) {
+            return hash % dim;
+        }
+        if self.hashes.len() >= SEMANTIC_BUCKET_CACHE_LIMIT {
+            self.hashes.clear();
+        }
+        let hash = stable_hash(key.as_bytes());
+        self.hashes.insert(key.to_string(), hash);
+        hash % dim
+    }
+}
+
+/// Local hash-ngram embedding. Token hashes come from `cache`, which never
+/// changes the result: a warm cache yields the same bits as a fresh one.
+pub(super) fn semantic_vector(
+    text: &str,
+    config: &SearchRuntimeConfig,
+    cache: &mut SemanticBucketCache,
+) -> Vec<f64> {
+    let dim = config.embedding_dim.max(1);
+    let mut vec = vec![0.0_f64; dim];
     let normalized = text.to_ascii_lowercase();
 
-    for token in tokenize(&normalized) {
-        let idx = stable_hash(token.as_bytes()) % EMBEDDING_DIM;
-        vec[idx] += 2.0;
+    for token in ranking_tokens(&normalized, config) {
+        vec[cache.bucket(&token, dim)] += 2.0;
     }
     let compact: String = normalized
         .chars()
         .filter(|c| c.is_ascii_alphanumeric() || c.is_whitespace())
         .collect();
     let chars: Vec<char> = compact.chars().collect();
+    let mut gram = String::with_capacity(12);
     for window in chars.windows(3) {
-        let gram = window.iter().collect::<String>();
-        let idx = stable_hash(gram.as_bytes()) % EMBEDDING_DIM;
-        vec[idx] += 1.0;
+        gram.clear();
+        gram.extend(window);
+        vec[cache.bucket(&gram, dim)] += 1.0;
     }
 
     let norm = vec.iter().map(|v| v * v).sum::<f64>().sqrt();
@@ -874,7 +2304,7 @@ pub(super) fn semantic_vector(text: &str) -> Vec<f64> {
 }
 
 pub(super) fn vector_to_blob(vec: &[f64]) -> Vec<u8> {
-    let mut out = Vec::with_capacity(vec.len() * std::mem::size_of::<f64>());
+    let mut out = Vec::with_capacity(std::mem::size_of_val(vec));
     for value in vec {
         out.extend_from_slice(&value.to_le_bytes());
     }
@@ -907,13 +2337,25 @@ pub(super) fn blob_to_vector(blob: &[u8]) -> Result<Vec<f64>> {
     Ok(out)
 }
 
+/// Cosine of the angle between `a` and `b`; inputs need not be normalized
+/// (HTTP embedding providers may store raw vectors). Returns 0.0 when either
+/// vector is zero or the dimensions differ.
 pub(super) fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
-    let len = a.len().min(b.len());
-    let mut dot = 0.0;
-    for i in 0..len {
-        dot += a[i] * b[i];
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    let denom = (norm_a * norm_b).sqrt();
+    if denom > 0.0 {
+        (dot / denom).clamp(-1.0, 1.0)
+    } else {
+        0.0
     }
-    dot
 }
 
 pub(super) fn stable_hash(bytes: &[u8]) -> usize {
@@ -925,6 +2367,60 @@ pub(super) fn stable_hash(bytes: &[u8]) -> usize {
     hash as usize
 }
 
+const SNIPPET_MATCH_START: char = '\u{2}';
+const SNIPPET_MATCH_END: char = '\u{3}';
+
+/// Strips the FTS5 `snippet()` match markers and records where they were.
+pub(super) fn parse_marked_snippet(raw: &str) -> (String, Vec<[usize; 2]>) {
+    let mut snippet = String::with_capacity(raw.len());
+    let mut highlights = Vec::new();
+    let mut open = None;
+    for ch in raw.chars() {
+        match ch {
+            SNIPPET_MATCH_START => open = Some(snippet.len()),
+            SNIPPET_MATCH_END => {
+                if let Some(start) = open.take()
+                    && start < snippet.len()
+                {
+                    highlights.push([start, snippet.len()]);
+                }
+            }
+            _ => snippet.push(ch),
+        }
+    }
+    (snippet, highlights)
+}
+
+pub(super) fn render_highlighted_snippet(snippet: &str, highlights: &[[usize; 2]]) -> String {
+    let mut out = String::with_capacity(snippet.len() + highlights.len() * 4);
+    let mut cursor = 0;
+    for [start, end] in highlights {
+        let (start, end) = (*start, *end);
+        if start < cursor || end > snippet.len() || start >= end {
+            continue;
+        }
+        out.push_str(&snippet[cursor..start]);
+        out.push_str("**");
+        out.push_str(&snippet[start..end]);
+        out.push_str("**");
+        cursor = end;
+    }
+    out.push_str(&snippet[cursor..]);
+    out
+}
+
+fn print_search_ndjson(output: &SearchQueryOutput) -> Result<()> {
+    use std::io::Write;
+
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    for hit in &output.hits {
+        serde_json::to_writer(&mut out, hit)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
 fn print_search_table(output: &SearchQueryOutput) {
     println!("query: {}", output.query);
     println!("mode: {}", output.mode);
@@ -940,8 +2436,50 @@ fn print_search_table(output: &SearchQueryOutput) {
             hit.matched_terms.join(",")
         };
         println!(
-            "  - {} | {} | score={:.4} | terms={} | {}",
-            hit.id, hit.path, hit.score, terms, hit.snippet
+            "  - {} | {} | score={:.4}{}{} | terms={} | {}",
+            hit.id,
+            hit.path,
+            hit.score,
+            hit.normalized_score
+                .map_or(String::new(), |n| format!(" normalized={n:.4}")),
+            if hit.fuzzy { " (fuzzy)" } else { "" },
+            terms,
+            render_highlighted_snippet(&hit.snippet, &hit.highlights)
+        );
+        if let Some(explanation) = &hit.explanation {
+            print_search_explanation(explanation);
+        }
+        for chunk in &hit.context {
+            println!(
+                "      context[ord={}{}]: {}",
+                chunk.ord,
+                if chunk.matched { ",matched" } else { "" },
+                chunk.text.split_whitespace().collect::<Vec<_>>().join(" ")
+            );
+        }
+    }
+}
+
+fn print_search_explanation(explanation: &SearchExplanation) {
+    let bm25 = explanation
+        .bm25_base
+        .map_or("-".to_string(), |b| format!("{b:.4}"));
+    match &explanation.boost {
+        Some(boost) => println!(
+            "      explain: bm25_base={bm25} title_overlap={:.4} term_overlap={:.4} exact_phrase={:.4}",
+            boost.title_overlap, boost.term_overlap, boost.exact_phrase
+        ),
+        None => println!("      explain: bm25_base={bm25} boost=-"),
+    }
+    if let Some(hybrid) = &explanation.hybrid {
+        let rank = |r: Option<usize>| r.map_or("-".to_string(), |r| r.to_string());
+        println!(
+            "      explain: lexical_rank={} lexical_rrf={:.4} semantic_rank={} semantic_rrf={:.4} strength={:.6}",
+            rank(hybrid.lexical_rank),
+            hybrid.lexical_rrf,
+            rank(hybrid.semantic_rank),
+            hybrid.semantic_rrf,
+            hybrid.strength
         );
     }
 }
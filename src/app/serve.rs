@@ -0,0 +1,152 @@
+use super::*;
+use axum::Json;
+use axum::extract::{Path as UrlPath, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Settings shared by every request handler.
+struct ServeState {
+    /// Resolved search database (`--db`, `FOUNDRY_SEARCH_DB`, or the default).
+    db: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImpactQuery {
+    depth: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    q: String,
+    mode: Option<String>,
+    top_k: Option<usize>,
+}
+
+pub(super) fn run_serve(args: &ServeArgs) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_io()
+        .build()
+        .context("failed to start async runtime")?;
+    let state = Arc::new(ServeState {
+        db: search_db_path(args.db.as_deref().map(Path::new)),
+    });
+    runtime.block_on(async {
+        let app = axum::Router::new()
+            .route("/nodes", get(list_nodes))
+            .route("/nodes/{id}", get(get_node))
+            .route("/nodes/{id}/impact", get(node_impact))
+            .route("/search", get(search))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind((args.bind.as_str(), args.port))
+            .await
+            .with_context(|| format!("failed to bind {}:{}", args.bind, args.port))?;
+        let addr = listener.local_addr()?;
+        println!("spec serve: listening on http://{addr}");
+        std::io::stdout().flush()?;
+        axum::serve(listener, app).await.context("server error")
+    })
+}
+
+// The graph is reloaded on every request so edits on disk show up without a
+// restart; fine for the graph sizes this tool targets.
+fn load_graph() -> Result<SpecGraph> {
+    let metas = load_all_meta(Path::new("spec"), &mut LintState::default())?;
+    Ok(SpecGraph::from_nodes(metas.into_iter().map(|(_, meta)| meta)))
+}
+
+// Handlers do file and SQLite I/O (and hybrid search may build a blocking
+// HTTP embedder), so the work runs on tokio's blocking pool, never on a
+// runtime worker.
+async fn run_blocking(work: impl FnOnce() -> Response + Send + 'static) -> Response {
+    tokio::task::spawn_blocking(work)
+        .await
+        .unwrap_or_else(|err| {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("request failed: {err}"))
+        })
+}
+
+async fn list_nodes() -> Response {
+    run_blocking(|| {
+        let graph = match load_graph() {
+            Ok(graph) => graph,
+            Err(err) => {
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}"));
+            }
+        };
+        let mut nodes = graph.into_nodes().into_values().collect::<Vec<_>>();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        Json(nodes).into_response()
+    })
+    .await
+}
+
+async fn get_node(UrlPath(id): UrlPath<String>) -> Response {
+    run_blocking(move || {
+        let graph = match load_graph() {
+            Ok(graph) => graph,
+            Err(err) => {
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}"));
+            }
+        };
+        match graph.get(&id) {
+            Some(meta) => Json(meta).into_response(),
+            None => error_response(StatusCode::NOT_FOUND, format!("node not found: {id}")),
+        }
+    })
+    .await
+}
+
+async fn node_impact(UrlPath(id): UrlPath<String>, Query(query): Query<ImpactQuery>) -> Response {
+    run_blocking(move || {
+        let graph = match load_graph() {
+            Ok(graph) => graph,
+            Err(err) => {
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}"));
+            }
+        };
+        match graph.impact(&id, query.depth.unwrap_or(2)) {
+            Some(report) => Json(report).into_response(),
+            None => error_response(StatusCode::NOT_FOUND, format!("node not found: {id}")),
+        }
+    })
+    .await
+}
+
+async fn search(
+    State(state): State<Arc<ServeState>>,
+    Query(params): Query<SearchParams>,
+) -> Response {
+    let mode = match params.mode.as_deref().unwrap_or("lexical") {
+        "lexical" => SearchMode::Lexical,
+        "hybrid" => SearchMode::Hybrid,
+        "rerank" => SearchMode::Rerank,
+        other => {
+            return error_response(StatusCode::BAD_REQUEST, format!("invalid mode: {other}"));
+        }
+    };
+    let args = SearchQueryArgs {
+        query: params.q,
+        top_k: params.top_k.unwrap_or(10),
+        format: SearchFormat::Json,
+        mode,
+        node_types: Vec::new(),
+        statuses: Vec::new(),
+        explain: false,
+        context: 0,
+        normalize: None,
+        modified_after: None,
+        field: SearchField::Body,
+    };
+    run_blocking(move || match build_search_query_output(&args, Some(&state.db)) {
+        Ok(output) => Json(output).into_response(),
+        Err(err) => error_response(StatusCode::BAD_REQUEST, format!("{err:#}")),
+    })
+    .await
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+pub(super) fn run_term(term: TermCommand) -> Result<()> {
+    match term.command {
+        TermSubcommand::Rename(args) => run_term_rename(&args),
+    }
+}
+
+fn run_term_rename(args: &TermRenameArgs) -> Result<()> {
+    let metas = load_all_meta(Path::new("spec"), &mut LintState::default())?;
+    let (key, canonical) = match (&args.canonicalize, &args.from, &args.to) {
+        (Some(key), _, _) => {
+            let key = normalize_term_key(key);
+            let canonical = most_common_variant(&metas, &key)
+                .with_context(|| format!("no terms normalize to key '{key}'"))?;
+            (key, canonical)
+        }
+        (None, Some(from), Some(to)) => (normalize_term_key(from), to.trim().to_string()),
+        _ => anyhow::bail!("term rename requires --from and --to, or --canonicalize"),
+    };
+    if key.is_empty() {
+        anyhow::bail!("term has no alphanumeric characters");
+    }
+    if normalize_term_key(&canonical).is_empty() {
+        anyhow::bail!("invalid --to term: '{canonical}'");
+    }
+
+    let mut updated = Vec::new();
+    for (path, mut meta) in metas {
+        if !rename_terms(&mut meta.terms, &key, &canonical) {
+            continue;
+        }
+        write_meta_json(&path, &meta)?;
+        println!("term rename: updated {} ({})", path.display(), meta.id);
+        updated.push(meta.id);
+    }
+    if !updated.is_empty() {
+        record_audit("term rename", "rename", &updated);
+    }
+    println!(
+        "term rename summary: key={key} canonical={canonical} updated={}",
+        updated.len()
+    );
+    Ok(())
+}
+
+/// Replaces every term normalizing to `key` with `canonical`, keeping the
+/// first position and dropping the duplicates the rename creates.
+/// Returns whether `terms` changed.
+fn rename_terms(terms: &mut Vec<String>, key: &str, canonical: &str) -> bool {
+    if !terms.iter().any(|t| normalize_term_key(t) == key) {
+        return false;
+    }
+    let before = terms.clone();
+    let mut placed = false;
+    terms.retain_mut(|term| {
+        if normalize_term_key(term) != key && term != canonical {
+            return true;
+        }
+        if placed {
+            return false;
+        }
+        placed = true;
+        *term = canonical.to_string();
+        true
+    });
+    *terms != before
+}
+
+/// The spelling of `key` used by the most nodes; ties go to the
+/// lexicographically smallest variant so the choice is stable.
+fn most_common_variant(metas: &[(PathBuf, SpecNodeMeta)], key: &str) -> Option<String> {
+    let mut counts = BTreeMap::<&str, usize>::new();
+    for (_, meta) in metas {
+        let variants = meta
+            .terms
+            .iter()
+            .filter(|t| normalize_term_key(t) == key)
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        for variant in variants {
+            *counts.entry(variant).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(variant, _)| variant.to_string())
+}
@@ -1,4 +1,5 @@
     use super::*;
+    use foundry::bfs_review_order;
 
     fn node(id: &str, edges: Vec<SpecEdge>) -> SpecNodeMeta {
         SpecNodeMeta {
@@ -10,6 +11,9 @@
             terms: Vec::new(),
             hash: "0".repeat(64),
             edges,
+            owner: String::new(),
+            priority: String::new(),
+            schema_version: META_SCHEMA_VERSION,
         }
     }
 
@@ -19,12 +23,48 @@
         assert_eq!(title, "Hello");
     }
 
+    #[test]
+    fn extract_title_accepts_tab_after_hash() {
+        let title = extract_title("#\tTabbed Title\n\ntext", Path::new("spec/a.md"));
+        assert_eq!(title, "Tabbed Title");
+    }
+
+    #[test]
+    fn extract_title_detects_setext_heading() {
+        let title = extract_title("Setext Title\n=====\n\ntext", Path::new("spec/a.md"));
+        assert_eq!(title, "Setext Title");
+    }
+
+    #[test]
+    fn extract_title_ignores_lower_level_headings() {
+        let title = extract_title("## Section\n\ntext", Path::new("spec/fallback-name.md"));
+        assert_eq!(title, "fallback-name");
+    }
+
+    #[test]
+    fn extract_title_does_not_treat_atx_heading_as_setext_text() {
+        let body = "## Section\n===\n\ntext";
+        let title = extract_title(body, Path::new("spec/fallback-name.md"));
+        assert_eq!(title, "fallback-name");
+    }
+
     #[test]
     fn extract_title_falls_back_to_filename() {
         let title = extract_title("no heading", Path::new("spec/fallback-name.md"));
         assert_eq!(title, "fallback-name");
     }
 
+    #[test]
+    fn extract_title_skips_front_matter() {
+        let body = "---\ntitle: Not This\nowner: team\n---\n# Real Title\n\ntext";
+        assert_eq!(extract_title(body, Path::new("spec/a.md")), "Real Title");
+        assert_eq!(
+            split_front_matter(body).0,
+            Some("---\ntitle: Not This\nowner: team\n---\n")
+        );
+        assert_eq!(split_front_matter("---\nunclosed\n# T").0, None);
+    }
+
     #[test]
     fn md_to_meta_path_converts_suffix() {
         let path = md_to_meta_path(Path::new("spec/10-domain-model.md")).unwrap();
@@ -51,6 +91,16 @@
         assert_eq!(next_available_id(&ids), 11);
     }
 
+    #[test]
+    fn allocate_id_dense_fills_lowest_gap() {
+        let mut ids = HashSet::from(["SPC-001".to_string(), "SPC-003".to_string()]);
+        assert_eq!(allocate_id(&mut ids, IdAllocation::Dense), "SPC-002");
+        assert_eq!(allocate_id(&mut ids, IdAllocation::Dense), "SPC-004");
+
+        let mut ids = HashSet::from(["SPC-001".to_string(), "SPC-003".to_string()]);
+        assert_eq!(allocate_id(&mut ids, IdAllocation::Append), "SPC-004");
+    }
+
     #[test]
     fn bfs_review_order_follows_link_types() {
         let mut map = HashMap::new();
@@ -65,6 +115,9 @@
                         rationale: "dep".to_string(),
                         confidence: 1.0,
                         status: "confirmed".to_string(),
+                        created_at: None,
+                        updated_at: None,
+                        source: None,
                     },
                     SpecEdge {
                         to: "SPC-004".to_string(),
@@ -72,6 +125,9 @@
                         rationale: "conflict".to_string(),
                         confidence: 1.0,
                         status: "confirmed".to_string(),
+                        created_at: None,
+                        updated_at: None,
+                        source: None,
                     },
                 ],
             ),
@@ -87,12 +143,15 @@
                     rationale: "test".to_string(),
                     confidence: 1.0,
                     status: "confirmed".to_string(),
+                    created_at: None,
+                    updated_at: None,
+                    source: None,
                 }],
             ),
         );
         map.insert("SPC-004".to_string(), node("SPC-004", Vec::new()));
 
-        let order = bfs_review_order("SPC-001", 3, &map);
+        let order = bfs_review_order("SPC-001", 3, &map, &EdgeFilter::default());
 
         assert!(order.contains(&"SPC-001".to_string()));
         assert!(order.contains(&"SPC-002".to_string()));
@@ -113,6 +172,9 @@
                     rationale: "dep".to_string(),
                     confidence: 1.0,
                     status: "confirmed".to_string(),
+                    created_at: None,
+                    updated_at: None,
+                    source: None,
                 }],
             ),
         );
@@ -126,12 +188,15 @@
                     rationale: "dep".to_string(),
                     confidence: 1.0,
                     status: "confirmed".to_string(),
+                    created_at: None,
+                    updated_at: None,
+                    source: None,
                 }],
             ),
         );
         map.insert("SPC-003".to_string(), node("SPC-003", Vec::new()));
 
-        let depth1 = bfs_review_order("SPC-001", 1, &map);
+        let depth1 = bfs_review_order("SPC-001", 1, &map, &EdgeFilter::default());
         assert!(depth1.contains(&"SPC-001".to_string()));
         assert!(depth1.contains(&"SPC-002".to_string()));
         assert!(!depth1.contains(&"SPC-003".to_string()));
@@ -155,15 +220,18 @@
             terms: vec![],
             hash: "not-a-hash".to_string(),
             edges: vec![],
+            owner: String::new(),
+            priority: String::new(),
+            schema_version: META_SCHEMA_VERSION,
         };
         let mut lint = LintState::default();
         validate_meta_semantics(Path::new("spec/a.meta.json"), &meta, &mut lint);
-        assert!(lint.errors.iter().any(|e| e.contains("invalid node id format")));
-        assert!(lint.errors.iter().any(|e| e.contains("invalid node type")));
-        assert!(lint.errors.iter().any(|e| e.contains("invalid node status")));
-        assert!(lint.errors.iter().any(|e| e.contains("empty title")));
-        assert!(lint.errors.iter().any(|e| e.contains("invalid body_md_path format")));
-        assert!(lint.errors.iter().any(|e| e.contains("invalid hash format")));
+        assert!(lint.findings.iter().any(|f| f.message.contains("invalid node id format")));
+        assert!(lint.findings.iter().any(|f| f.message.contains("invalid node type")));
+        assert!(lint.findings.iter().any(|f| f.message.contains("invalid node status")));
+        assert!(lint.findings.iter().any(|f| f.message.contains("empty title")));
+        assert!(lint.findings.iter().any(|f| f.message.contains("invalid body_md_path format")));
+        assert!(lint.findings.iter().any(|f| f.message.contains("invalid hash format")));
     }
 
     #[test]
@@ -176,26 +244,129 @@
     #[test]
     fn split_into_chunks_splits_long_text() {
         let text = "Sentence one. Sentence two is long enough to force splitting. Sentence three keeps going with more words. Sentence four concludes.";
-        let chunks = split_into_chunks(text, 40);
+        let chunks = split_into_chunks(text, 40, default_chunk_overlap(40));
         assert!(chunks.len() >= 2);
         assert!(chunks.iter().all(|c| !c.trim().is_empty()));
     }
 
+    #[test]
+    fn split_into_chunks_measures_multibyte_text_in_chars() {
+        let sentence = "認証フローはセッションを検証し、期限切れのトークンを拒否します。";
+        let text = sentence.repeat(40);
+        let target = 120;
+        let chunks = split_into_chunks(&text, target, default_chunk_overlap(target));
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            let len = chunk.chars().count();
+            assert!(len <= target, "chunk has {len} chars, target {target}");
+        }
+        // Byte-based sizing would have produced chunks of roughly a third of the target.
+        assert!(chunks[0].chars().count() > target / 2);
+    }
+
     #[test]
     fn semantic_similarity_prefers_related_text() {
-        let q = semantic_vector("authorization policy");
-        let related = semantic_vector("authorization rules and policy for access");
-        let unrelated = semantic_vector("invoice tax and payment details");
+        let config = SearchRuntimeConfig::default();
+        let mut cache = SemanticBucketCache::default();
+        let q = semantic_vector("authorization policy", &config, &mut cache);
+        let related = semantic_vector(
+            "authorization rules and policy for access",
+            &config,
+            &mut cache,
+        );
+        let unrelated = semantic_vector("invoice tax and payment details", &config, &mut cache);
         assert!(cosine_similarity(&q, &related) > cosine_similarity(&q, &unrelated));
     }
 
+    #[test]
+    fn cosine_similarity_normalizes_raw_vectors() {
+        let a = [3.0, 4.0];
+        let b = [30.0, 40.0];
+        assert!((cosine_similarity(&a, &b) - 1.0).abs() < 1e-12);
+        assert!((cosine_similarity(&a, &[-6.0, -8.0]) + 1.0).abs() < 1e-12);
+        assert!(cosine_similarity(&a, &[4.0, -3.0]).abs() < 1e-12);
+        let skewed = cosine_similarity(&[10.0, 0.0, 2.0], &[0.5, 7.0, 0.0]);
+        assert!((-1.0..=1.0).contains(&skewed));
+        assert!((skewed - 5.0 / (104.0_f64.sqrt() * 49.25_f64.sqrt())).abs() < 1e-12);
+        assert_eq!(cosine_similarity(&a, &[0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn semantic_vector_is_bit_identical_across_calls() {
+        let text = "authorization policy for access tokens and access rules";
+        let config = SearchRuntimeConfig::default();
+        let first = semantic_vector(text, &config, &mut SemanticBucketCache::default());
+        let second = semantic_vector(text, &config, &mut SemanticBucketCache::default());
+        assert_eq!(first.len(), DEFAULT_EMBEDDING_DIM);
+        assert!(
+            first
+                .iter()
+                .zip(second.iter())
+                .all(|(a, b)| a.to_bits() == b.to_bits())
+        );
+    }
+
+    #[test]
+    fn semantic_vector_warm_cache_matches_fresh_cache() {
+        let config = SearchRuntimeConfig::default();
+        let mut warm = SemanticBucketCache::default();
+        for text in [
+            "access tokens rotate daily",
+            "access tokens expire",
+            "access tokens rotate daily",
+        ] {
+            let cached = semantic_vector(text, &config, &mut warm);
+            let fresh = semantic_vector(text, &config, &mut SemanticBucketCache::default());
+            assert!(
+                cached
+                    .iter()
+                    .zip(fresh.iter())
+                    .all(|(a, b)| a.to_bits() == b.to_bits())
+            );
+        }
+    }
+
     #[test]
     fn ranking_boost_favors_title_phrase_match() {
-        let boost = ranking_boost("checkout flow", "Checkout Flow", &[]);
-        let low = ranking_boost("checkout flow", "Payment module", &[]);
+        let config = SearchRuntimeConfig::default();
+        let boost = ranking_boost("checkout flow", "Checkout Flow", &[], &config);
+        let low = ranking_boost("checkout flow", "Payment module", &[], &config);
         assert!(boost > low);
     }
 
+    #[test]
+    fn stem_token_collapses_inflections() {
+        assert_eq!(stem_token("logging"), "log");
+        assert_eq!(stem_token("logs"), "log");
+        assert_eq!(stem_token("logged"), "log");
+        assert_eq!(stem_token("policies"), stem_token("policy"));
+        assert_eq!(stem_token("access"), "access");
+    }
+
+    #[test]
+    fn matched_terms_uses_stemming_only_when_enabled() {
+        let terms = vec!["logs".to_string()];
+        let stemmed = SearchRuntimeConfig {
+            stemming: true,
+            ..SearchRuntimeConfig::default()
+        };
+        let exact = SearchRuntimeConfig::default();
+        assert_eq!(matched_terms("logging", &terms, &stemmed), terms);
+        assert!(matched_terms("logging", &terms, &exact).is_empty());
+    }
+
+    #[test]
+    fn ranking_boost_ignores_stopwords() {
+        let config = SearchRuntimeConfig {
+            stopwords: true,
+            ..SearchRuntimeConfig::default()
+        };
+        let boost = ranking_boost("the flow", "The Payment", &[], &config);
+        assert_eq!(boost, 0.0);
+        let no_filter = SearchRuntimeConfig::default();
+        assert!(ranking_boost("the flow", "The Payment", &[], &no_filter) > 0.0);
+    }
+
     #[test]
     fn vector_blob_roundtrip() {
         let vec = vec![0.1, -0.5, 1.25, 3.0];
@@ -215,6 +386,43 @@
         assert!(json.contains(','));
     }
 
+    #[test]
+    fn hybrid_fused_score_prefers_strong_semantic_match_at_equal_rank() {
+        let config = SearchRuntimeConfig::default();
+        let weak_lexical_only = hybrid_fused_score(Some((1, 0.01)), None, &config);
+        let strong_semantic_only = hybrid_fused_score(None, Some((1, 0.92)), &config);
+        assert!(strong_semantic_only > weak_lexical_only);
+        // Appearing in both lists still beats any single-list hit.
+        let both = hybrid_fused_score(Some((5, 0.01)), Some((5, 0.3)), &config);
+        assert!(both > strong_semantic_only);
+    }
+
+    #[test]
+    fn hybrid_fused_score_semantic_weight_reorders_disagreeing_ranks() {
+        let lexical_favorite = (Some((1, 0.5)), Some((2, 0.5)));
+        let semantic_favorite = (Some((3, 0.5)), Some((1, 0.5)));
+        let score = |(lexical, semantic), config: &SearchRuntimeConfig| {
+            hybrid_fused_score(lexical, semantic, config)
+        };
+
+        let config = SearchRuntimeConfig::default();
+        assert!(score(lexical_favorite, &config) > score(semantic_favorite, &config));
+
+        let config = SearchRuntimeConfig {
+            semantic_weight: 3.0,
+            ..SearchRuntimeConfig::default()
+        };
+        assert!(score(semantic_favorite, &config) > score(lexical_favorite, &config));
+        assert!(
+            SearchRuntimeConfig {
+                rrf_k: -1.0,
+                ..SearchRuntimeConfig::default()
+            }
+            .validate_fusion()
+            .is_err()
+        );
+    }
+
     #[test]
     fn normalize_query_for_fts_removes_punctuation() {
         let normalized = normalize_query_for_fts("How does auth-flow work?");
@@ -241,6 +449,9 @@
                         rationale: "dep".to_string(),
                         confidence: 1.0,
                         status: "confirmed".to_string(),
+                        created_at: None,
+                        updated_at: None,
+                        source: None,
                     },
                     SpecEdge {
                         to: "SPC-003".to_string(),
@@ -248,8 +459,14 @@
                         rationale: "risk".to_string(),
                         confidence: 1.0,
                         status: "confirmed".to_string(),
+                        created_at: None,
+                        updated_at: None,
+                        source: None,
                     },
                 ],
+                owner: String::new(),
+                priority: String::new(),
+                schema_version: META_SCHEMA_VERSION,
             },
         );
         map.insert(
@@ -268,7 +485,13 @@
                     rationale: "test".to_string(),
                     confidence: 1.0,
                     status: "confirmed".to_string(),
+                    created_at: None,
+                    updated_at: None,
+                    source: None,
                 }],
+                owner: String::new(),
+                priority: String::new(),
+                schema_version: META_SCHEMA_VERSION,
             },
         );
         let hits = vec![SearchHit {
@@ -276,17 +499,89 @@
             title: "A".to_string(),
             path: "spec/a.md".to_string(),
             score: 0.5,
+            normalized_score: None,
             matched_terms: vec![],
             snippet: "x".to_string(),
+            highlights: vec![],
+            fuzzy: false,
+            explanation: None,
+            chunk_ord: None,
+            context: Vec::new(),
+            updated_at: None,
         }];
-        let (related, conflicts) =
-            ask::expand_ask_context(&hits, &map, 10, &AskEdgeWeightConfig::default());
+        let (related, conflicts) = ask::expand_ask_context(
+            &hits,
+            &map,
+            10,
+            &AskEdgeWeightConfig::default(),
+            &EdgeFilter::default(),
+        );
         assert!(related.contains(&"SPC-002".to_string()));
         assert!(related.contains(&"SPC-003".to_string()));
         assert!(related.contains(&"SPC-004".to_string()));
         assert!(conflicts.contains(&"SPC-003".to_string()));
     }
 
+    #[test]
+    fn expand_ask_context_prefers_high_confidence_neighbors() {
+        let edge = |to: &str, confidence: f64, status: &str| SpecEdge {
+            to: to.to_string(),
+            edge_type: "depends_on".to_string(),
+            rationale: "dep".to_string(),
+            confidence,
+            status: status.to_string(),
+            created_at: None,
+            updated_at: None,
+            source: None,
+        };
+        let mut map = HashMap::new();
+        map.insert(
+            "SPC-001".to_string(),
+            node(
+                "SPC-001",
+                vec![
+                    edge("SPC-002", 0.3, "confirmed"),
+                    edge("SPC-003", 0.9, "confirmed"),
+                    edge("SPC-004", 0.9, "proposed"),
+                ],
+            ),
+        );
+        let hits = vec![SearchHit {
+            id: "SPC-001".to_string(),
+            title: "A".to_string(),
+            path: "spec/a.md".to_string(),
+            score: 0.5,
+            normalized_score: None,
+            matched_terms: vec![],
+            snippet: "x".to_string(),
+            highlights: vec![],
+            fuzzy: false,
+            explanation: None,
+            chunk_ord: None,
+            context: Vec::new(),
+            updated_at: None,
+        }];
+        let (related, _) = ask::expand_ask_context(
+            &hits,
+            &map,
+            3,
+            &AskEdgeWeightConfig::default(),
+            &EdgeFilter::default(),
+        );
+        assert_eq!(related, vec!["SPC-003", "SPC-004", "SPC-002"]);
+    }
+
+    #[test]
+    fn marked_snippet_round_trips_to_highlighted_text() {
+        let (snippet, highlights) = parse_marked_snippet("...rotate \u{2}token\u{3} daily");
+        assert_eq!(snippet, "...rotate token daily");
+        assert_eq!(highlights, vec![[10, 15]]);
+        assert_eq!(
+            render_highlighted_snippet(&snippet, &highlights),
+            "...rotate **token** daily"
+        );
+    }
+
     #[test]
     fn load_runtime_config_defaults_when_missing() {
         let cfg = load_runtime_config();
@@ -313,7 +608,13 @@
                     rationale: "dep".to_string(),
                     confidence: 1.0,
                     status: "confirmed".to_string(),
+                    created_at: None,
+                    updated_at: None,
+                    source: None,
                 }],
+                owner: String::new(),
+                priority: String::new(),
+                schema_version: META_SCHEMA_VERSION,
             },
         );
         map.insert(
@@ -327,6 +628,9 @@
                 terms: vec![],
                 hash: "0".repeat(64),
                 edges: vec![],
+                owner: String::new(),
+                priority: String::new(),
+                schema_version: META_SCHEMA_VERSION,
             },
         );
         let hits = vec![SearchHit {
@@ -334,8 +638,15 @@
             title: "Root".to_string(),
             path: "spec/root.md".to_string(),
             score: 0.5,
+            normalized_score: None,
             matched_terms: vec![],
             snippet: "root".to_string(),
+            highlights: vec![],
+            fuzzy: false,
+            explanation: None,
+            chunk_ord: None,
+            context: Vec::new(),
+            updated_at: None,
         }];
         let exps = ask::build_ask_explanations(
             "root dependency",
@@ -347,3 +658,103 @@
         assert!(exps.iter().any(|e| e.id == "SPC-002" && e.reason.contains("graph neighbor")));
         assert!(exps.iter().any(|e| e.id == "SPC-002" && e.reason.contains("w=")));
     }
+
+    #[test]
+    fn synthesize_ask_output_cites_each_id_once() {
+        let depends_on = |to: &str| SpecEdge {
+            to: to.to_string(),
+            edge_type: "depends_on".to_string(),
+            rationale: "dep".to_string(),
+            confidence: 1.0,
+            status: "confirmed".to_string(),
+            created_at: None,
+            updated_at: None,
+            source: None,
+        };
+        // SPC-002 is both a hit and a neighbor of SPC-001; SPC-003 is a
+        // neighbor of both hits.
+        let map = HashMap::from([
+            (
+                "SPC-001".to_string(),
+                node(
+                    "SPC-001",
+                    vec![depends_on("SPC-002"), depends_on("SPC-003")],
+                ),
+            ),
+            (
+                "SPC-002".to_string(),
+                node("SPC-002", vec![depends_on("SPC-003")]),
+            ),
+            ("SPC-003".to_string(), node("SPC-003", vec![])),
+        ]);
+        let hit = |id: &str, score: f64| SearchHit {
+            id: id.to_string(),
+            title: id.to_string(),
+            path: format!("spec/{id}.md"),
+            score,
+            normalized_score: None,
+            matched_terms: vec![],
+            snippet: format!("{id} at {score}"),
+            highlights: vec![],
+            fuzzy: false,
+            explanation: None,
+            chunk_ord: None,
+            context: Vec::new(),
+            updated_at: None,
+        };
+        let hits = vec![
+            hit("SPC-001", 0.9),
+            hit("SPC-002", 0.2),
+            hit("SPC-002", 0.6),
+        ];
+        let args = AskArgs {
+            question: Some("dependencies".to_string()),
+            questions_file: None,
+            top_k: 5,
+            offset: 0,
+            mode: SearchMode::Lexical,
+            format: AskFormat::Json,
+            explain: false,
+            max_context_chars: None,
+            cite_bodies: false,
+            min_confidence: 0.0,
+            confirmed_only: false,
+            no_graph: false,
+            db: None,
+        };
+        let output = ask::synthesize_ask_output(&args, hits, &map, &AskRuntimeConfig::default());
+
+        let cited = output
+            .citations
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(cited, ["SPC-001", "SPC-002", "SPC-003"]);
+        let evidence = output
+            .evidence
+            .iter()
+            .map(|e| (e.id.as_str(), e.score))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            evidence,
+            [("SPC-001", 0.9), ("SPC-002", 0.6), ("SPC-003", 0.0)]
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_accepts_unix_seconds_and_rfc3339() {
+        assert_eq!(parse_timestamp("1700000000"), Some(1_700_000_000));
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2001-09-09T01:46:40Z"), Some(1_000_000_000));
+        assert_eq!(
+            parse_timestamp("2001-09-09T03:46:40.250+02:00"),
+            Some(1_000_000_000)
+        );
+        assert_eq!(
+            parse_timestamp("2024-02-29T12:00:00-00:30"),
+            Some(1_709_209_800)
+        );
+        assert_eq!(parse_timestamp("2023-02-29T12:00:00Z"), None);
+        assert_eq!(parse_timestamp("2024-01-01T12:00:00"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
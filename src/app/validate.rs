@@ -0,0 +1,93 @@
+use super::*;
+
+const SPEC_NODE_META_SCHEMA: &str = include_str!("../../schemas/spec-node-meta.schema.json");
+
+#[derive(Debug, Serialize)]
+struct SchemaViolation {
+    path: String,
+    pointer: String,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateSchemaOutput {
+    ok: bool,
+    checked: usize,
+    violation_count: usize,
+    violations: Vec<SchemaViolation>,
+}
+
+pub(super) fn run_validate(validate: ValidateCommand) -> Result<i32> {
+    match validate.command {
+        ValidateSubcommand::Schema(args) => run_validate_schema(&args),
+    }
+}
+
+fn run_validate_schema(args: &ValidateSchemaArgs) -> Result<i32> {
+    let schema: serde_json::Value =
+        serde_json::from_str(SPEC_NODE_META_SCHEMA).context("embedded meta schema is invalid json")?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|err| anyhow::anyhow!("embedded meta schema is invalid: {err}"))?;
+
+    let files = if args.paths.is_empty() {
+        find_meta_files(Path::new("spec"))
+    } else {
+        args.paths.iter().map(PathBuf::from).collect()
+    };
+
+    let mut violations = Vec::new();
+    for path in &files {
+        let display = normalize_path(path).to_string_lossy().to_string();
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed reading meta file: {}", path.display()))?;
+        let instance = match serde_json::from_str::<serde_json::Value>(&raw) {
+            Ok(v) => v,
+            Err(err) => {
+                violations.push(SchemaViolation {
+                    path: display,
+                    pointer: String::new(),
+                    message: format!("invalid json: {err}"),
+                });
+                continue;
+            }
+        };
+        for err in validator.iter_errors(&instance) {
+            violations.push(SchemaViolation {
+                path: display.clone(),
+                pointer: err.instance_path.to_string(),
+                message: err.to_string(),
+            });
+        }
+    }
+
+    let output = ValidateSchemaOutput {
+        ok: violations.is_empty(),
+        checked: files.len(),
+        violation_count: violations.len(),
+        violations,
+    };
+    match args.format {
+        ValidateFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        ValidateFormat::Table => print_validate_schema_table(&output),
+    }
+    if output.ok {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
+
+fn print_validate_schema_table(output: &ValidateSchemaOutput) {
+    if output.ok {
+        println!("validate schema: ok (checked={})", output.checked);
+        return;
+    }
+    for v in &output.violations {
+        let pointer = if v.pointer.is_empty() { "/" } else { v.pointer.as_str() };
+        println!("validate schema: violation: {} {}: {}", v.path, pointer, v.message);
+    }
+    println!(
+        "validate schema summary: checked={} violations={}",
+        output.checked, output.violation_count
+    );
+}
@@ -25,25 +25,45 @@ fn run_write_internal(args: &WriteArgs, emit_log: bool) -> Result<String> {
         }
     }
 
-    if let Some(body_file) = &args.body_file {
-        let body = fs::read_to_string(body_file)
-            .with_context(|| format!("failed reading --body-file: {body_file}"))?;
-        fs::write(&md_path, body)
-            .with_context(|| format!("failed writing markdown: {}", md_path.display()))?;
-    } else if let Some(body) = &args.body {
-        fs::write(&md_path, body)
-            .with_context(|| format!("failed writing markdown: {}", md_path.display()))?;
+    let incoming = match (&args.body, &args.body_file) {
+        (Some(body), _) => Some(body.clone()),
+        (None, Some(body_file)) => Some(
+            fs::read_to_string(body_file)
+                .with_context(|| format!("failed reading --body-file: {body_file}"))?,
+        ),
+        (None, None) => None,
+    };
+    if args.append && incoming.is_none() {
+        anyhow::bail!("--append requires --body or --body-file");
     }
+    let front_matter = parse_front_matter_args(&args.front_matter)?;
+    let existing = if md_path.exists() {
+        Some(
+            fs::read_to_string(&md_path)
+                .with_context(|| format!("failed reading markdown: {}", md_path.display()))?,
+        )
+    } else {
+        None
+    };
 
-    if !md_path.exists() {
-        anyhow::bail!(
+    let mut body = match (incoming, existing.as_deref()) {
+        (Some(new), Some(old)) if args.append => append_markdown(old, &new),
+        (Some(new), Some(old)) => keep_front_matter(old, new),
+        (Some(new), None) => new,
+        (None, Some(old)) => old.to_string(),
+        (None, None) => anyhow::bail!(
             "markdown file not found: {} (use --body or --body-file to create it)",
             md_path.display()
-        );
+        ),
+    };
+    if !front_matter.is_empty() {
+        body = set_front_matter(&body, &front_matter);
+    }
+    if existing.as_deref() != Some(body.as_str()) {
+        fs::write(&md_path, &body)
+            .with_context(|| format!("failed writing markdown: {}", md_path.display()))?;
     }
 
-    let body = fs::read_to_string(&md_path)
-        .with_context(|| format!("failed reading markdown: {}", md_path.display()))?;
     let body_hash = sha256_hex(body.as_bytes());
     let title = args
         .title
@@ -53,7 +73,6 @@ fn run_write_internal(args: &WriteArgs, emit_log: bool) -> Result<String> {
     let meta_path = md_to_meta_path(&md_path)?;
 
     let mut existing_ids = load_existing_ids(Path::new("spec"))?;
-    let next_id = next_available_id(&existing_ids);
 
     let mut created = false;
     let mut meta = if meta_path.exists() {
@@ -72,6 +91,9 @@ fn run_write_internal(args: &WriteArgs, emit_log: bool) -> Result<String> {
             terms: Vec::new(),
             hash: String::new(),
             edges: Vec::new(),
+            owner: String::new(),
+            priority: String::new(),
+            schema_version: META_SCHEMA_VERSION,
         }
     };
 
@@ -82,7 +104,7 @@ fn run_write_internal(args: &WriteArgs, emit_log: bool) -> Result<String> {
         }
         meta.id = id.clone();
     } else if meta.id.trim().is_empty() {
-        meta.id = format!("SPC-{next_id:03}");
+        meta.id = allocate_id(&mut existing_ids, load_runtime_config().id_allocation);
     }
     existing_ids.insert(meta.id.clone());
 
@@ -104,27 +126,230 @@ fn run_write_internal(args: &WriteArgs, emit_log: bool) -> Result<String> {
         meta.status = "draft".to_string();
     }
 
+    if let Some(priority) = &args.priority {
+        if !NODE_PRIORITIES.contains(&priority.as_str()) {
+            anyhow::bail!("invalid node priority: {priority}");
+        }
+        meta.priority = priority.clone();
+    }
+
     meta.title = title;
     meta.body_md_path = body_md_path;
     meta.hash = body_hash;
     if !args.terms.is_empty() {
         meta.terms = args.terms.clone();
     }
+    if args.auto_terms {
+        let limit = load_runtime_config().write.auto_terms_limit;
+        let mut seen = meta
+            .terms
+            .iter()
+            .map(|t| normalize_term_key(t))
+            .collect::<HashSet<_>>();
+        let extracted = extract_candidate_terms(split_front_matter(&body).1)
+            .into_iter()
+            .filter(|t| seen.insert(normalize_term_key(t)))
+            .take(limit)
+            .collect::<Vec<_>>();
+        meta.terms.extend(extracted);
+    }
 
+    if args.backup {
+        backup_meta_json(&meta_path)?;
+    }
     write_meta_json(&meta_path, &meta)?;
     let action = if created { "created" } else { "updated" };
     if emit_log {
-        println!(
-            "spec write: {} id={} md={} meta={}",
-            action,
-            meta.id,
-            md_path.display(),
-            meta_path.display()
-        );
+        record_audit("write", action, std::slice::from_ref(&meta.id));
+        match args.format {
+            WriteFormat::Table => println!(
+                "spec write: {} id={} md={} meta={}",
+                action,
+                meta.id,
+                md_path.display(),
+                meta_path.display()
+            ),
+            WriteFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&WriteOutput {
+                    id: &meta.id,
+                    path: &meta.body_md_path,
+                    action,
+                    title: &meta.title,
+                    status: &meta.status,
+                })?
+            ),
+        }
     }
     Ok(meta.id)
 }
 
+#[derive(Serialize)]
+struct WriteOutput<'a> {
+    id: &'a str,
+    path: &'a str,
+    action: &'a str,
+    title: &'a str,
+    status: &'a str,
+}
+
+/// `old` followed by `new` as a new block, separated by one blank line.
+fn append_markdown(old: &str, new: &str) -> String {
+    let old = old.trim_end_matches(['\r', '\n']);
+    if old.is_empty() {
+        return new.to_string();
+    }
+    format!("{old}\n\n{new}")
+}
+
+/// A replacement body without front-matter inherits the block from `old`, so
+/// `--front-matter` values survive later full `--body` writes.
+fn keep_front_matter(old: &str, new: String) -> String {
+    match split_front_matter(old).0 {
+        Some(block) if split_front_matter(&new).0.is_none() => format!("{block}{new}"),
+        _ => new,
+    }
+}
+
+fn parse_front_matter_args(raw: &[String]) -> Result<Vec<(String, String)>> {
+    raw.iter()
+        .map(|item| {
+            let (key, value) = item
+                .split_once('=')
+                .with_context(|| format!("invalid --front-matter (expected key=value): {item}"))?;
+            let key = key.trim();
+            if key.is_empty() || key.contains(|c: char| c.is_whitespace() || c == ':') {
+                anyhow::bail!("invalid --front-matter key: {item}");
+            }
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Sets top-level `key: value` lines in the YAML front-matter block of
+/// `body`, creating the block when missing. Existing keys are replaced in
+/// place; other lines in the block are kept verbatim.
+fn set_front_matter(body: &str, entries: &[(String, String)]) -> String {
+    let (block, rest) = split_front_matter(body);
+    let mut lines = block
+        .map(|b| b.lines().skip(1).map(str::to_string).collect::<Vec<_>>())
+        .unwrap_or_default();
+    // Drop the closing delimiter; it is re-added below.
+    lines.pop();
+    for (key, value) in entries {
+        let line = format!("{key}: {}", yaml_scalar(value));
+        let prefix = format!("{key}:");
+        match lines.iter_mut().find(|l| l.starts_with(&prefix)) {
+            Some(existing) => *existing = line,
+            None => lines.push(line),
+        }
+    }
+    format!("---\n{}\n---\n{rest}", lines.join("\n"))
+}
+
+/// Plain YAML scalar when unambiguous, else a double-quoted one (a JSON
+/// string is valid YAML).
+fn yaml_scalar(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && !value.contains(": ")
+        && !value.contains(" #");
+    if plain {
+        value.to_string()
+    } else {
+        serde_json::to_string(value).expect("string serializes")
+    }
+}
+
+/// Candidate terms from a markdown body, most specific first: backticked
+/// identifiers, heading words, then runs of two or more capitalized words.
+/// Fenced code blocks and stopwords are skipped; results are deduped by
+/// `normalize_term_key`.
+pub(super) fn extract_candidate_terms(body: &str) -> Vec<String> {
+    let mut code_spans = Vec::new();
+    let mut heading_words = Vec::new();
+    let mut phrases = Vec::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        for (idx, span) in trimmed.split('`').enumerate() {
+            let span = span.trim();
+            if idx % 2 == 1 && (2..=64).contains(&span.chars().count()) && !span.contains(' ') {
+                code_spans.push(span.to_string());
+            }
+        }
+        let prose = trimmed
+            .split('`')
+            .enumerate()
+            .filter(|(idx, _)| idx % 2 == 0)
+            .map(|(_, part)| part)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if let Some(heading) = prose.strip_prefix('#') {
+            for word in heading.trim_start_matches('#').split(|c: char| {
+                !(c.is_alphanumeric() || c == '_' || c == '-')
+            }) {
+                let word = word.trim_matches('-');
+                if word.chars().count() >= 3 && !is_stopword(&word.to_lowercase()) {
+                    heading_words.push(word.to_string());
+                }
+            }
+            continue;
+        }
+
+        // A run ends at any lowercase word, at an empty token (two separators
+        // in a row), or after a word followed by clause punctuation, so
+        // "Auth. Session Token" yields "Session Token" only.
+        let is_separator = |c: char| !(c.is_alphanumeric() || c == '-');
+        let mut run = Vec::<&str>::new();
+        for token in prose.split_inclusive(is_separator) {
+            let (word, separator) = match token.char_indices().next_back() {
+                Some((idx, c)) if is_separator(c) => (&token[..idx], Some(c)),
+                _ => (token, None),
+            };
+            let capitalized = word.chars().next().is_some_and(char::is_uppercase)
+                && !is_stopword(&word.to_lowercase());
+            if capitalized {
+                run.push(word);
+            } else {
+                flush_phrase_run(&mut run, &mut phrases);
+            }
+            if matches!(separator, Some('.' | ',' | ';' | ':')) {
+                flush_phrase_run(&mut run, &mut phrases);
+            }
+        }
+        flush_phrase_run(&mut run, &mut phrases);
+    }
+
+    let mut seen = HashSet::new();
+    code_spans
+        .into_iter()
+        .chain(heading_words)
+        .chain(phrases)
+        .filter(|term| {
+            let key = normalize_term_key(term);
+            !key.is_empty() && seen.insert(key)
+        })
+        .collect()
+}
+
+fn flush_phrase_run(run: &mut Vec<&str>, phrases: &mut Vec<String>) {
+    if run.len() >= 2 {
+        phrases.push(run.join(" "));
+    }
+    run.clear();
+}
+
 fn validate_markdown_path(md_path: &Path) -> Result<()> {
     let root = md_path
         .components()
@@ -4,6 +4,14 @@ use clap::{Args, Parser, Subcommand};
 #[command(name = "foundry")]
 #[command(about = "Spec graph CLI for AI-driven development support")]
 pub(crate) struct Cli {
+    #[arg(long, global = true, value_name = "DIR")]
+    pub(crate) root: Option<String>,
+    #[arg(long, global = true)]
+    pub(crate) no_ignore: bool,
+    #[arg(long, short = 'v', global = true, conflicts_with = "quiet")]
+    pub(crate) verbose: bool,
+    #[arg(long, short = 'q', global = true)]
+    pub(crate) quiet: bool,
     #[command(subcommand)]
     pub(crate) command: Command,
 }
@@ -31,6 +39,20 @@ pub(crate) enum SpecSubcommand {
     Agent(AgentCommand),
     Search(SearchCommand),
     Ask(AskArgs),
+    Validate(ValidateCommand),
+    Diff(DiffArgs),
+    Archive(ArchiveArgs),
+    Export(ExportCommand),
+    Import(ImportCommand),
+    Node(NodeCommand),
+    Migrate,
+    Term(TermCommand),
+    Doctor(DoctorArgs),
+    History(HistoryArgs),
+    Coverage(CoverageArgs),
+    Completions(CompletionsArgs),
+    #[cfg(feature = "serve")]
+    Serve(ServeArgs),
 }
 
 #[derive(Args, Debug)]
@@ -43,6 +65,7 @@ pub(crate) struct DeriveCommand {
 pub(crate) enum DeriveSubcommand {
     Design(DeriveDesignArgs),
     Tasks(DeriveTasksArgs),
+    Test(DeriveTestArgs),
 }
 
 #[derive(Args, Debug)]
@@ -99,6 +122,28 @@ pub(crate) struct DeriveTasksArgs {
     pub(crate) format: DeriveFormat,
 }
 
+#[derive(Args, Debug)]
+pub(crate) struct DeriveTestArgs {
+    #[arg(long)]
+    pub(crate) from: String,
+    #[arg(long)]
+    pub(crate) path: Option<String>,
+    #[arg(long)]
+    pub(crate) title: Option<String>,
+    #[arg(long, default_value = "draft")]
+    pub(crate) status: String,
+    #[arg(long)]
+    pub(crate) body: Option<String>,
+    #[arg(long)]
+    pub(crate) body_file: Option<String>,
+    #[arg(long, default_value = "derived test spec from source spec")]
+    pub(crate) rationale: String,
+    #[arg(long = "term")]
+    pub(crate) terms: Vec<String>,
+    #[arg(long, value_enum, default_value_t = DeriveFormat::Table)]
+    pub(crate) format: DeriveFormat,
+}
+
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum DeriveFormat {
     Table,
@@ -116,6 +161,8 @@ pub(crate) struct WriteArgs {
     #[arg(long)]
     pub(crate) status: Option<String>,
     #[arg(long)]
+    pub(crate) priority: Option<String>,
+    #[arg(long)]
     pub(crate) title: Option<String>,
     #[arg(long)]
     pub(crate) body: Option<String>,
@@ -123,14 +170,34 @@ pub(crate) struct WriteArgs {
     pub(crate) body_file: Option<String>,
     #[arg(long = "term")]
     pub(crate) terms: Vec<String>,
+    #[arg(long)]
+    pub(crate) auto_terms: bool,
+    #[arg(long)]
+    pub(crate) append: bool,
+    #[arg(long = "front-matter", value_name = "KEY=VALUE")]
+    pub(crate) front_matter: Vec<String>,
+    #[arg(long)]
+    pub(crate) backup: bool,
+    #[arg(long, value_enum, default_value_t = WriteFormat::Table)]
+    pub(crate) format: WriteFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WriteFormat {
+    Table,
+    Json,
 }
 
 #[derive(Args, Debug)]
 pub(crate) struct InitArgs {
     #[arg(long)]
     pub(crate) sync: bool,
+    #[arg(long)]
+    pub(crate) watch: bool,
     #[arg(long, value_enum)]
     pub(crate) agent: Vec<AgentTarget>,
+    #[arg(long = "agent-dir", value_name = "NAME:PATH")]
+    pub(crate) agent_dirs: Vec<String>,
     #[arg(long)]
     pub(crate) agent_sync: bool,
     #[arg(long, value_enum, default_value_t = AgentOutput::Docs)]
@@ -139,24 +206,43 @@ pub(crate) struct InitArgs {
     pub(crate) codex_home: Option<String>,
     #[arg(long)]
     pub(crate) claude_dir: Option<String>,
+    #[arg(long)]
+    pub(crate) gemini_home: Option<String>,
     #[arg(long, value_enum, default_value_t = TemplateSource::Github)]
     pub(crate) template_source: TemplateSource,
     #[arg(long, default_value = "https://github.com/nurliv/foundry.git")]
     pub(crate) template_repo: String,
     #[arg(long, default_value = "main")]
     pub(crate) template_ref: String,
+    #[arg(long, value_enum, default_value_t = InitFormat::Table, conflicts_with = "watch")]
+    pub(crate) format: InitFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InitFormat {
+    Table,
+    Json,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub(crate) enum AgentTarget {
     Codex,
     Claude,
+    Gemini,
 }
 
 #[derive(Args, Debug)]
 pub(crate) struct LintArgs {
     #[arg(long, value_enum, default_value_t = LintFormat::Table)]
     pub(crate) format: LintFormat,
+    #[arg(long, value_delimiter = ',')]
+    pub(crate) only: Vec<String>,
+    #[arg(long, value_delimiter = ',')]
+    pub(crate) ignore: Vec<String>,
+    #[arg(long)]
+    pub(crate) fix: bool,
+    #[arg(long)]
+    pub(crate) max_depth: Option<usize>,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -172,12 +258,36 @@ pub(crate) struct ImpactArgs {
     pub(crate) depth: usize,
     #[arg(long, value_enum, default_value_t = ImpactFormat::Table)]
     pub(crate) format: ImpactFormat,
+    #[arg(long, alias = "confidence-threshold", default_value_t = 0.0)]
+    pub(crate) min_confidence: f64,
+    #[arg(long)]
+    pub(crate) confirmed_only: bool,
+    #[arg(long = "reverse-via", value_delimiter = ',')]
+    pub(crate) reverse_via: Vec<String>,
+    #[arg(long)]
+    pub(crate) conflicts_first: bool,
+    #[arg(long, value_enum, default_value_t = ImpactDirection::Both)]
+    pub(crate) direction: ImpactDirection,
+    /// Print `id -> body_md_path` for each node in table/markdown output.
+    #[arg(long)]
+    pub(crate) link_paths: bool,
+    /// Exit 1 when the estimated review effort exceeds this value.
+    #[arg(long, value_name = "N")]
+    pub(crate) budget: Option<f64>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImpactDirection {
+    Forward,
+    Reverse,
+    Both,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum ImpactFormat {
     Table,
     Json,
+    Markdown,
 }
 
 #[derive(Args, Debug)]
@@ -190,18 +300,49 @@ pub(crate) struct PlanCommand {
 pub(crate) enum PlanSubcommand {
     Ready(PlanReadyArgs),
     Batches(PlanBatchesArgs),
+    CriticalPath(PlanCriticalPathArgs),
+    Assign(PlanAssignArgs),
 }
 
 #[derive(Args, Debug)]
 pub(crate) struct PlanReadyArgs {
+    #[arg(long, value_enum, default_value_t = PlanReadyFormat::Table)]
+    pub(crate) format: PlanReadyFormat,
+    #[arg(long)]
+    pub(crate) owner: Option<String>,
+    /// Also treat `proposed` `depends_on` edges as blocking.
+    #[arg(long)]
+    pub(crate) include_proposed: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct PlanAssignArgs {
+    #[arg(long)]
+    pub(crate) id: String,
+    #[arg(long)]
+    pub(crate) owner: String,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct PlanBatchesArgs {
     #[arg(long, value_enum, default_value_t = PlanFormat::Table)]
     pub(crate) format: PlanFormat,
+    #[arg(long, value_name = "N")]
+    pub(crate) max_parallel: Option<usize>,
+    #[arg(long)]
+    pub(crate) owner: Option<String>,
+    /// Also treat `proposed` `depends_on` edges as blocking.
+    #[arg(long)]
+    pub(crate) include_proposed: bool,
 }
 
 #[derive(Args, Debug)]
-pub(crate) struct PlanBatchesArgs {
+pub(crate) struct PlanCriticalPathArgs {
     #[arg(long, value_enum, default_value_t = PlanFormat::Table)]
     pub(crate) format: PlanFormat,
+    /// Also treat `proposed` `depends_on` edges as blocking.
+    #[arg(long)]
+    pub(crate) include_proposed: bool,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -210,6 +351,13 @@ pub(crate) enum PlanFormat {
     Json,
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PlanReadyFormat {
+    Table,
+    Json,
+    Csv,
+}
+
 #[derive(Args, Debug)]
 pub(crate) struct AgentCommand {
     #[command(subcommand)]
@@ -219,12 +367,27 @@ pub(crate) struct AgentCommand {
 #[derive(Subcommand, Debug)]
 pub(crate) enum AgentSubcommand {
     Doctor(AgentDoctorArgs),
+    List(AgentListArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct AgentListArgs {
+    #[arg(long, value_enum, default_value_t = AgentFormat::Table)]
+    pub(crate) format: AgentFormat,
+    #[arg(long)]
+    pub(crate) codex_home: Option<String>,
+    #[arg(long)]
+    pub(crate) claude_dir: Option<String>,
+    #[arg(long)]
+    pub(crate) gemini_home: Option<String>,
 }
 
 #[derive(Args, Debug)]
 pub(crate) struct AgentDoctorArgs {
     #[arg(long, value_enum)]
     pub(crate) agent: Vec<AgentTarget>,
+    #[arg(long = "agent-dir", value_name = "NAME:PATH")]
+    pub(crate) agent_dirs: Vec<String>,
     #[arg(long, value_enum, default_value_t = AgentFormat::Table)]
     pub(crate) format: AgentFormat,
     #[arg(long, value_enum, default_value_t = AgentOutput::Docs)]
@@ -233,6 +396,36 @@ pub(crate) struct AgentDoctorArgs {
     pub(crate) codex_home: Option<String>,
     #[arg(long)]
     pub(crate) claude_dir: Option<String>,
+    #[arg(long)]
+    pub(crate) gemini_home: Option<String>,
+    #[arg(long, value_enum, default_value_t = TemplateSource::Github)]
+    pub(crate) template_source: TemplateSource,
+    #[arg(long, default_value = "https://github.com/nurliv/foundry.git")]
+    pub(crate) template_repo: String,
+    #[arg(long, default_value = "main")]
+    pub(crate) template_ref: String,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct DoctorArgs {
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub(crate) skip: Vec<DoctorCheckKind>,
+    #[arg(long)]
+    pub(crate) db: Option<String>,
+    #[arg(long, value_enum, default_value_t = DoctorFormat::Table)]
+    pub(crate) format: DoctorFormat,
+    #[arg(long, value_enum)]
+    pub(crate) agent: Vec<AgentTarget>,
+    #[arg(long = "agent-dir", value_name = "NAME:PATH")]
+    pub(crate) agent_dirs: Vec<String>,
+    #[arg(long, value_enum, default_value_t = AgentOutput::Docs)]
+    pub(crate) agent_output: AgentOutput,
+    #[arg(long)]
+    pub(crate) codex_home: Option<String>,
+    #[arg(long)]
+    pub(crate) claude_dir: Option<String>,
+    #[arg(long)]
+    pub(crate) gemini_home: Option<String>,
     #[arg(long, value_enum, default_value_t = TemplateSource::Github)]
     pub(crate) template_source: TemplateSource,
     #[arg(long, default_value = "https://github.com/nurliv/foundry.git")]
@@ -241,6 +434,19 @@ pub(crate) struct AgentDoctorArgs {
     pub(crate) template_ref: String,
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DoctorCheckKind {
+    Lint,
+    Search,
+    Agent,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DoctorFormat {
+    Table,
+    Json,
+}
+
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum AgentFormat {
     Table,
@@ -262,6 +468,8 @@ pub(crate) enum TemplateSource {
 
 #[derive(Args, Debug)]
 pub(crate) struct SearchCommand {
+    #[arg(long, global = true, visible_alias = "output-db")]
+    pub(crate) db: Option<String>,
     #[command(subcommand)]
     pub(crate) command: SearchSubcommand,
 }
@@ -270,13 +478,21 @@ pub(crate) struct SearchCommand {
 pub(crate) enum SearchSubcommand {
     Index(SearchIndexArgs),
     Query(SearchQueryArgs),
-    Doctor,
+    Doctor(SearchDoctorArgs),
+    ReindexVectors,
 }
 
 #[derive(Args, Debug)]
 pub(crate) struct SearchIndexArgs {
     #[arg(long)]
     pub(crate) rebuild: bool,
+    #[arg(long, value_name = "GIT_REF", conflicts_with = "rebuild")]
+    pub(crate) since: Option<String>,
+    #[arg(long)]
+    pub(crate) vacuum: bool,
+    /// Also index each node's title and terms as a synthetic FTS chunk.
+    #[arg(long)]
+    pub(crate) include_title_terms: bool,
 }
 
 #[derive(Args, Debug)]
@@ -288,37 +504,105 @@ pub(crate) struct SearchQueryArgs {
     pub(crate) format: SearchFormat,
     #[arg(long, value_enum, default_value_t = SearchMode::Lexical)]
     pub(crate) mode: SearchMode,
+    #[arg(long = "node-type")]
+    pub(crate) node_types: Vec<String>,
+    #[arg(long = "status")]
+    pub(crate) statuses: Vec<String>,
+    #[arg(long)]
+    pub(crate) explain: bool,
+    #[arg(
+        long = "context",
+        visible_alias = "context-lines",
+        value_name = "N",
+        default_value_t = 0
+    )]
+    pub(crate) context: usize,
+    #[arg(long, value_enum)]
+    pub(crate) normalize: Option<ScoreNormalization>,
+    #[arg(long, visible_alias = "since", value_name = "TIME")]
+    pub(crate) modified_after: Option<String>,    #[arg(long, value_enum, default_value_t = SearchField::Body)]
+    pub(crate) field: SearchField,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScoreNormalization {
+    Minmax,
+    Softmax,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum SearchFormat {
     Table,
     Json,
+    Ndjson,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct SearchDoctorArgs {
+    #[arg(long, value_enum, default_value_t = SearchDoctorFormat::Table)]
+    pub(crate) format: SearchDoctorFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SearchDoctorFormat {
+    Table,
+    Json,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum SearchField {
+    #[default]
+    Body,
+    Title,
+    Terms,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum SearchMode {
     Lexical,
     Hybrid,
+    Rerank,
 }
 
 #[derive(Args, Debug)]
 pub(crate) struct AskArgs {
-    pub(crate) question: String,
+    #[arg(
+        required_unless_present = "questions_file",
+        conflicts_with = "questions_file"
+    )]
+    pub(crate) question: Option<String>,
+    #[arg(long)]
+    pub(crate) questions_file: Option<String>,
     #[arg(long, default_value_t = 5)]
     pub(crate) top_k: usize,
+    #[arg(long, default_value_t = 0)]
+    pub(crate) offset: usize,
     #[arg(long, value_enum, default_value_t = SearchMode::Hybrid)]
     pub(crate) mode: SearchMode,
     #[arg(long, value_enum, default_value_t = AskFormat::Table)]
     pub(crate) format: AskFormat,
     #[arg(long)]
     pub(crate) explain: bool,
+    #[arg(long)]
+    pub(crate) max_context_chars: Option<usize>,
+    #[arg(long)]
+    pub(crate) cite_bodies: bool,
+    #[arg(long, alias = "confidence-threshold", default_value_t = 0.0)]
+    pub(crate) min_confidence: f64,
+    #[arg(long)]
+    pub(crate) confirmed_only: bool,
+    /// Answer from retrieval hits alone: no neighbor expansion or conflict checks.
+    #[arg(long)]
+    pub(crate) no_graph: bool,
+    #[arg(long)]
+    pub(crate) db: Option<String>,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum AskFormat {
     Table,
     Json,
+    Markdown,
 }
 
 #[derive(Args, Debug)]
@@ -333,20 +617,41 @@ pub(crate) enum LinkSubcommand {
     Remove(LinkRemoveArgs),
     List(LinkListArgs),
     Propose(LinkProposeArgs),
+    Prune(LinkPruneArgs),
+    GraphMetrics(LinkGraphMetricsArgs),
+    Verify(LinkVerifyArgs),
 }
 
 #[derive(Args, Debug)]
-pub(crate) struct LinkAddArgs {
+pub(crate) struct LinkVerifyArgs {
     #[arg(long)]
-    pub(crate) from: String,
-    #[arg(long)]
-    pub(crate) to: String,
-    #[arg(long)]
-    pub(crate) r#type: String,
-    #[arg(long)]
-    pub(crate) rationale: String,
+    pub(crate) fix: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LinkGraphMetricsArgs {
+    #[arg(long, default_value_t = 10)]
+    pub(crate) top: usize,
+    #[arg(long, value_enum, default_value_t = LinkFormat::Table)]
+    pub(crate) format: LinkFormat,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LinkAddArgs {
+    #[arg(long, required_unless_present = "bulk")]
+    pub(crate) from: Option<String>,
+    #[arg(long, required_unless_present = "bulk")]
+    pub(crate) to: Option<String>,
+    #[arg(long, required_unless_present = "bulk")]
+    pub(crate) r#type: Option<String>,
+    #[arg(long, required_unless_present = "bulk")]
+    pub(crate) rationale: Option<String>,
     #[arg(long, default_value_t = 1.0)]
     pub(crate) confidence: f64,
+    #[arg(long, conflicts_with_all = ["from", "to", "type", "rationale"])]
+    pub(crate) bulk: Option<String>,
+    #[arg(long, requires = "bulk")]
+    pub(crate) continue_on_error: bool,
 }
 
 #[derive(Args, Debug)]
@@ -361,8 +666,28 @@ pub(crate) struct LinkRemoveArgs {
 
 #[derive(Args, Debug)]
 pub(crate) struct LinkListArgs {
-    #[arg(long)]
-    pub(crate) node: String,
+    #[arg(long, required_unless_present = "all")]
+    pub(crate) node: Option<String>,
+    #[arg(long, conflicts_with = "node")]
+    pub(crate) all: bool,
+    #[arg(long, value_enum, default_value_t = LinkListSort::Default)]
+    pub(crate) sort: LinkListSort,
+    #[arg(long, value_enum, default_value_t = LinkFormat::Table)]
+    pub(crate) format: LinkFormat,
+    #[arg(long, value_name = "SOURCE")]
+    pub(crate) source: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LinkListSort {
+    Default,
+    Age,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LinkFormat {
+    Table,
+    Json,
 }
 
 #[derive(Args, Debug)]
@@ -381,4 +706,208 @@ pub(crate) struct LinkProposeArgs {
     pub(crate) confidence: f64,
     #[arg(long, default_value_t = 3)]
     pub(crate) limit: usize,
+    #[arg(long, value_enum, default_value_t = ProposeUsing::Term)]
+    pub(crate) using: ProposeUsing,
+    #[arg(long, requires = "node", conflicts_with = "rationale")]
+    pub(crate) rationale_template: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProposeUsing {
+    Term,
+    Semantic,
+    Hybrid,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LinkPruneArgs {
+    #[arg(long)]
+    pub(crate) dry_run: bool,
+    #[arg(long)]
+    pub(crate) also_proposed: bool,
+    #[arg(long, value_name = "SOURCE")]
+    pub(crate) source: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ValidateCommand {
+    #[command(subcommand)]
+    pub(crate) command: ValidateSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ValidateSubcommand {
+    Schema(ValidateSchemaArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ValidateSchemaArgs {
+    #[arg(long = "path")]
+    pub(crate) paths: Vec<String>,
+    #[arg(long, value_enum, default_value_t = ValidateFormat::Table)]
+    pub(crate) format: ValidateFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ValidateFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ArchiveArgs {
+    pub(crate) node_id: String,
+    #[arg(long)]
+    pub(crate) force: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ExportCommand {
+    #[command(subcommand)]
+    pub(crate) command: ExportSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ExportSubcommand {
+    Bundle(ExportBundleArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ExportBundleArgs {
+    #[arg(long)]
+    pub(crate) out: String,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ImportCommand {
+    #[command(subcommand)]
+    pub(crate) command: ImportSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ImportSubcommand {
+    Bundle(ImportBundleArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ImportBundleArgs {
+    #[arg(long = "in")]
+    pub(crate) input: String,
+    #[arg(long)]
+    pub(crate) force: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct NodeCommand {
+    #[command(subcommand)]
+    pub(crate) command: NodeSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum NodeSubcommand {
+    Show(NodeShowArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct TermCommand {
+    #[command(subcommand)]
+    pub(crate) command: TermSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum TermSubcommand {
+    Rename(TermRenameArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct TermRenameArgs {
+    #[arg(long, requires = "to", required_unless_present = "canonicalize")]
+    pub(crate) from: Option<String>,
+    #[arg(long)]
+    pub(crate) to: Option<String>,
+    #[arg(long, conflicts_with_all = ["from", "to"])]
+    pub(crate) canonicalize: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct HistoryArgs {
+    pub(crate) node_id: String,
+    #[arg(long)]
+    pub(crate) diff: bool,
+    #[arg(long, value_enum, default_value_t = HistoryFormat::Table)]
+    pub(crate) format: HistoryFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HistoryFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct CoverageArgs {
+    #[arg(long = "type", value_name = "NODE_TYPE")]
+    pub(crate) node_types: Vec<String>,
+    #[arg(long, value_enum, default_value_t = CoverageFormat::Table)]
+    pub(crate) format: CoverageFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CoverageFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct NodeShowArgs {
+    pub(crate) node_id: String,
+    #[arg(long, default_value_t = 20)]
+    pub(crate) body_lines: usize,
+    #[arg(long, value_enum, default_value_t = NodeFormat::Table)]
+    pub(crate) format: NodeFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct CompletionsArgs {
+    /// Target shell; detected from `$SHELL` when omitted.
+    #[arg(value_enum)]
+    pub(crate) shell: Option<CompletionShell>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+#[cfg(feature = "serve")]
+#[derive(Args, Debug)]
+pub(crate) struct ServeArgs {
+    #[arg(long, default_value = "127.0.0.1")]
+    pub(crate) bind: String,
+    #[arg(long, default_value_t = 8787)]
+    pub(crate) port: u16,
+    #[arg(long)]
+    pub(crate) db: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct DiffArgs {
+    pub(crate) node_id: Option<String>,
+    #[arg(long, value_enum, default_value_t = DiffFormat::Table)]
+    pub(crate) format: DiffFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffFormat {
+    Table,
+    Json,
 }
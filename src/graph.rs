@@ -0,0 +1,575 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+
+use crate::model::{SpecEdge, SpecNodeMeta};
+
+/// In-memory spec graph keyed by node id.
+#[derive(Debug, Clone, Default)]
+pub struct SpecGraph {
+    nodes: HashMap<String, SpecNodeMeta>,
+}
+
+/// Which edges graph traversals may follow. The default admits every edge.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EdgeFilter {
+    /// Edges with `confidence` below this are ignored.
+    pub min_confidence: f64,
+    /// Ignore edges whose status is `proposed`.
+    pub confirmed_only: bool,
+}
+
+impl EdgeFilter {
+    pub fn allows(&self, edge: &SpecEdge) -> bool {
+        edge.confidence >= self.min_confidence
+            && !(self.confirmed_only && edge.status == "proposed")
+    }
+}
+
+/// Which side of a node [`SpecGraph::impact_directed`] reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraversalDirection {
+    /// What the node depends on: `direct_dependencies` and outgoing edges.
+    Forward,
+    /// What depends on the node: `reverse_dependents` and incoming edges.
+    Reverse,
+    /// Both sides, as [`SpecGraph::impact`] reports.
+    #[default]
+    Both,
+}
+
+impl TraversalDirection {
+    fn forward(self) -> bool {
+        self != Self::Reverse
+    }
+
+    fn reverse(self) -> bool {
+        self != Self::Forward
+    }
+}
+
+/// Edge types [`reverse_dependents`] follows backward by default.
+pub const DEFAULT_REVERSE_EDGE_TYPES: &[&str] = &["depends_on"];
+
+/// Edge types [`SpecGraph::page_rank`] callers use by default: rank flows
+/// from a node to what it depends on or refines.
+pub const PAGE_RANK_EDGE_TYPES: &[&str] = &["depends_on", "refines"];
+
+const PAGE_RANK_DAMPING: f64 = 0.85;
+const PAGE_RANK_EPSILON: f64 = 1e-9;
+const PAGE_RANK_MAX_ITERATIONS: usize = 100;
+
+/// Edge out of the impacted node that it depends on or impacts.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectDependency {
+    pub to: String,
+    pub edge_type: String,
+    pub status: String,
+    pub confidence: f64,
+    pub rationale: String,
+}
+
+/// Result of [`SpecGraph::impact`]; mirrors `spec impact --format json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactReport {
+    pub node_id: String,
+    pub depth: usize,
+    pub direct_dependencies: Vec<DirectDependency>,
+    pub reverse_dependents: Vec<String>,
+    pub test_coverage_chain: Vec<String>,
+    pub conflict_risks: Vec<String>,
+    pub recommended_review_order: Vec<String>,
+}
+
+impl ImpactReport {
+    /// Moves the `conflict_risks` nodes right after the seed in
+    /// `recommended_review_order`, adding any the traversal did not reach, so
+    /// conflicting specs are reviewed before dependents.
+    pub fn prioritize_conflicts(&mut self) {
+        let conflicts = self
+            .conflict_risks
+            .iter()
+            .filter(|id| **id != self.node_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        let rest = self
+            .recommended_review_order
+            .iter()
+            .filter(|id| **id != self.node_id && !conflicts.contains(id))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.recommended_review_order = std::iter::once(self.node_id.clone())
+            .chain(conflicts)
+            .chain(rest)
+            .collect();
+    }
+}
+
+impl SpecGraph {
+    /// Loads every `*.meta.json` under `spec_root` and its sibling `tasks/`.
+    /// Unreadable or invalid meta files are skipped; use [`load_all_meta`]
+    /// to see why.
+    pub fn load(spec_root: &Path) -> Result<Self> {
+        Self::load_with(spec_root, WalkOptions::default())
+    }
+
+    /// [`SpecGraph::load`] with explicit [`WalkOptions`].
+    pub fn load_with(spec_root: &Path, options: WalkOptions) -> Result<Self> {
+        let mut errors = Vec::new();
+        let metas = load_all_meta(spec_root, options, &mut errors);
+        Ok(Self::from_nodes(metas.into_iter().map(|(_, meta)| meta)))
+    }
+
+    pub fn from_nodes(nodes: impl IntoIterator<Item = SpecNodeMeta>) -> Self {
+        Self {
+            nodes: nodes
+                .into_iter()
+                .map(|meta| (meta.id.clone(), meta))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SpecNodeMeta> {
+        self.nodes.get(id)
+    }
+
+    pub fn nodes(&self) -> &HashMap<String, SpecNodeMeta> {
+        &self.nodes
+    }
+
+    pub fn into_nodes(self) -> HashMap<String, SpecNodeMeta> {
+        self.nodes
+    }
+
+    /// Impact analysis for `id` with traversal bounded by `depth`, or `None`
+    /// when the node is unknown.
+    pub fn impact(&self, id: &str, depth: usize) -> Option<ImpactReport> {
+        self.impact_filtered(
+            id,
+            depth,
+            &EdgeFilter::default(),
+            DEFAULT_REVERSE_EDGE_TYPES,
+        )
+    }
+
+    /// Like [`SpecGraph::impact`], but only edges admitted by `filter` are
+    /// reported or traversed, and reverse dependents are found through
+    /// `reverse_types` edges.
+    pub fn impact_filtered(
+        &self,
+        id: &str,
+        depth: usize,
+        filter: &EdgeFilter,
+        reverse_types: &[&str],
+    ) -> Option<ImpactReport> {
+        self.impact_directed(id, depth, filter, reverse_types, TraversalDirection::Both)
+    }
+
+    /// Like [`SpecGraph::impact_filtered`], limited to one side of the node:
+    /// the excluded side's list is left empty and the review order only
+    /// walks edges in `direction`.
+    pub fn impact_directed(
+        &self,
+        id: &str,
+        depth: usize,
+        filter: &EdgeFilter,
+        reverse_types: &[&str],
+        direction: TraversalDirection,
+    ) -> Option<ImpactReport> {
+        let node = self.nodes.get(id)?;
+        let mut direct_dependencies = node
+            .edges
+            .iter()
+            .filter(|_| direction.forward())
+            .filter(|e| filter.allows(e))
+            .filter(|e| e.edge_type == "depends_on" || e.edge_type == "impacts")
+            .map(|e| DirectDependency {
+                to: e.to.clone(),
+                edge_type: e.edge_type.clone(),
+                status: e.status.clone(),
+                confidence: e.confidence,
+                rationale: e.rationale.clone(),
+            })
+            .collect::<Vec<_>>();
+        direct_dependencies.sort_by(|a, b| a.to.cmp(&b.to).then(a.edge_type.cmp(&b.edge_type)));
+
+        Some(ImpactReport {
+            node_id: id.to_string(),
+            depth,
+            direct_dependencies,
+            reverse_dependents: if direction.reverse() {
+                reverse_dependents(id, depth, &self.nodes, filter, reverse_types)
+            } else {
+                Vec::new()
+            },
+            test_coverage_chain: test_coverage_chain(id, depth, &self.nodes, filter),
+            conflict_risks: conflict_risks(id, &self.nodes, filter),
+            recommended_review_order: directed_review_order(
+                id,
+                depth,
+                &self.nodes,
+                filter,
+                direction,
+            ),
+        })
+    }
+
+    /// PageRank over `edge_types` edges between known nodes, iterated until
+    /// the total change drops below an epsilon (bounded iteration count).
+    /// Nodes without such outgoing edges spread their rank evenly. Scores
+    /// sum to `1.0`.
+    pub fn page_rank(&self, edge_types: &[&str]) -> HashMap<String, f64> {
+        let n = self.nodes.len();
+        if n == 0 {
+            return HashMap::new();
+        }
+        let mut ids = self.nodes.keys().map(String::as_str).collect::<Vec<_>>();
+        ids.sort_unstable();
+        let index = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect::<HashMap<_, _>>();
+        let out_links = ids
+            .iter()
+            .map(|id| {
+                self.nodes[*id]
+                    .edges
+                    .iter()
+                    .filter(|e| edge_types.contains(&e.edge_type.as_str()))
+                    .filter_map(|e| index.get(e.to.as_str()).copied())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let base = (1.0 - PAGE_RANK_DAMPING) / n as f64;
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..PAGE_RANK_MAX_ITERATIONS {
+            let dangling = out_links
+                .iter()
+                .zip(&rank)
+                .filter(|(links, _)| links.is_empty())
+                .map(|(_, r)| r)
+                .sum::<f64>();
+            let mut next = vec![base + PAGE_RANK_DAMPING * dangling / n as f64; n];
+            for (from, links) in out_links.iter().enumerate() {
+                for &to in links {
+                    next[to] += PAGE_RANK_DAMPING * rank[from] / links.len() as f64;
+                }
+            }
+            let delta = next
+                .iter()
+                .zip(&rank)
+                .map(|(a, b)| (a - b).abs())
+                .sum::<f64>();
+            rank = next;
+            if delta < PAGE_RANK_EPSILON {
+                break;
+            }
+        }
+        ids.into_iter().map(str::to_string).zip(rank).collect()
+    }
+}
+
+/// A meta file [`load_all_meta`] could not read or parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaLoadError {
+    pub path: PathBuf,
+    pub message: String,
+    /// One-based `(line, column)` reported by `serde_json` for a parse
+    /// failure; `None` when the file could not be read.
+    pub position: Option<(usize, usize)>,
+}
+
+impl std::fmt::Display for MetaLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Reads all meta files under the doc roots of `spec_root`, recording read
+/// and parse failures in `errors` instead of aborting.
+pub fn load_all_meta(
+    spec_root: &Path,
+    options: WalkOptions,
+    errors: &mut Vec<MetaLoadError>,
+) -> Vec<(PathBuf, SpecNodeMeta)> {
+    let mut metas = Vec::new();
+    for path in walk_doc_files(spec_root, options) {
+        if !is_meta_json(&path) {
+            continue;
+        }
+        let raw = match fs::read_to_string(&path) {
+            Ok(v) => v,
+            Err(err) => {
+                errors.push(MetaLoadError {
+                    message: format!("cannot read {}: {err}", path.display()),
+                    path,
+                    position: None,
+                });
+                continue;
+            }
+        };
+        match serde_json::from_str::<SpecNodeMeta>(&raw) {
+            Ok(meta) => metas.push((path, meta)),
+            Err(err) => errors.push(MetaLoadError {
+                message: format!("invalid json {}: {err}", path.display()),
+                path,
+                position: Some((err.line(), err.column())),
+            }),
+        }
+    }
+    metas
+}
+
+/// How [`walk_doc_files`] decides which files to skip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Honor `.gitignore` files, `.git/info/exclude`, and `.git` directories;
+    /// on by default. `.foundryignore` always applies.
+    pub respect_gitignore: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            respect_gitignore: true,
+        }
+    }
+}
+
+/// Every file under the doc roots of `spec_root`, skipping paths matched by
+/// the gitignore-style `.foundryignore` next to `spec_root` and, unless
+/// [`WalkOptions::respect_gitignore`] is off, by `.gitignore` files and `.git`
+/// directories. Symlinks are not followed, so link loops cannot recurse.
+pub fn walk_doc_files(spec_root: &Path, options: WalkOptions) -> Vec<PathBuf> {
+    let ignore = foundry_ignore(spec_root);
+    let gitignore = options.respect_gitignore;
+    let mut files = Vec::new();
+    for root in doc_roots(spec_root) {
+        if !root.exists() {
+            continue;
+        }
+        let ignore = ignore.clone();
+        let walker = WalkBuilder::new(&root)
+            .standard_filters(false)
+            .git_ignore(gitignore)
+            .git_global(gitignore)
+            .git_exclude(gitignore)
+            .parents(gitignore)
+            .require_git(false)
+            .follow_links(false)
+            .filter_entry(move |e| {
+                let is_dir = e.file_type().is_some_and(|t| t.is_dir());
+                let git_dir = gitignore && is_dir && e.file_name() == ".git";
+                let ignored = ignore
+                    .matched_path_or_any_parents(e.path(), is_dir)
+                    .is_ignore();
+                !git_dir && !ignored
+            })
+            .build();
+        for entry in walker.filter_map(std::result::Result::ok) {
+            if entry.file_type().is_some_and(|t| t.is_file()) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files
+}
+
+/// Patterns from `.foundryignore`; invalid lines are skipped, and a missing
+/// file ignores nothing.
+fn foundry_ignore(spec_root: &Path) -> Gitignore {
+    let project_root = spec_root.parent().unwrap_or(Path::new(""));
+    let path = project_root.join(".foundryignore");
+    if !path.is_file() {
+        return Gitignore::empty();
+    }
+    let mut builder = GitignoreBuilder::new(project_root);
+    let _ = builder.add(&path);
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Directories holding spec nodes: `spec_root` and its sibling `tasks/`.
+pub fn doc_roots(spec_root: &Path) -> Vec<PathBuf> {
+    let spec = PathBuf::from(spec_root.to_string_lossy().replace('\\', "/"));
+    let parent = spec.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+    let tasks = PathBuf::from(parent.join("tasks").to_string_lossy().replace('\\', "/"));
+    vec![spec, tasks]
+}
+
+pub fn is_meta_json(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .is_some_and(|name| name.ends_with(".meta.json"))
+}
+
+/// Nodes that transitively point at the seed through an edge whose type is in
+/// `edge_types` (see [`DEFAULT_REVERSE_EDGE_TYPES`]), up to `max_depth` hops.
+pub fn reverse_dependents(
+    seed: &str,
+    max_depth: usize,
+    by_id: &HashMap<String, SpecNodeMeta>,
+    filter: &EdgeFilter,
+    edge_types: &[&str],
+) -> Vec<String> {
+    let mut queue = VecDeque::new();
+    let mut visited = HashSet::new();
+    let mut out = BTreeSet::new();
+
+    queue.push_back((seed.to_string(), 0usize));
+    visited.insert(seed.to_string());
+
+    while let Some((current, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        for (id, m) in by_id {
+            let connected = m.edges.iter().any(|e| {
+                e.to == current && edge_types.contains(&e.edge_type.as_str()) && filter.allows(e)
+            });
+            if connected && visited.insert(id.clone()) {
+                out.insert(id.clone());
+                queue.push_back((id.clone(), depth + 1));
+            }
+        }
+    }
+
+    out.into_iter().collect()
+}
+
+/// Nodes reachable through `tests` edges in either direction.
+pub fn test_coverage_chain(
+    seed: &str,
+    max_depth: usize,
+    by_id: &HashMap<String, SpecNodeMeta>,
+    filter: &EdgeFilter,
+) -> Vec<String> {
+    let mut queue = VecDeque::new();
+    let mut visited = HashSet::new();
+    let mut out = BTreeSet::new();
+
+    queue.push_back((seed.to_string(), 0usize));
+    visited.insert(seed.to_string());
+
+    while let Some((current, depth)) = queue.pop_front() {
+        if depth >= max_depth {
+            continue;
+        }
+        if let Some(meta) = by_id.get(&current) {
+            for edge in &meta.edges {
+                if edge.edge_type == "tests"
+                    && filter.allows(edge)
+                    && visited.insert(edge.to.clone())
+                {
+                    out.insert(edge.to.clone());
+                    queue.push_back((edge.to.clone(), depth + 1));
+                }
+            }
+        }
+        for (id, m) in by_id {
+            let connected = m
+                .edges
+                .iter()
+                .any(|e| e.to == current && e.edge_type == "tests" && filter.allows(e));
+            if connected && visited.insert(id.clone()) {
+                out.insert(id.clone());
+                queue.push_back((id.clone(), depth + 1));
+            }
+        }
+    }
+
+    out.into_iter().collect()
+}
+
+/// Nodes joined to the seed by a `conflicts_with` edge in either direction.
+pub fn conflict_risks(
+    seed: &str,
+    by_id: &HashMap<String, SpecNodeMeta>,
+    filter: &EdgeFilter,
+) -> Vec<String> {
+    let mut conflicts = BTreeSet::<String>::new();
+    if let Some(node) = by_id.get(seed) {
+        for e in &node.edges {
+            if e.edge_type == "conflicts_with" && filter.allows(e) {
+                conflicts.insert(e.to.clone());
+            }
+        }
+    }
+    for (id, m) in by_id {
+        if m.edges
+            .iter()
+            .any(|e| e.to == seed && e.edge_type == "conflicts_with" && filter.allows(e))
+        {
+            conflicts.insert(id.clone());
+        }
+    }
+    conflicts.into_iter().collect()
+}
+
+/// Breadth-first review order over `depends_on`/`impacts`/`tests` edges in
+/// both directions, starting with the seed.
+pub fn bfs_review_order(
+    seed: &str,
+    max_depth: usize,
+    by_id: &HashMap<String, SpecNodeMeta>,
+    filter: &EdgeFilter,
+) -> Vec<String> {
+    directed_review_order(seed, max_depth, by_id, filter, TraversalDirection::Both)
+}
+
+fn directed_review_order(
+    seed: &str,
+    max_depth: usize,
+    by_id: &HashMap<String, SpecNodeMeta>,
+    filter: &EdgeFilter,
+    direction: TraversalDirection,
+) -> Vec<String> {
+    let mut queue = VecDeque::new();
+    let mut visited = HashSet::new();
+    let mut out = Vec::new();
+
+    queue.push_back((seed.to_string(), 0usize));
+    visited.insert(seed.to_string());
+
+    while let Some((current, depth)) = queue.pop_front() {
+        out.push(current.clone());
+        if depth >= max_depth {
+            continue;
+        }
+
+        if let Some(meta) = by_id.get(&current).filter(|_| direction.forward()) {
+            for edge in &meta.edges {
+                if (edge.edge_type == "depends_on"
+                    || edge.edge_type == "impacts"
+                    || edge.edge_type == "tests")
+                    && filter.allows(edge)
+                    && visited.insert(edge.to.clone())
+                {
+                    queue.push_back((edge.to.clone(), depth + 1));
+                }
+            }
+        }
+        for (id, m) in by_id.iter().filter(|_| direction.reverse()) {
+            let connected = m.edges.iter().any(|e| {
+                e.to == current
+                    && (e.edge_type == "depends_on"
+                        || e.edge_type == "impacts"
+                        || e.edge_type == "tests")
+                    && filter.allows(e)
+            });
+            if connected && visited.insert(id.clone()) {
+                queue.push_back((id.clone(), depth + 1));
+            }
+        }
+    }
+
+    out
+}
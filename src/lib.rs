@@ -0,0 +1,44 @@
+//! Library surface of foundry: spec graph types and graph analysis.
+//!
+//! The `foundry` binary is a CLI over this crate. Embedders can load the
+//! `*.meta.json` graph from a spec root and run the same impact analysis:
+//!
+//! ```
+//! use foundry::SpecGraph;
+//!
+//! let root = tempfile::tempdir()?;
+//! let spec = root.path().join("spec");
+//! std::fs::create_dir_all(&spec)?;
+//! let meta = |id: &str, edges: &str| {
+//!     format!(
+//!         r#"{{"id":"{id}","type":"feature_requirement","status":"active","title":"{id}",
+//!             "body_md_path":"spec/{id}.md","terms":[],"hash":"{hash}","edges":{edges}}}"#,
+//!         hash = "0".repeat(64)
+//!     )
+//! };
+//! std::fs::write(spec.join("a.meta.json"), meta("SPC-001", "[]"))?;
+//! std::fs::write(
+//!     spec.join("b.meta.json"),
+//!     meta(
+//!         "SPC-002",
+//!         r#"[{"to":"SPC-001","type":"depends_on","rationale":"uses a","confidence":1.0,"status":"confirmed"}]"#,
+//!     ),
+//! )?;
+//!
+//! let graph = SpecGraph::load(&spec)?;
+//! let report = graph.impact("SPC-001", 2).expect("node exists");
+//! assert_eq!(report.reverse_dependents, vec!["SPC-002"]);
+//! assert_eq!(report.recommended_review_order, vec!["SPC-001", "SPC-002"]);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+mod graph;
+mod model;
+
+pub use graph::{
+    DEFAULT_REVERSE_EDGE_TYPES, DirectDependency, EdgeFilter, ImpactReport, MetaLoadError,
+    PAGE_RANK_EDGE_TYPES, SpecGraph, TraversalDirection, WalkOptions, bfs_review_order,
+    conflict_risks, doc_roots, is_meta_json, load_all_meta, reverse_dependents,
+    test_coverage_chain, walk_doc_files,
+};
+pub use model::{META_SCHEMA_VERSION, SpecEdge, SpecNodeMeta};
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Meta schema version written by this build; `spec migrate` upgrades older
+/// files (including unversioned ones, read as `0`) to it.
+pub const META_SCHEMA_VERSION: u32 = 1;
+
+/// One `*.meta.json` node: graph metadata for a spec or task markdown file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecNodeMeta {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub status: String,
+    pub title: String,
+    pub body_md_path: String,
+    pub terms: Vec<String>,
+    pub hash: String,
+    pub edges: Vec<SpecEdge>,
+    /// Person responsible for a task node; empty when unassigned.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub owner: String,
+    /// `critical`, `high`, `medium` or `low`; empty when unset.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub priority: String,
+    /// `0` for files written before versioning; see [`META_SCHEMA_VERSION`].
+    #[serde(default, skip_serializing_if = "is_unversioned")]
+    pub schema_version: u32,
+}
+
+fn is_unversioned(version: &u32) -> bool {
+    *version == 0
+}
+
+/// Outgoing edge stored on the source node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecEdge {
+    pub to: String,
+    #[serde(rename = "type")]
+    pub edge_type: String,
+    pub rationale: String,
+    pub confidence: f64,
+    pub status: String,
+    /// Unix seconds when the edge was first added; absent on older edges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    /// Unix seconds of the last add/update of the edge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    /// What wrote the edge last (`manual`, `auto-term`, `derive`, ...);
+    /// absent on older edges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
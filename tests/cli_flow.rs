@@ -21,7 +21,7 @@ fn init_creates_meta_json_and_lint_passes() {
 
     fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(
         init.status.success(),
         "init failed: {}",
@@ -42,7 +42,7 @@ fn init_creates_meta_json_and_lint_passes() {
     )
     .expect("write updated meta");
 
-    let lint = run_foundry(&root, &["spec", "lint"]);
+    let lint = run_foundry(root, &["spec", "lint"]);
     assert!(
         lint.status.success(),
         "lint failed: {}\n{}",
@@ -63,7 +63,7 @@ fn init_scans_tasks_directory_and_generates_meta() {
     fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
     fs::write(tasks_dir.join("01-task.md"), "# Task\n\ncontent").expect("write task markdown");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(
         init.status.success(),
         "init failed: {}",
@@ -76,6 +76,283 @@ fn init_scans_tasks_directory_and_generates_meta() {
     assert!(task_meta_raw.contains("\"body_md_path\": \"tasks/spc-001/01-task.md\""));
 }
 
+#[test]
+fn init_and_write_print_json_summaries() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+    fs::write(root.join("spec/a.md"), "# A\n\ncontent").expect("write a");
+    fs::write(root.join("spec/b.md"), "# B\n\ncontent").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--format", "json"]);
+    assert!(init.status.success(), "init failed");
+    let summary: serde_json::Value = serde_json::from_slice(&init.stdout).expect("parse init json");
+    assert_eq!(summary["created"], 2);
+    assert_eq!(summary["updated"], 0);
+    assert_eq!(summary["skipped"], 0);
+    assert_eq!(summary["errors"], 0);
+    assert_eq!(summary["ids"], serde_json::json!(["SPC-001", "SPC-002"]));
+    assert!(summary.get("agent_templates").is_none());
+
+    let write = run_foundry(
+        root,
+        &[
+            "spec", "write", "--id", "SPC-002", "--status", "active", "--title", "Bee", "--format",
+            "json",
+        ],
+    );
+    assert!(write.status.success(), "write failed");
+    let output: serde_json::Value =
+        serde_json::from_slice(&write.stdout).expect("parse write json");
+    assert_eq!(
+        output,
+        serde_json::json!({
+            "id": "SPC-002",
+            "path": "spec/b.md",
+            "action": "updated",
+            "title": "Bee",
+            "status": "active",
+        })
+    );
+
+    let init = run_foundry(root, &["spec", "init", "--format", "json"]);
+    let summary: serde_json::Value = serde_json::from_slice(&init.stdout).expect("parse init json");
+    assert_eq!(summary["skipped"], 2);
+    assert_eq!(summary["ids"], serde_json::json!([]));
+}
+
+#[test]
+fn quiet_silences_status_lines_but_not_json_output() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+    fs::write(root.join("spec/a.md"), "# A\n\ncontent").expect("write a");
+
+    let init = run_foundry(root, &["spec", "init", "--quiet"]);
+    assert!(init.status.success(), "quiet init failed");
+    assert!(init.stdout.is_empty(), "{}", String::from_utf8_lossy(&init.stdout));
+    assert!(root.join("spec/a.meta.json").exists());
+
+    let init = run_foundry(root, &["--quiet", "spec", "init", "--format", "json"]);
+    assert!(init.status.success(), "quiet json init failed");
+    let summary: serde_json::Value = serde_json::from_slice(&init.stdout).expect("parse init json");
+    assert_eq!(summary["skipped"], 1);
+
+    let init = run_foundry(root, &["spec", "init", "--verbose"]);
+    assert!(init.status.success(), "verbose init failed");
+    assert!(String::from_utf8_lossy(&init.stdout).contains("init summary: created=0"));
+    assert!(String::from_utf8_lossy(&init.stderr).contains("debug: project root: "));
+
+    let both = run_foundry(root, &["spec", "init", "--quiet", "--verbose"]);
+    assert_eq!(both.status.code(), Some(2));
+}
+
+#[test]
+fn init_watch_creates_meta_for_new_markdown() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"audit":{"enabled":true}}"#,
+    )
+    .expect("write config");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_foundry"))
+        .args(["spec", "init", "--watch"])
+        .current_dir(root)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn init --watch");
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout"));
+    let mut line = String::new();
+    while !line.starts_with("init watch: watching") {
+        line.clear();
+        let read = stdout.read_line(&mut line).expect("read watch output");
+        assert!(read > 0, "watcher exited before it started");
+    }
+    assert!(spec_dir.join("a.meta.json").exists());
+
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    let meta_path = spec_dir.join("b.meta.json");
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let meta = loop {
+        let parsed = fs::read_to_string(&meta_path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+        if let Some(meta) = parsed {
+            break meta;
+        }
+        assert!(Instant::now() < deadline, "b.meta.json was not created");
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    // Edits arriving faster than the debounce window must not postpone the
+    // sync until they stop.
+    let c_md = spec_dir.join("c.md");
+    let c_meta = spec_dir.join("c.meta.json");
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut revision = 0;
+    while !c_meta.exists() && Instant::now() < deadline {
+        revision += 1;
+        fs::write(&c_md, format!("# C\n\nrevision {revision}")).expect("write c");
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    child.kill().expect("stop watcher");
+    let _ = child.wait();
+
+    assert_eq!(meta["id"], "SPC-002");
+    assert_eq!(meta["title"], "B");
+    assert!(c_meta.exists(), "c.meta.json waited for edits to stop");
+
+    let audit = fs::read_to_string(root.join(".foundry/audit.jsonl")).expect("read audit log");
+    let watch_entry = audit
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("parse audit line"))
+        .find(|entry| entry["command"] == "init watch" && entry["ids"][0] == "SPC-002")
+        .unwrap_or_else(|| panic!("no init watch audit line for SPC-002: {audit}"));
+    assert_eq!(watch_entry["action"], "created");
+}
+
+#[test]
+fn export_then_import_bundle_round_trips_nodes() {
+    let source = tempdir().expect("create source dir");
+    let source = source.path();
+    fs::create_dir_all(source.join("spec/nested")).expect("create spec dir");
+    fs::write(source.join("spec/a.md"), "# A\n\nFirst node.\n").expect("write a");
+    fs::write(source.join("spec/b.md"), "# B\n\nSecond node, no trailing newline")
+        .expect("write b");
+    fs::write(source.join("spec/nested/c.md"), "# C\n\n`user_id` rules.\n").expect("write c");
+    let init = run_foundry(source, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let link = run_foundry(
+        source,
+        &[
+            "spec", "link", "add", "--from", "SPC-002", "--to", "SPC-001", "--type", "depends_on",
+            "--rationale", "b needs a",
+        ],
+    );
+    assert!(link.status.success(), "link failed");
+
+    let bundle_path = source.join("specs.json");
+    let bundle_arg = bundle_path.to_str().expect("utf8 path");
+    let export = run_foundry(source, &["spec", "export", "bundle", "--out", bundle_arg]);
+    assert!(
+        export.status.success(),
+        "export failed: {}",
+        String::from_utf8_lossy(&export.stderr)
+    );
+    let bundle: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&bundle_path).expect("read bundle"))
+            .expect("parse bundle");
+    assert_eq!(bundle["schema_version"], 1);
+    assert_eq!(bundle["nodes"].as_array().expect("nodes").len(), 3);
+
+    let target = tempdir().expect("create target dir");
+    let target = target.path();
+    let import = run_foundry(target, &["spec", "import", "bundle", "--in", bundle_arg]);
+    assert!(
+        import.status.success(),
+        "import failed: {}",
+        String::from_utf8_lossy(&import.stderr)
+    );
+    for md in ["spec/a.md", "spec/b.md", "spec/nested/c.md"] {
+        assert_eq!(
+            fs::read(source.join(md)).expect("read source md"),
+            fs::read(target.join(md)).expect("read imported md"),
+            "{md} differs"
+        );
+        let meta = md.replace(".md", ".meta.json");
+        let parse = |root: &Path| -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(root.join(&meta)).expect("read meta"))
+                .expect("parse meta")
+        };
+        assert_eq!(parse(source), parse(target), "{meta} differs");
+    }
+    let again = run_foundry(target, &["spec", "import", "bundle", "--in", bundle_arg]);
+    assert!(!again.status.success(), "import should refuse to overwrite");
+    assert!(String::from_utf8_lossy(&again.stderr).contains("--force"));
+    let forced = run_foundry(
+        target,
+        &["spec", "import", "bundle", "--in", bundle_arg, "--force"],
+    );
+    assert!(forced.status.success(), "forced import failed");
+}
+
+#[test]
+fn foundryignore_skips_matching_files_for_init_and_index() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(spec_dir.join("_drafts")).expect("create drafts dir");
+    fs::write(root.join(".foundryignore"), "# scratch space\n_drafts/\n").expect("write ignore");
+    fs::write(spec_dir.join("a.md"), "# Published\n\nSession rules.").expect("write a");
+    fs::write(spec_dir.join("_drafts/idea.md"), "# Draft\n\nSession ideas.").expect("write draft");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    assert!(String::from_utf8_lossy(&init.stdout).contains("created=1"));
+    assert!(spec_dir.join("a.meta.json").exists());
+    assert!(!spec_dir.join("_drafts/idea.meta.json").exists());
+
+    // A stale meta left behind in an ignored directory stays invisible too.
+    fs::write(spec_dir.join("_drafts/idea.meta.json"), "not json").expect("write stale meta");
+    let lint = run_foundry(root, &["spec", "lint", "--ignore", "orphan"]);
+    assert!(
+        lint.status.success(),
+        "lint failed: {}",
+        String::from_utf8_lossy(&lint.stdout)
+    );
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let conn = rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+    let paths = conn
+        .prepare("SELECT md_path FROM nodes")
+        .expect("prepare")
+        .query_map([], |row| row.get::<_, String>(0))
+        .expect("query nodes")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("read nodes");
+    assert_eq!(paths, vec!["spec/a.md"]);
+}
+
+#[test]
+fn gitignored_markdown_is_skipped_unless_no_ignore() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(spec_dir.join("vendor")).expect("create vendor dir");
+    fs::write(root.join(".gitignore"), "spec/vendor/\n").expect("write gitignore");
+    fs::write(spec_dir.join("a.md"), "# Ours\n\ncontent").expect("write a");
+    fs::write(spec_dir.join("vendor/lib.md"), "# Vendored\n\ncontent").expect("write vendored");
+
+    let init = run_foundry(root, &["spec", "init", "--format", "json"]);
+    assert!(init.status.success(), "init failed");
+    let summary: serde_json::Value = serde_json::from_slice(&init.stdout).expect("parse init json");
+    assert_eq!(summary["created"], 1);
+    assert!(!spec_dir.join("vendor/lib.meta.json").exists());
+
+    let init = run_foundry(root, &["spec", "init", "--no-ignore", "--format", "json"]);
+    assert!(init.status.success(), "init --no-ignore failed");
+    let summary: serde_json::Value = serde_json::from_slice(&init.stdout).expect("parse init json");
+    assert_eq!(summary["created"], 1);
+    assert_eq!(summary["skipped"], 1);
+    assert!(spec_dir.join("vendor/lib.meta.json").exists());
+
+    let node = run_foundry(root, &["spec", "node", "show", "SPC-002"]);
+    assert!(!node.status.success(), "gitignored node should stay hidden");
+    let node = run_foundry(root, &["--no-ignore", "spec", "node", "show", "SPC-002"]);
+    assert!(node.status.success(), "--no-ignore should expose the vendored node");
+}
+
 #[test]
 fn write_creates_markdown_and_meta_with_defaults() {
     let root = tempdir().expect("create temp dir");
@@ -83,7 +360,7 @@ fn write_creates_markdown_and_meta_with_defaults() {
     fs::create_dir_all(root.join("spec")).expect("create spec dir");
 
     let write = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "write",
@@ -129,11 +406,11 @@ fn write_updates_existing_meta_without_losing_edges() {
     fs::write(spec_dir.join("a.md"), "# A").expect("write a");
     fs::write(spec_dir.join("b.md"), "# B").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let add = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "link",
@@ -151,7 +428,7 @@ fn write_updates_existing_meta_without_losing_edges() {
     assert!(add.status.success(), "add failed");
 
     let write = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "write",
@@ -180,6 +457,101 @@ fn write_updates_existing_meta_without_losing_edges() {
     assert_eq!(edges[0]["to"], "SPC-002");
 }
 
+#[test]
+fn write_replaces_meta_atomically_and_keeps_backup_on_request() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let add = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "add",
+            "--from",
+            "SPC-001",
+            "--to",
+            "SPC-002",
+            "--type",
+            "depends_on",
+            "--rationale",
+            "a depends on b",
+        ],
+    );
+    assert!(add.status.success(), "add failed");
+    let before = fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a");
+
+    // A temp file left behind by an interrupted write must not leak into
+    // the result.
+    fs::write(spec_dir.join(".a.meta.json.tmp"), "{\"id\": \"SPC-0").expect("write stale tmp");
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/a.md",
+            "--body",
+            "# A v2",
+            "--backup",
+        ],
+    );
+    assert!(
+        write.status.success(),
+        "write failed: {}",
+        String::from_utf8_lossy(&write.stderr)
+    );
+
+    let a_meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+            .expect("meta stays valid JSON");
+    assert_eq!(a_meta["title"], "A v2");
+    assert_eq!(a_meta["edges"][0]["to"], "SPC-002");
+    assert_eq!(
+        fs::read_to_string(spec_dir.join("a.meta.json.bak")).expect("read backup"),
+        before
+    );
+    let mut leftovers = fs::read_dir(&spec_dir)
+        .expect("read spec dir")
+        .map(|entry| {
+            entry
+                .expect("dir entry")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    leftovers.sort();
+    assert_eq!(
+        leftovers,
+        [
+            "a.md",
+            "a.meta.json",
+            "a.meta.json.bak",
+            "b.md",
+            "b.meta.json"
+        ]
+    );
+
+    let lint = run_foundry(root, &["spec", "lint"]);
+    assert!(lint.status.success(), "backups are not read as nodes");
+
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/b.md", "--body", "# B v2"],
+    );
+    assert!(write.status.success(), "write failed");
+    assert!(
+        !spec_dir.join("b.meta.json.bak").exists(),
+        "no backup without --backup"
+    );
+}
+
 #[test]
 fn write_updates_node_by_id_without_path() {
     let root = tempdir().expect("create temp dir");
@@ -188,11 +560,11 @@ fn write_updates_node_by_id_without_path() {
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(spec_dir.join("a.md"), "# A\n\nold").expect("write a");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let write = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "write",
@@ -221,6 +593,209 @@ fn write_updates_node_by_id_without_path() {
     assert_eq!(meta["title"], "A In Progress");
 }
 
+#[test]
+fn write_auto_terms_extracts_identifiers_and_heading_words() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/session.md",
+            "--term",
+            "Session",
+            "--auto-terms",
+            "--body",
+            "# Session Rotation\n\nRotate tokens keyed by `user_id` via the Token Store.\nRevoke on Auth. Session Token reuse.",
+        ],
+    );
+    assert!(
+        write.status.success(),
+        "write failed: {}",
+        String::from_utf8_lossy(&write.stderr)
+    );
+
+    let meta: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(root.join("spec/session.meta.json")).expect("read meta"),
+    )
+    .expect("parse meta");
+    let terms = meta["terms"]
+        .as_array()
+        .expect("terms array")
+        .iter()
+        .filter_map(|t| t.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        terms,
+        vec!["Session", "user_id", "Rotation", "Token Store", "Session Token"]
+    );
+}
+
+#[test]
+fn diff_reports_stale_node_after_markdown_edit() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# Old Title\n\nbody").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let clean = run_foundry(root, &["spec", "diff"]);
+    assert_eq!(clean.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&clean.stdout).contains("spec diff: ok (checked=2)"));
+
+    let old_meta: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a meta"),
+    )
+    .expect("parse a meta");
+    fs::write(spec_dir.join("a.md"), "# New Title\n\nbody changed").expect("rewrite a");
+
+    let diff = run_foundry(root, &["spec", "diff", "SPC-001"]);
+    assert_eq!(diff.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&diff.stdout);
+    assert!(stdout.contains("SPC-001 stale spec/a.md"), "{stdout}");
+    assert!(
+        stdout.contains(&format!("hash: {} -> ", old_meta["hash"].as_str().unwrap())),
+        "{stdout}"
+    );
+    assert!(stdout.contains("  -Old Title\n  +New Title"), "{stdout}");
+
+    let json = run_foundry(root, &["spec", "diff", "--format", "json"]);
+    let output: serde_json::Value = serde_json::from_slice(&json.stdout).expect("parse diff json");
+    assert_eq!(output["stale_count"], 1);
+    assert_eq!(output["nodes"][0]["state"], "stale");
+    assert_eq!(output["nodes"][0]["current_title"], "New Title");
+    assert_eq!(output["nodes"][1]["state"], "ok");
+}
+
+#[test]
+fn archive_moves_leaf_node_and_downgrades_incoming_edges() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(spec_dir.join("auth")).expect("create spec dir");
+    fs::write(spec_dir.join("auth/login.md"), "# Login").expect("write login");
+    fs::write(spec_dir.join("session.md"), "# Session").expect("write session");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let link = run_foundry(
+        root,
+        &[
+            "spec", "link", "add", "--from", "SPC-002", "--to", "SPC-001", "--type", "depends_on",
+            "--rationale", "session needs login",
+        ],
+    );
+    assert!(link.status.success(), "link add failed");
+
+    let refused = run_foundry(root, &["spec", "archive", "SPC-001"]);
+    assert_eq!(refused.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("active nodes depend on it (SPC-002)"));
+    assert!(spec_dir.join("auth/login.md").exists(), "refused archive must not move files");
+
+    let archive = run_foundry(root, &["spec", "archive", "SPC-001", "--force"]);
+    assert!(archive.status.success(), "archive failed");
+    assert!(!spec_dir.join("auth/login.md").exists());
+    assert!(!spec_dir.join("auth/login.meta.json").exists());
+    assert!(spec_dir.join("archive/auth/login.md").exists());
+    let archived: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(spec_dir.join("archive/auth/login.meta.json")).expect("read archived"),
+    )
+    .expect("parse archived");
+    assert_eq!(archived["status"], "archived");
+    assert_eq!(archived["body_md_path"], "spec/archive/auth/login.md");
+
+    let session: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(spec_dir.join("session.meta.json")).expect("read session"),
+    )
+    .expect("parse session");
+    assert_eq!(session["edges"][0]["status"], "proposed");
+}
+
+#[test]
+fn archive_leaves_files_in_place_when_meta_write_fails() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(spec_dir.join("auth")).expect("create spec dir");
+    fs::write(spec_dir.join("auth/login.md"), "# Login").expect("write login");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    // A directory where the meta's temp file goes makes the meta write fail.
+    fs::create_dir_all(spec_dir.join("archive/auth/.login.meta.json.tmp"))
+        .expect("block temp path");
+    let archive = run_foundry(root, &["spec", "archive", "SPC-001"]);
+    assert_eq!(archive.status.code(), Some(2));
+    assert!(spec_dir.join("auth/login.md").exists(), "body must stay put");
+    assert!(!spec_dir.join("archive/auth/login.md").exists());
+    assert!(!spec_dir.join("archive/auth/login.meta.json").exists());
+    let meta = fs::read_to_string(spec_dir.join("auth/login.meta.json")).expect("read meta");
+    assert!(meta.contains("\"body_md_path\": \"spec/auth/login.md\""), "{meta}");
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn serve_returns_node_json_on_ephemeral_port() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::process::Stdio;
+
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+    fs::write(root.join("spec/a.md"), "# Served Node").expect("write markdown");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "--db", "custom.db", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_foundry"))
+        .args(["spec", "serve", "--port", "0", "--db", "custom.db"])
+        .current_dir(root)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn serve");
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().expect("stdout"))
+        .read_line(&mut line)
+        .expect("read listen line");
+    let addr = line
+        .trim()
+        .strip_prefix("spec serve: listening on http://")
+        .expect("listen address")
+        .to_string();
+
+    let get = |path: &str| -> String {
+        let mut stream = std::net::TcpStream::connect(&addr).expect("connect");
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n")
+            .expect("send request");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read response");
+        response
+    };
+    let response = get("/nodes/SPC-001");
+    let missing = get("/nodes/SPC-999");
+    let search = get("/search?q=served");
+    child.kill().expect("stop serve");
+    let _ = child.wait();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "response: {response}");
+    let body = response.split("\r\n\r\n").nth(1).expect("body");
+    let node: serde_json::Value = serde_json::from_str(body).expect("parse node");
+    assert_eq!(node["id"], "SPC-001");
+    assert_eq!(node["title"], "Served Node");
+    assert!(missing.starts_with("HTTP/1.1 404"), "response: {missing}");
+    assert!(search.starts_with("HTTP/1.1 200"), "response: {search}");
+    let body = search.split("\r\n\r\n").nth(1).expect("body");
+    let output: serde_json::Value = serde_json::from_str(body).expect("parse search");
+    assert_eq!(output["hits"][0]["id"], "SPC-001");
+}
+
 #[test]
 fn derive_design_creates_design_node_and_refines_edge() {
     let root = tempdir().expect("create temp dir");
@@ -229,11 +804,11 @@ fn derive_design_creates_design_node_and_refines_edge() {
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(spec_dir.join("10-spec.md"), "# Auth Spec\n\ncontent").expect("write source spec");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let derive = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -277,7 +852,7 @@ fn derive_design_fails_when_source_missing() {
     fs::create_dir_all(root.join("spec")).expect("create spec dir");
 
     let derive = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -294,6 +869,60 @@ fn derive_design_fails_when_source_missing() {
     );
 }
 
+#[test]
+fn derive_test_creates_test_spec_with_tests_edge() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("10-spec.md"), "# Auth Spec\n\ncontent").expect("write source spec");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let derive = run_foundry(
+        root,
+        &[
+            "spec",
+            "derive",
+            "test",
+            "--from",
+            "SPC-001",
+            "--path",
+            "spec/50-auth-test.md",
+        ],
+    );
+    assert!(
+        derive.status.success(),
+        "derive failed: {}",
+        String::from_utf8_lossy(&derive.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&derive.stdout).trim(),
+        "spec derive test: source=SPC-001 derived=SPC-002 edge=tests"
+    );
+
+    let meta: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(root.join("spec/50-auth-test.meta.json")).expect("read test meta"),
+    )
+    .expect("parse test meta");
+    assert_eq!(meta["type"], "test_spec");
+    assert_eq!(meta["status"], "draft");
+    assert_eq!(meta["title"], "Tests for Auth Spec");
+    let edges = meta["edges"].as_array().expect("edges should be array");
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["to"], "SPC-001");
+    assert_eq!(edges[0]["type"], "tests");
+    assert_eq!(edges[0]["status"], "confirmed");
+    let body = fs::read_to_string(root.join("spec/50-auth-test.md")).expect("read test body");
+    assert!(body.contains("## Test Cases"), "{body}");
+    assert!(body.contains("- Tests: SPC-001"), "{body}");
+
+    let missing = run_foundry(root, &["spec", "derive", "test", "--from", "SPC-999"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("source node not found: SPC-999"));
+}
+
 #[test]
 fn derive_design_json_format_returns_machine_readable_output() {
     let root = tempdir().expect("create temp dir");
@@ -302,11 +931,11 @@ fn derive_design_json_format_returns_machine_readable_output() {
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(spec_dir.join("10-spec.md"), "# Auth Spec\n\ncontent").expect("write source spec");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let derive = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -338,11 +967,11 @@ fn derive_tasks_creates_task_node_with_refines_and_depends_on() {
 
     fs::write(spec_dir.join("10-design-a.md"), "# Design A\n\ncontent").expect("write design a");
     fs::write(spec_dir.join("11-design-b.md"), "# Design B\n\ncontent").expect("write design b");
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let task1 = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -360,7 +989,7 @@ fn derive_tasks_creates_task_node_with_refines_and_depends_on() {
     assert!(task1.status.success(), "task1 derive failed");
 
     let task2 = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -403,11 +1032,11 @@ fn derive_tasks_fails_for_unknown_depends_on_target() {
     fs::create_dir_all(&tasks_dir).expect("create tasks dir");
     fs::write(spec_dir.join("10-design-a.md"), "# Design A\n\ncontent").expect("write design a");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let task = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -433,11 +1062,11 @@ fn derive_tasks_with_items_creates_multiple_task_nodes() {
     fs::create_dir_all(&tasks_dir).expect("create tasks dir");
     fs::write(spec_dir.join("10-design-a.md"), "# Design A\n\ncontent").expect("write design a");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let derive = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -488,11 +1117,11 @@ fn derive_tasks_with_chain_adds_dependency_to_previous_generated_task() {
     fs::create_dir_all(&tasks_dir).expect("create tasks dir");
     fs::write(spec_dir.join("10-design-a.md"), "# Design A\n\ncontent").expect("write design a");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let derive = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -532,11 +1161,11 @@ fn derive_tasks_json_format_returns_machine_readable_output() {
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(spec_dir.join("10-design-a.md"), "# Design A\n\ncontent").expect("write design a");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let derive = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "derive",
@@ -574,7 +1203,7 @@ fn init_with_agents_generates_command_templates() {
     fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
 
     let init = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "init",
@@ -666,7 +1295,7 @@ fn init_with_agents_install_output_writes_agent_auto_read_dirs() {
     let claude_dir_str = claude_dir.to_string_lossy().to_string();
 
     let init = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "init",
@@ -716,7 +1345,7 @@ fn init_agent_without_sync_does_not_overwrite_existing_template() {
     fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
 
     let first = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "init",
@@ -733,7 +1362,7 @@ fn init_agent_without_sync_does_not_overwrite_existing_template() {
     fs::write(&target, "CUSTOM\n").expect("write custom");
 
     let second = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "init",
@@ -758,7 +1387,7 @@ fn agent_doctor_reports_ok_after_agent_init() {
     fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
 
     let init = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "init",
@@ -774,7 +1403,7 @@ fn agent_doctor_reports_ok_after_agent_init() {
     assert!(init.status.success(), "init failed");
 
     let doctor = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "agent",
@@ -793,32 +1422,159 @@ fn agent_doctor_reports_ok_after_agent_init() {
 }
 
 #[test]
-fn agent_doctor_detects_stale_generated_file() {
+fn agent_list_prints_supported_agents_and_unknown_agent_is_rejected() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
-    let spec_dir = root.join("spec");
-    fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
 
-    let init = run_foundry(
-        &root,
+    let init = run_foundry(root, &["spec", "init", "--agent", "cursor"]);
+    assert_eq!(init.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&init.stderr);
+    assert!(stderr.contains("cursor"), "{stderr}");
+    assert!(stderr.contains("codex, claude, gemini"), "{stderr}");
+
+    let list = run_foundry(
+        root,
         &[
             "spec",
-            "init",
-            "--sync",
-            "--template-source",
-            "local",
-            "--agent",
-            "codex",
+            "agent",
+            "list",
+            "--codex-home",
+            "/opt/codex",
+            "--claude-dir",
+            "/opt/claude",
+            "--format",
+            "json",
         ],
     );
-    assert!(init.status.success(), "init failed");
+    assert!(list.status.success(), "agent list failed");
+    let output: serde_json::Value = serde_json::from_slice(&list.stdout).expect("parse list");
+    let agents = output["agents"].as_array().expect("agents");
+    let names = agents
+        .iter()
+        .map(|a| a["agent"].as_str().expect("agent"))
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["codex", "claude", "gemini"]);
+    assert_eq!(agents[0]["docs_dir"], "docs/agents/codex");
+    assert_eq!(
+        agents[0]["install_dirs"],
+        serde_json::json!([
+            { "artifact": "commands", "path": "/opt/codex/prompts" },
+            { "artifact": "skills", "path": "/opt/codex/skills/foundry" }
+        ])
+    );
+    assert_eq!(
+        agents[1]["install_dirs"][0]["path"],
+        "/opt/claude/commands/foundry"
+    );
+
+    let table = run_foundry(root, &["spec", "agent", "list"]);
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    assert!(
+        stdout.lines().any(|l| l.starts_with("codex | ")),
+        "{stdout}"
+    );
+    assert!(
+        stdout.lines().any(|l| l.starts_with("claude | ")),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn init_generates_gemini_and_generic_agent_templates() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let gemini_home = root.join("gemini-home");
+    let gemini_home = gemini_home.to_str().expect("utf8 path");
+
+    let init = run_foundry(
+        root,
+        &[
+            "spec",
+            "init",
+            "--template-source",
+            "local",
+            "--agent",
+            "gemini",
+            "--agent-output",
+            "both",
+            "--gemini-home",
+            gemini_home,
+            "--agent-dir",
+            "cursor:tools/cursor",
+        ],
+    );
+    assert!(init.status.success(), "init failed");
+    for artifact in ["commands", "skills"] {
+        let docs = root.join(format!("docs/agents/gemini/{artifact}/implement.md"));
+        let docs = fs::read_to_string(&docs).expect("read gemini docs output");
+        assert!(docs.contains("Gemini"), "{docs}");
+        assert!(
+            root.join(format!("gemini-home/{artifact}/foundry/implement.md"))
+                .is_file()
+        );
+        let generic =
+            fs::read_to_string(root.join(format!("tools/cursor/{artifact}/implement.md")))
+                .expect("read generic output");
+        assert!(generic.contains("Agent"), "{generic}");
+    }
+    assert!(!root.join("docs/agents/cursor").exists());
+
+    let doctor = run_foundry(
+        root,
+        &[
+            "spec",
+            "agent",
+            "doctor",
+            "--template-source",
+            "local",
+            "--agent",
+            "gemini",
+            "--agent-output",
+            "both",
+            "--gemini-home",
+            gemini_home,
+            "--agent-dir",
+            "cursor:tools/cursor",
+            "--format",
+            "json",
+        ],
+    );
+    let output: serde_json::Value = serde_json::from_slice(&doctor.stdout).expect("parse doctor");
+    assert_eq!(output["ok"], true, "{output}");
+    assert_eq!(output["checked"], 28);
+
+    let bad = run_foundry(root, &["spec", "init", "--agent-dir", "cursor"]);
+    assert_eq!(bad.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("<name>:<path>"));
+}
+
+#[test]
+fn agent_doctor_detects_stale_generated_file() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("01-example.md"), "# Example\n\ncontent").expect("write markdown");
+
+    let init = run_foundry(
+        root,
+        &[
+            "spec",
+            "init",
+            "--sync",
+            "--template-source",
+            "local",
+            "--agent",
+            "codex",
+        ],
+    );
+    assert!(init.status.success(), "init failed");
 
     let target = root.join("docs/agents/codex/commands/spec-plan.md");
     fs::write(&target, "BROKEN\n").expect("write broken template");
 
     let doctor = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "agent",
@@ -848,11 +1604,11 @@ fn link_add_and_remove_updates_meta() {
     fs::write(spec_dir.join("a.md"), "# A").expect("write a");
     fs::write(spec_dir.join("b.md"), "# B").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let add = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "link",
@@ -874,7 +1630,7 @@ fn link_add_and_remove_updates_meta() {
     assert!(a_meta.contains("\"type\": \"depends_on\""));
 
     let remove = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "link",
@@ -894,6 +1650,283 @@ fn link_add_and_remove_updates_meta() {
     assert!(!a_meta_after.contains("\"to\": \"SPC-002\""));
 }
 
+#[test]
+fn link_add_appends_audit_line_when_enabled() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let audit_path = root.join(".foundry/audit.jsonl");
+    assert!(!audit_path.exists(), "audit log written while disabled");
+
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"audit":{"enabled":true}}"#,
+    )
+    .expect("write config");
+
+    let add = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "add",
+            "--from",
+            "SPC-001",
+            "--to",
+            "SPC-002",
+            "--type",
+            "depends_on",
+            "--rationale",
+            "a depends on b",
+        ],
+    );
+    assert!(add.status.success(), "add failed");
+
+    let log = fs::read_to_string(&audit_path).expect("read audit log");
+    let lines = log.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1, "unexpected audit log: {log}");
+    let entry: serde_json::Value = serde_json::from_str(lines[0]).expect("parse audit line");
+    assert_eq!(entry["command"], "link add");
+    assert_eq!(entry["action"], "add");
+    assert_eq!(entry["ids"], serde_json::json!(["SPC-001", "SPC-002"]));
+    assert!(entry["timestamp"].as_i64().is_some());
+}
+
+#[test]
+fn meta_rewriting_commands_append_audit_lines() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"audit":{"enabled":true}}"#,
+    )
+    .expect("write config");
+
+    let steps: [&[&str]; 4] = [
+        &[
+            "spec", "link", "add", "--from", "SPC-002", "--to", "SPC-001", "--type", "depends_on",
+            "--rationale", "b builds on a",
+        ],
+        &["spec", "lint", "--fix"],
+        &["spec", "archive", "SPC-001", "--force"],
+        &["spec", "link", "prune", "--also-proposed"],
+    ];
+    fs::write(spec_dir.join("a.md"), "# A\n\nedited").expect("edit a");
+    for args in steps {
+        let out = run_foundry(root, args);
+        assert!(out.status.success(), "{args:?} failed");
+    }
+
+    let log = fs::read_to_string(root.join(".foundry/audit.jsonl")).expect("read audit log");
+    let entries = log
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("parse audit line"))
+        .map(|entry| (entry["command"].clone(), entry["ids"].clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            (serde_json::json!("link add"), serde_json::json!(["SPC-002", "SPC-001"])),
+            (serde_json::json!("lint"), serde_json::json!(["SPC-001"])),
+            (serde_json::json!("archive"), serde_json::json!(["SPC-001", "SPC-002"])),
+            (serde_json::json!("link prune"), serde_json::json!(["SPC-002"])),
+        ]
+    );
+}
+
+#[test]
+fn link_prune_removes_edges_to_deleted_nodes() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let link = run_foundry(
+        root,
+        &[
+            "spec", "link", "add", "--from", "SPC-001", "--to", "SPC-002", "--type", "depends_on",
+            "--rationale", "a needs b",
+        ],
+    );
+    assert!(link.status.success(), "link add failed");
+    fs::remove_file(spec_dir.join("b.meta.json")).expect("remove b meta");
+
+    let dry_run = run_foundry(root, &["spec", "link", "prune", "--dry-run"]);
+    assert!(dry_run.status.success(), "dry run failed");
+    let stdout = String::from_utf8_lossy(&dry_run.stdout);
+    assert!(stdout.contains("would prune 1 edge(s)"));
+    assert!(stdout.contains("-> SPC-002 (depends_on) dangling"));
+    let a_meta = fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a");
+    assert!(a_meta.contains("SPC-002"), "dry run must not write");
+
+    let prune = run_foundry(root, &["spec", "link", "prune"]);
+    assert!(prune.status.success(), "prune failed");
+    assert!(String::from_utf8_lossy(&prune.stdout).contains("link prune summary: files=1 edges=1"));
+    let a_meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+            .expect("parse a");
+    assert!(a_meta["edges"].as_array().expect("edges").is_empty());
+}
+
+#[test]
+fn link_edges_are_stored_sorted_regardless_of_insertion_order() {
+    let edges = [
+        ("SPC-003", "impacts"),
+        ("SPC-002", "refines"),
+        ("SPC-004", "depends_on"),
+        ("SPC-002", "depends_on"),
+    ];
+    let stored_edges = |order: &[usize]| -> Vec<(String, String)> {
+        let root = tempdir().expect("create temp dir");
+        let root = root.path();
+        let spec_dir = root.join("spec");
+        fs::create_dir_all(&spec_dir).expect("create spec dir");
+        for name in ["a", "b", "c", "d"] {
+            fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+        }
+        let init = run_foundry(root, &["spec", "init", "--sync"]);
+        assert!(init.status.success(), "init failed");
+        for &idx in order {
+            let (to, edge_type) = edges[idx];
+            let add = run_foundry(
+                root,
+                &[
+                    "spec", "link", "add", "--from", "SPC-001", "--to", to, "--type", edge_type,
+                    "--rationale", "ordering",
+                ],
+            );
+            assert!(add.status.success(), "add {to} {edge_type} failed");
+        }
+        let meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+                .expect("parse a");
+        meta["edges"]
+            .as_array()
+            .expect("edges")
+            .iter()
+            .map(|e| {
+                (
+                    e["to"].as_str().expect("to").to_string(),
+                    e["type"].as_str().expect("type").to_string(),
+                )
+            })
+            .collect()
+    };
+
+    let expected = vec![
+        ("SPC-002".to_string(), "depends_on".to_string()),
+        ("SPC-002".to_string(), "refines".to_string()),
+        ("SPC-003".to_string(), "impacts".to_string()),
+        ("SPC-004".to_string(), "depends_on".to_string()),
+    ];
+    assert_eq!(stored_edges(&[0, 1, 2, 3]), expected);
+    assert_eq!(stored_edges(&[3, 2, 1, 0]), expected);
+    assert_eq!(stored_edges(&[2, 0, 3, 1]), expected);
+}
+
+#[test]
+fn link_add_bulk_applies_all_rows_from_json_file() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["a", "b", "c", "d"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}"))
+            .expect("write markdown");
+    }
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let bulk = serde_json::json!([
+        {"from": "SPC-001", "to": "SPC-002", "type": "depends_on", "rationale": "a->b"},
+        {"from": "SPC-001", "to": "SPC-003", "type": "impacts", "rationale": "a->c", "confidence": 0.7},
+        {"from": "SPC-002", "to": "SPC-003", "type": "depends_on", "rationale": "b->c"},
+        {"from": "SPC-003", "to": "SPC-004", "type": "refines", "rationale": "c->d"},
+        {"from": "SPC-004", "to": "SPC-001", "type": "tests", "rationale": "d->a"}
+    ]);
+    fs::write(
+        root.join("edges.json"),
+        serde_json::to_string_pretty(&bulk).expect("serialize bulk"),
+    )
+    .expect("write bulk file");
+
+    let add = run_foundry(root, &["spec", "link", "add", "--bulk", "edges.json"]);
+    assert!(
+        add.status.success(),
+        "bulk add failed: {}",
+        String::from_utf8_lossy(&add.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&add.stdout);
+    assert!(stdout.contains("bulk summary: rows=5 applied=5 failed=0"), "{stdout}");
+
+    let read_meta = |name: &str| -> serde_json::Value {
+        serde_json::from_str(
+            &fs::read_to_string(spec_dir.join(format!("{name}.meta.json"))).expect("read meta"),
+        )
+        .expect("parse meta")
+    };
+    let a = read_meta("a");
+    assert_eq!(a["edges"].as_array().expect("edges").len(), 2);
+    assert_eq!(a["edges"][1]["to"], "SPC-003");
+    assert_eq!(a["edges"][1]["confidence"], 0.7);
+    assert_eq!(read_meta("b")["edges"][0]["to"], "SPC-003");
+    assert_eq!(read_meta("c")["edges"][0]["type"], "refines");
+    assert_eq!(read_meta("d")["edges"][0]["to"], "SPC-001");
+}
+
+#[test]
+fn link_add_bulk_csv_with_invalid_row_writes_nothing() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    fs::write(
+        root.join("edges.csv"),
+        "from,to,type,rationale,confidence\nSPC-001,SPC-002,depends_on,\"a, then\nb\",1.0\nSPC-001,SPC-999,depends_on,missing,1.0\n",
+    )
+    .expect("write bulk csv");
+
+    let add = run_foundry(root, &["spec", "link", "add", "--bulk", "edges.csv"]);
+    assert_eq!(add.status.code(), Some(2));
+    let a_meta = fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a meta");
+    assert!(!a_meta.contains("SPC-002"), "{a_meta}");
+
+    let add = run_foundry(
+        root,
+        &["spec", "link", "add", "--bulk", "edges.csv", "--continue-on-error"],
+    );
+    assert!(add.status.success(), "continue-on-error add failed");
+    let a_meta = fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a meta");
+    // The quoted rationale keeps both its comma and its line break.
+    assert!(a_meta.contains("\"rationale\": \"a, then\\nb\""), "{a_meta}");
+    assert!(!a_meta.contains("SPC-999"), "{a_meta}");
+}
+
 #[test]
 fn impact_supports_depth_and_json_format() {
     let root = tempdir().expect("create temp dir");
@@ -904,11 +1937,11 @@ fn impact_supports_depth_and_json_format() {
     fs::write(spec_dir.join("b.md"), "# B").expect("write b");
     fs::write(spec_dir.join("c.md"), "# C").expect("write c");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let add_ab = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "link",
@@ -926,7 +1959,7 @@ fn impact_supports_depth_and_json_format() {
     assert!(add_ab.status.success(), "add a->b failed");
 
     let add_bc = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "link",
@@ -944,7 +1977,7 @@ fn impact_supports_depth_and_json_format() {
     assert!(add_bc.status.success(), "add b->c failed");
 
     let impact_depth_1 = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "impact",
@@ -965,7 +1998,7 @@ fn impact_supports_depth_and_json_format() {
     assert_eq!(json_depth_1["depth"], 1);
 
     let impact_depth_2 = run_foundry(
-        &root,
+        root,
         &[
             "spec",
             "impact",
@@ -986,6 +2019,201 @@ fn impact_supports_depth_and_json_format() {
     assert_eq!(json_depth_2["depth"], 2);
 }
 
+#[test]
+fn impact_min_confidence_drops_low_confidence_proposed_edges() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let meta_path = spec_dir.join("b.meta.json");
+    let mut meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&meta_path).expect("read meta"))
+            .expect("parse meta");
+    meta["edges"] = serde_json::json!([{
+        "to": "SPC-001",
+        "type": "depends_on",
+        "rationale": "guessed",
+        "confidence": 0.4,
+        "status": "proposed"
+    }]);
+    fs::write(&meta_path, serde_json::to_string_pretty(&meta).expect("encode meta"))
+        .expect("write meta");
+
+    let dependents = |extra: &[&str]| {
+        let mut args = vec!["spec", "impact", "SPC-001", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(
+            out.status.success(),
+            "impact failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+        json["reverse_dependents"]
+            .as_array()
+            .expect("reverse dependents array")
+            .len()
+    };
+    assert_eq!(dependents(&[]), 1);
+    assert_eq!(dependents(&["--min-confidence", "0.6"]), 0);
+    assert_eq!(dependents(&["--confirmed-only"]), 0);
+}
+
+#[test]
+fn impact_markdown_format_renders_report_sections() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    fs::write(spec_dir.join("c.md"), "# C").expect("write c");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    for (from, to, edge_type) in [
+        ("SPC-001", "SPC-002", "depends_on"),
+        ("SPC-003", "SPC-001", "depends_on"),
+    ] {
+        let add = run_foundry(
+            root,
+            &[
+                "spec", "link", "add", "--from", from, "--to", to, "--type", edge_type,
+                "--rationale", "needs it",
+            ],
+        );
+        assert!(add.status.success(), "add {from}->{to} failed");
+    }
+
+    let impact = run_foundry(root, &["spec", "impact", "SPC-001", "--format", "markdown"]);
+    assert!(impact.status.success(), "impact markdown failed");
+    let stdout = String::from_utf8_lossy(&impact.stdout);
+    assert!(stdout.contains("# Impact Report: [SPC-001](spec/a.md)"), "{stdout}");
+    assert!(stdout.contains("## Direct Dependencies"), "{stdout}");
+    assert!(
+        stdout.contains("| [SPC-002](spec/b.md) | depends_on | confirmed | 1 | needs it |"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("## Reverse Dependents\n\n- [SPC-003](spec/c.md)"), "{stdout}");
+    assert!(stdout.contains("## Conflict Risks\n\n_None_"), "{stdout}");
+    assert!(stdout.contains("1. [SPC-001](spec/a.md)"), "{stdout}");
+}
+
+#[test]
+fn impact_link_paths_prints_markdown_path_per_node() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    fs::write(spec_dir.join("c.md"), "# C").expect("write c");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    for (from, to) in [("SPC-001", "SPC-002"), ("SPC-003", "SPC-001")] {
+        let add = run_foundry(
+            root,
+            &[
+                "spec", "link", "add", "--from", from, "--to", to, "--type", "depends_on",
+                "--rationale", "needs it",
+            ],
+        );
+        assert!(add.status.success(), "add {from}->{to} failed");
+    }
+
+    let plain = run_foundry(root, &["spec", "impact", "SPC-001"]);
+    assert!(plain.status.success(), "impact failed");
+    assert!(!String::from_utf8_lossy(&plain.stdout).contains("spec/"));
+
+    let impact = run_foundry(root, &["spec", "impact", "SPC-001", "--link-paths"]);
+    assert!(impact.status.success(), "impact --link-paths failed");
+    let stdout = String::from_utf8_lossy(&impact.stdout);
+    assert!(
+        stdout.contains("  - SPC-002 -> spec/b.md [depends_on] status=confirmed"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("reverse_dependents:\n  - SPC-003 -> spec/c.md\n"),
+        "{stdout}"
+    );
+    for line in [
+        "  - SPC-001 -> spec/a.md",
+        "  - SPC-002 -> spec/b.md",
+        "  - SPC-003 -> spec/c.md",
+    ] {
+        assert!(stdout.contains(&format!("{line}\n")), "missing {line}: {stdout}");
+    }
+
+    let markdown = run_foundry(
+        root,
+        &["spec", "impact", "SPC-001", "--format", "markdown", "--link-paths"],
+    );
+    assert!(markdown.status.success(), "impact markdown --link-paths failed");
+    let stdout = String::from_utf8_lossy(&markdown.stdout);
+    assert!(stdout.contains("- [SPC-003](spec/c.md) -> spec/c.md"), "{stdout}");
+}
+
+#[test]
+fn impact_budget_fails_when_review_effort_exceeds_it() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for name in ["core", "api", "cli", "docs", "ui"] {
+        let path = format!("spec/{name}.md");
+        let body = format!("# {name}");
+        let mut args = vec!["spec", "write", "--path", path.as_str(), "--body", body.as_str()];
+        if name == "ui" {
+            args.extend(["--term", "estimate:3"]);
+        }
+        let write = run_foundry(root, &args);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    for from in ["SPC-002", "SPC-003", "SPC-004", "SPC-005"] {
+        let add = run_foundry(
+            root,
+            &[
+                "spec", "link", "add", "--from", from, "--to", "SPC-001", "--type", "depends_on",
+                "--rationale", "built on core",
+            ],
+        );
+        assert!(add.status.success(), "add {from} failed");
+    }
+
+    let unbounded = run_foundry(root, &["spec", "impact", "SPC-001", "--format", "json"]);
+    assert!(unbounded.status.success(), "impact failed");
+    let json: serde_json::Value = serde_json::from_slice(&unbounded.stdout).expect("parse impact");
+    // Four nodes weigh 1 each; SPC-005 carries `estimate:3`.
+    assert_eq!(json["effort"], 7.0);
+    assert_eq!(json["over_budget"], false);
+    assert!(json.get("budget").is_none());
+
+    let over = run_foundry(
+        root,
+        &["spec", "impact", "SPC-001", "--budget", "3", "--format", "json"],
+    );
+    assert_eq!(over.status.code(), Some(1), "sweeping change must fail the budget");
+    let json: serde_json::Value = serde_json::from_slice(&over.stdout).expect("parse impact");
+    assert_eq!(json["budget"], 3.0);
+    assert_eq!(json["over_budget"], true);
+    assert!(
+        String::from_utf8_lossy(&over.stderr)
+            .contains("warning: impact: estimated effort 7 exceeds --budget 3")
+    );
+
+    let table = run_foundry(root, &["spec", "impact", "SPC-001", "--budget", "3"]);
+    assert_eq!(table.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&table.stdout).contains("effort: 7 (budget 3 exceeded)"));
+
+    let within = run_foundry(root, &["spec", "impact", "SPC-001", "--budget", "7"]);
+    assert!(within.status.success(), "effort equal to the budget passes");
+}
+
 #[test]
 fn lint_detects_term_key_drift() {
     let root = tempdir().expect("create temp dir");
@@ -995,7 +2223,7 @@ fn lint_detects_term_key_drift() {
     fs::write(spec_dir.join("a.md"), "# A").expect("write a");
     fs::write(spec_dir.join("b.md"), "# B").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let mut a_meta: serde_json::Value =
@@ -1020,7 +2248,7 @@ fn lint_detects_term_key_drift() {
     )
     .expect("write b");
 
-    let lint = run_foundry(&root, &["spec", "lint"]);
+    let lint = run_foundry(root, &["spec", "lint"]);
     assert!(!lint.status.success(), "lint should fail on term drift");
     assert_eq!(lint.status.code(), Some(1));
     let stdout = String::from_utf8_lossy(&lint.stdout);
@@ -1028,33 +2256,84 @@ fn lint_detects_term_key_drift() {
 }
 
 #[test]
-fn lint_json_format_reports_success() {
+fn lint_detects_duplicate_and_self_edges() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
+    let a_meta_path = spec_dir.join("a.meta.json");
     let mut a_meta: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
-            .expect("parse a");
-    a_meta["type"] = serde_json::Value::String("product_goal".to_string());
+        serde_json::from_str(&fs::read_to_string(&a_meta_path).expect("read a meta"))
+            .expect("parse a meta");
+    let edge = |to: &str| {
+        serde_json::json!({
+            "to": to,
+            "type": "depends_on",
+            "rationale": "hand edited",
+            "confidence": 1.0,
+            "status": "confirmed"
+        })
+    };
+    a_meta["edges"] = serde_json::json!([edge("SPC-002"), edge("SPC-002"), edge("SPC-001")]);
     fs::write(
-        spec_dir.join("a.meta.json"),
-        serde_json::to_string_pretty(&a_meta).expect("serialize a") + "\n",
+        &a_meta_path,
+        serde_json::to_string_pretty(&a_meta).expect("serialize a meta") + "\n",
     )
-    .expect("write a");
-
-    let lint = run_foundry(&root, &["spec", "lint", "--format", "json"]);
-    assert!(lint.status.success(), "lint should pass");
-    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
-    assert_eq!(output["ok"], true);
-    assert_eq!(output["error_count"], 0);
-    assert_eq!(output["errors"], serde_json::json!([]));
-}
+    .expect("write a meta");
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert_eq!(lint.status.code(), Some(1));
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint");
+    let errors = output["errors"]
+        .as_array()
+        .expect("errors should be array")
+        .iter()
+        .filter_map(|e| e.as_str())
+        .collect::<Vec<_>>();
+    assert!(
+        errors.contains(&"duplicate edge in SPC-001: -> SPC-002 (depends_on) x2"),
+        "{errors:?}"
+    );
+    assert!(
+        errors.contains(&"self edge in SPC-001: -> SPC-001 (depends_on)"),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn lint_json_format_reports_success() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let mut a_meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+            .expect("parse a");
+    a_meta["type"] = serde_json::Value::String("product_goal".to_string());
+    fs::write(
+        spec_dir.join("a.meta.json"),
+        serde_json::to_string_pretty(&a_meta).expect("serialize a") + "\n",
+    )
+    .expect("write a");
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert!(lint.status.success(), "lint should pass");
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    assert_eq!(output["ok"], true);
+    assert_eq!(output["error_count"], 0);
+    assert_eq!(output["errors"], serde_json::json!([]));
+}
 
 #[test]
 fn lint_json_format_reports_errors_with_exit_code_one() {
@@ -1065,7 +2344,7 @@ fn lint_json_format_reports_errors_with_exit_code_one() {
     fs::write(spec_dir.join("a.md"), "# A").expect("write a");
     fs::write(spec_dir.join("b.md"), "# B").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let mut a_meta: serde_json::Value =
@@ -1088,7 +2367,7 @@ fn lint_json_format_reports_errors_with_exit_code_one() {
     )
     .expect("write b");
 
-    let lint = run_foundry(&root, &["spec", "lint", "--format", "json"]);
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
     assert_eq!(lint.status.code(), Some(1));
     let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
     assert_eq!(output["ok"], false);
@@ -1097,6 +2376,601 @@ fn lint_json_format_reports_errors_with_exit_code_one() {
     assert!(!errors.is_empty());
 }
 
+#[test]
+fn lint_json_details_point_at_invalid_edge_field() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let meta_path = spec_dir.join("b.meta.json");
+    let mut meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&meta_path).expect("read b")).expect("parse b");
+    meta["edges"] = serde_json::json!([{
+        "to": "SPC-001",
+        "type": "depends_on",
+        "rationale": "b needs a",
+        "confidence": 1.5,
+        "status": "confirmed"
+    }]);
+    fs::write(
+        &meta_path,
+        serde_json::to_string_pretty(&meta).expect("serialize b") + "\n",
+    )
+    .expect("write b");
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert_eq!(lint.status.code(), Some(1));
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    let details = output["details"].as_array().expect("details should be array");
+    let confidence = details
+        .iter()
+        .find(|d| d["pointer"] == "/edges/0/confidence")
+        .expect("confidence detail present");
+    assert!(
+        confidence["path"]
+            .as_str()
+            .is_some_and(|p| p.ends_with("b.meta.json"))
+    );
+    assert!(
+        confidence["message"]
+            .as_str()
+            .is_some_and(|m| m.contains("invalid edge confidence"))
+    );
+}
+
+#[test]
+fn lint_json_details_locate_unparseable_meta_file() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(
+        spec_dir.join("a.meta.json"),
+        "{\n  \"id\": \"SPC-001\",\n  \"type\": oops\n}\n",
+    )
+    .expect("write broken meta");
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert_eq!(lint.status.code(), Some(1));
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    let details = output["details"].as_array().expect("details should be array");
+    let invalid = details
+        .iter()
+        .find(|d| d["rule"] == "invalid-meta")
+        .expect("invalid-meta detail present");
+    assert!(
+        invalid["path"]
+            .as_str()
+            .is_some_and(|p| p.ends_with("a.meta.json"))
+    );
+    assert_eq!(invalid["pointer"], serde_json::Value::Null);
+    assert_eq!(invalid["line"], 3);
+    assert_eq!(invalid["column"], 11);
+}
+
+#[test]
+fn lint_flags_confirmed_edges_without_rationale() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    fs::write(spec_dir.join("c.md"), "# C").expect("write c");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let set_edges = |name: &str, edges: serde_json::Value| {
+        let path = spec_dir.join(name);
+        let mut meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).expect("read meta")).expect("parse");
+        meta["edges"] = edges;
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&meta).expect("serialize") + "\n",
+        )
+        .expect("write meta");
+    };
+    set_edges(
+        "b.meta.json",
+        serde_json::json!([{
+            "to": "SPC-001", "type": "depends_on", "rationale": "  ",
+            "confidence": 1.0, "status": "confirmed"
+        }, {
+            "to": "SPC-003", "type": "refines", "rationale": "",
+            "confidence": 0.6, "status": "proposed"
+        }]),
+    );
+    set_edges(
+        "c.meta.json",
+        serde_json::json!([{
+            "to": "SPC-001", "type": "depends_on",
+            "rationale": "c reads the session tokens issued by a",
+            "confidence": 1.0, "status": "confirmed"
+        }]),
+    );
+
+    let weak = |root: &Path| -> Vec<String> {
+        let lint = run_foundry(
+            root,
+            &[
+                "spec",
+                "lint",
+                "--format",
+                "json",
+                "--only",
+                "weak-rationale",
+            ],
+        );
+        let output: serde_json::Value =
+            serde_json::from_slice(&lint.stdout).expect("parse lint json");
+        output["details"]
+            .as_array()
+            .expect("details array")
+            .iter()
+            .map(|d| {
+                let path = d["path"].as_str().expect("path");
+                format!(
+                    "{}{}",
+                    &path[path.len() - "b.meta.json".len()..],
+                    d["pointer"].as_str().expect("pointer")
+                )
+            })
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(weak(root), vec!["b.meta.json/edges/0/rationale"]);
+
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"lint":{"min_rationale_len":60}}"#,
+    )
+    .expect("write config");
+    assert_eq!(
+        weak(root),
+        vec![
+            "b.meta.json/edges/0/rationale",
+            "c.meta.json/edges/0/rationale"
+        ]
+    );
+}
+
+#[test]
+fn lint_warns_on_tests_edges_from_non_test_spec_nodes() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, node_type) in [
+        ("feature", "feature_requirement"),
+        ("checks", "test_spec"),
+        ("other", "feature_requirement"),
+    ] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {name}"),
+                "--type",
+                node_type,
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    for from in ["SPC-002", "SPC-003"] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                "SPC-001",
+                "--type",
+                "tests",
+                "--rationale",
+                "covers the feature",
+            ],
+        );
+        assert!(link.status.success(), "link add from {from} failed");
+    }
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert_eq!(
+        lint.status.code(),
+        Some(0),
+        "warnings alone must not fail lint"
+    );
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    assert_eq!(output["ok"], true);
+    assert_eq!(output["warning_count"], 1);
+    let details = output["details"].as_array().expect("details array");
+    assert_eq!(details.len(), 1, "{details:?}");
+    assert_eq!(details[0]["rule"], "tests-source");
+    assert_eq!(details[0]["severity"], "warning");
+    assert!(
+        details[0]["path"]
+            .as_str()
+            .is_some_and(|p| p.ends_with("other.meta.json"))
+    );
+
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"lint":{"severity":{"tests-source":"error"}}}"#,
+    )
+    .expect("write config");
+    let lint = run_foundry(root, &["spec", "lint"]);
+    assert_eq!(lint.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&lint.stdout)
+            .contains("lint: error: tests edge from non-test_spec node SPC-003")
+    );
+}
+
+#[test]
+fn lint_orphan_rules_follow_node_type() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, node_type) in [("limits", "constraint"), ("build", "implementation_task")] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {name}"),
+                "--type",
+                node_type,
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let rules = |output: &std::process::Output| {
+        let output: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("parse lint json");
+        output["details"]
+            .as_array()
+            .expect("details array")
+            .iter()
+            .map(|d| {
+                (
+                    d["rule"].as_str().unwrap().to_string(),
+                    d["severity"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert_eq!(lint.status.code(), Some(1));
+    let found = rules(&lint);
+    assert!(
+        found.contains(&("orphan".into(), "error".into())),
+        "{found:?}"
+    );
+    assert!(
+        found.contains(&("orphan-task".into(), "error".into())),
+        "{found:?}"
+    );
+
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"lint":{"orphan_exempt_types":["constraint","implementation_task"],"severity":{"orphan":"warning"}}}"#,
+    )
+    .expect("write config");
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert_eq!(lint.status.code(), Some(1), "an orphan task always errors");
+    assert_eq!(
+        rules(&lint),
+        vec![("orphan-task".to_string(), "error".to_string())]
+    );
+    assert!(String::from_utf8_lossy(&lint.stdout).contains("orphan implementation task: SPC-002"));
+
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"lint":{"orphan_exempt_types":["constraint"]}}"#,
+    )
+    .expect("write config");
+    let lint = run_foundry(root, &["spec", "lint", "--ignore", "orphan-task"]);
+    assert!(
+        lint.status.success(),
+        "exempt constraint should pass: {}",
+        String::from_utf8_lossy(&lint.stdout)
+    );
+}
+
+#[test]
+fn lint_reports_components_disconnected_from_product_goals() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, node_type) in [
+        ("vision", "product_goal"),
+        ("login", "feature_requirement"),
+        ("export", "feature_requirement"),
+        ("export-format", "data_contract"),
+    ] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {name}"),
+                "--type",
+                node_type,
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    for (from, to, edge_type) in [
+        ("SPC-002", "SPC-001", "refines"),
+        ("SPC-004", "SPC-003", "depends_on"),
+    ] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                to,
+                "--type",
+                edge_type,
+                "--rationale",
+                "linked for the lint test",
+            ],
+        );
+        assert!(link.status.success(), "link {from} -> {to} failed");
+    }
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert!(lint.status.success(), "disconnected components only warn");
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    let details = output["details"].as_array().expect("details array");
+    assert_eq!(details.len(), 1, "{details:?}");
+    assert_eq!(details[0]["rule"], "disconnected-component");
+    assert_eq!(details[0]["severity"], "warning");
+    assert_eq!(
+        details[0]["message"],
+        "component of 2 nodes not connected to any product_goal: SPC-003, SPC-004"
+    );
+
+    let lint = run_foundry(
+        root,
+        &["spec", "lint", "--ignore", "disconnected-component"],
+    );
+    assert_eq!(String::from_utf8_lossy(&lint.stdout).trim(), "lint: ok");
+}
+
+#[test]
+fn lint_flags_refines_chains_deeper_than_max_depth() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, node_type) in [
+        ("goal", "product_goal"),
+        ("requirement", "feature_requirement"),
+        ("design", "architecture"),
+        ("sub-design", "component_design"),
+        ("task", "implementation_task"),
+    ] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {name}"),
+                "--type",
+                node_type,
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    for (from, to) in [
+        ("SPC-002", "SPC-001"),
+        ("SPC-003", "SPC-002"),
+        ("SPC-004", "SPC-003"),
+        ("SPC-005", "SPC-004"),
+    ] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                to,
+                "--type",
+                "refines",
+                "--rationale",
+                "refinement step",
+            ],
+        );
+        assert!(link.status.success(), "link {from} -> {to} failed");
+    }
+
+    let lint = run_foundry(root, &["spec", "lint"]);
+    assert_eq!(String::from_utf8_lossy(&lint.stdout).trim(), "lint: ok");
+
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"lint":{"max_refine_depth":3}}"#,
+    )
+    .expect("write config");
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    assert!(lint.status.success(), "deep chains only warn");
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    let details = output["details"].as_array().expect("details array");
+    assert_eq!(details.len(), 1, "{details:?}");
+    assert_eq!(details[0]["rule"], "refine-depth");
+    assert_eq!(details[0]["severity"], "warning");
+    assert_eq!(
+        details[0]["message"],
+        "refines chain of depth 4 exceeds max depth 3: SPC-005 -> SPC-004 -> SPC-003 -> SPC-002 -> SPC-001"
+    );
+
+    let lint = run_foundry(root, &["spec", "lint", "--max-depth", "4"]);
+    assert_eq!(String::from_utf8_lossy(&lint.stdout).trim(), "lint: ok");
+}
+
+#[test]
+fn lint_fix_repairs_stale_hash_and_leaves_edges_alone() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let link = run_foundry(
+        root,
+        &[
+            "spec", "link", "add", "--from", "SPC-002", "--to", "SPC-001", "--type", "depends_on",
+            "--rationale", "b needs a",
+        ],
+    );
+    assert!(link.status.success(), "link add failed");
+    let edges_before = fs::read_to_string(spec_dir.join("b.meta.json")).expect("read b");
+
+    fs::write(spec_dir.join("a.md"), "# A\n\nedited after init").expect("edit a");
+    let stale = run_foundry(root, &["spec", "lint"]);
+    assert_eq!(stale.status.code(), Some(1));
+
+    let fix = run_foundry(root, &["spec", "lint", "--fix"]);
+    assert_eq!(fix.status.code(), Some(0), "fixable issues only");
+    let stdout = String::from_utf8_lossy(&fix.stdout);
+    assert!(stdout.contains("a.meta.json (SPC-001): hash"), "{stdout}");
+    assert!(stdout.contains("lint: ok"), "{stdout}");
+
+    let lint = run_foundry(root, &["spec", "lint"]);
+    assert_eq!(lint.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(spec_dir.join("b.meta.json")).expect("read b"),
+        edges_before
+    );
+}
+
+#[test]
+fn lint_only_selects_named_rules() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    fs::write(spec_dir.join("a.md"), "# A\n\nedited after init").expect("edit a");
+
+    let rules = |args: &[&str]| -> Vec<String> {
+        let mut cmd = vec!["spec", "lint", "--format", "json"];
+        cmd.extend_from_slice(args);
+        let lint = run_foundry(root, &cmd);
+        let output: serde_json::Value =
+            serde_json::from_slice(&lint.stdout).expect("parse lint json");
+        output["details"]
+            .as_array()
+            .expect("details array")
+            .iter()
+            .map(|d| d["rule"].as_str().expect("rule").to_string())
+            .collect()
+    };
+    let all = rules(&[]);
+    assert!(all.iter().any(|r| r == "orphan"), "{all:?}");
+    assert!(all.iter().any(|r| r == "hash-mismatch"), "{all:?}");
+
+    assert_eq!(rules(&["--only", "hash-mismatch"]), vec!["hash-mismatch"]);
+    let ignored = rules(&["--ignore", "orphan,term-drift"]);
+    assert_eq!(ignored, vec!["hash-mismatch"]);
+
+    let ok = run_foundry(root, &["spec", "lint", "--only", "orphan", "--ignore", "orphan"]);
+    assert!(ok.status.success(), "everything filtered out should pass");
+
+    let unknown = run_foundry(root, &["spec", "lint", "--only", "hash-mismatch,bogus"]);
+    assert_eq!(unknown.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&unknown.stderr).contains("unknown lint rule: bogus"));
+}
+
+#[test]
+fn validate_schema_reports_missing_and_unknown_fields() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let clean = run_foundry(root, &["spec", "validate", "schema"]);
+    assert_eq!(clean.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&clean.stdout).contains("validate schema: ok (checked=2)"));
+
+    let edit_meta = |name: &str, edit: &dyn Fn(&mut serde_json::Value)| {
+        let path = spec_dir.join(name);
+        let mut meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).expect("read meta"))
+                .expect("parse meta");
+        edit(&mut meta);
+        fs::write(&path, serde_json::to_string_pretty(&meta).expect("serialize meta"))
+            .expect("write meta");
+    };
+    edit_meta("a.meta.json", &|m| {
+        m.as_object_mut().expect("object").remove("terms");
+    });
+    edit_meta("b.meta.json", &|m| {
+        m["assignee"] = serde_json::Value::String("alice".to_string());
+    });
+
+    let validate = run_foundry(root, &["spec", "validate", "schema", "--format", "json"]);
+    assert_eq!(validate.status.code(), Some(1));
+    let output: serde_json::Value =
+        serde_json::from_slice(&validate.stdout).expect("parse validate output");
+    assert_eq!(output["ok"], false);
+    assert_eq!(output["checked"], 2);
+    let violations = output["violations"].as_array().expect("violations array");
+    assert!(
+        violations.iter().any(|v| v["path"] == "spec/a.meta.json"
+            && v["message"].as_str().unwrap_or("").contains("terms")),
+        "{violations:?}"
+    );
+    assert!(
+        violations.iter().any(|v| v["path"] == "spec/b.meta.json"
+            && v["message"].as_str().unwrap_or("").contains("assignee")),
+        "{violations:?}"
+    );
+}
+
 #[test]
 fn link_propose_creates_proposed_edge() {
     let root = tempdir().expect("create temp dir");
@@ -1106,7 +2980,7 @@ fn link_propose_creates_proposed_edge() {
     fs::write(spec_dir.join("a.md"), "# Account User").expect("write a");
     fs::write(spec_dir.join("b.md"), "# User Profile").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
 
     let mut a_meta: serde_json::Value =
@@ -1130,7 +3004,7 @@ fn link_propose_creates_proposed_edge() {
     .expect("write b");
 
     let propose = run_foundry(
-        &root,
+        root,
         &["spec", "link", "propose", "--node", "SPC-001", "--limit", "1"],
     );
     assert!(propose.status.success(), "propose failed");
@@ -1142,564 +3016,4192 @@ fn link_propose_creates_proposed_edge() {
 }
 
 #[test]
-fn search_index_and_query_json_work() {
+fn link_propose_semantic_mode_finds_related_node_without_shared_terms() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(
         spec_dir.join("a.md"),
-        "# Login Flow\n\nUser logs in with email and password.",
+        "# Ingress\n\nRequests are throttled per client with a token bucket refilled every second.",
     )
     .expect("write a");
     fs::write(
         spec_dir.join("b.md"),
-        "# Billing Flow\n\nUser updates payment method.",
+        "# Quotas\n\nEach client is throttled using a token bucket that refills every second.",
     )
     .expect("write b");
+    fs::write(
+        spec_dir.join("c.md"),
+        "# Invoices\n\nMonthly statements list purchased seats and applied discounts.",
+    )
+    .expect("write c");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
-
-    let index = run_foundry(&root, &["spec", "search", "index"]);
+    let index = run_foundry(root, &["spec", "search", "index"]);
     assert!(index.status.success(), "index failed");
 
-    let query = run_foundry(
-        &root,
+    let term = run_foundry(root, &["spec", "link", "propose", "--node", "SPC-001"]);
+    assert!(term.status.success(), "term propose failed");
+    assert!(String::from_utf8_lossy(&term.stdout).contains("proposed=0"));
+
+    let semantic = run_foundry(
+        root,
         &[
-            "spec",
-            "search",
-            "query",
-            "email password",
-            "--format",
-            "json",
-            "--top-k",
-            "5",
+            "spec", "link", "propose", "--node", "SPC-001", "--using", "semantic", "--type",
+            "depends_on", "--limit", "1",
         ],
     );
-    assert!(query.status.success(), "query failed");
-    let output: serde_json::Value =
-        serde_json::from_slice(&query.stdout).expect("parse query output");
-    let hits = output["hits"].as_array().expect("hits should be array");
-    assert!(!hits.is_empty(), "search should return at least one hit");
-    assert_eq!(hits[0]["id"], "SPC-001");
-}
-
-#[test]
-fn search_doctor_reports_ok_after_index() {
-    let root = tempdir().expect("create temp dir");
-    let root = root.path();
-    let spec_dir = root.join("spec");
-    fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(spec_dir.join("a.md"), "# A\n\ntext").expect("write a");
-    fs::write(spec_dir.join("b.md"), "# B\n\ntext").expect("write b");
-
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
-    assert!(init.status.success(), "init failed");
-    let index = run_foundry(&root, &["spec", "search", "index"]);
-    assert!(index.status.success(), "index failed");
+    assert!(
+        semantic.status.success(),
+        "semantic propose failed: {}",
+        String::from_utf8_lossy(&semantic.stderr)
+    );
 
-    let doctor = run_foundry(&root, &["spec", "search", "doctor"]);
-    assert!(doctor.status.success(), "doctor command failed");
-    let stdout = String::from_utf8_lossy(&doctor.stdout);
-    assert!(stdout.contains("search doctor: ok"), "{stdout}");
+    let a_meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+            .expect("parse a");
+    let edges = a_meta["edges"].as_array().expect("edges array");
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["to"], "SPC-002");
+    assert_eq!(edges[0]["type"], "depends_on");
+    assert_eq!(edges[0]["status"], "proposed");
 }
 
 #[test]
-fn search_hybrid_handles_near_match_query() {
+fn link_propose_rationale_template_names_shared_terms() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
-    let spec_dir = root.join("spec");
-    fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(
-        spec_dir.join("a.md"),
-        "# Authorization Policy\n\nThis spec defines authorization controls.",
-    )
-    .expect("write a");
-    fs::write(
-        spec_dir.join("b.md"),
-        "# Billing Rules\n\nInvoice and payment constraints.",
-    )
-    .expect("write b");
-
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
-    assert!(init.status.success(), "init failed");
-    let index = run_foundry(&root, &["spec", "search", "index"]);
-    assert!(index.status.success(), "index failed");
+    for (name, title, terms) in [
+        (
+            "auth",
+            "Session Login",
+            &["OAuth", "refresh token", "audit"][..],
+        ),
+        ("tokens", "Token Storage", &["oauth", "Refresh Token"][..]),
+    ] {
+        let path = format!("spec/{name}.md");
+        let body = format!("# {title}");
+        let mut args = vec!["spec", "write", "--path", &path, "--body", &body];
+        for term in terms {
+            args.extend(["--term", term]);
+        }
+        let write = run_foundry(root, &args);
+        assert!(write.status.success(), "write {name} failed");
+    }
 
-    let lexical = run_foundry(
-        &root,
+    let propose = run_foundry(
+        root,
         &[
             "spec",
-            "search",
-            "query",
-            "authorisation policy",
-            "--mode",
-            "lexical",
-            "--format",
-            "json",
+            "link",
+            "propose",
+            "--node",
+            "SPC-001",
+            "--rationale-template",
+            "{from} -> {to} share {terms} (score {score})",
         ],
     );
-    assert!(lexical.status.success(), "lexical query failed");
+    assert!(propose.status.success(), "propose failed");
+    let meta: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(root.join("spec/auth.meta.json")).expect("read meta"),
+    )
+    .expect("parse meta");
+    assert_eq!(
+        meta["edges"][0]["rationale"],
+        "SPC-001 -> SPC-002 share OAuth, refresh token (score 4)"
+    );
 
-    let hybrid = run_foundry(
-        &root,
+    let manual = run_foundry(
+        root,
         &[
             "spec",
-            "search",
-            "query",
-            "authorisation policy",
-            "--mode",
-            "hybrid",
-            "--format",
-            "json",
+            "link",
+            "propose",
+            "--from",
+            "SPC-001",
+            "--to",
+            "SPC-002",
+            "--rationale-template",
+            "{terms}",
         ],
     );
-    assert!(hybrid.status.success(), "hybrid query failed");
-    let output: serde_json::Value =
-        serde_json::from_slice(&hybrid.stdout).expect("parse hybrid output");
-    assert_eq!(output["mode"], "hybrid");
-    let hits = output["hits"].as_array().expect("hits array");
-    assert!(!hits.is_empty(), "hybrid should return at least one hit");
-    assert_eq!(hits[0]["id"], "SPC-001");
+    assert_eq!(manual.status.code(), Some(2));
 }
 
 #[test]
-fn search_lexical_boosts_title_match() {
+fn link_source_tags_auto_proposals_for_list_and_prune() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(
         spec_dir.join("a.md"),
-        "# Checkout Flow\n\nSimple checkout process.",
+        "# Ingress\n\nRequests are throttled per client with a token bucket refilled every second.",
     )
     .expect("write a");
     fs::write(
         spec_dir.join("b.md"),
-        "# Payment Domain\n\ncheckout checkout checkout for edge text weight.",
+        "# Quotas\n\nEach client is throttled using a token bucket that refills every second.",
     )
     .expect("write b");
-
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    fs::write(
+        spec_dir.join("c.md"),
+        "# Invoices\n\nMonthly statements list purchased seats and applied discounts.",
+    )
+    .expect("write c");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
-    let index = run_foundry(&root, &["spec", "search", "index"]);
+    let index = run_foundry(root, &["spec", "search", "index"]);
     assert!(index.status.success(), "index failed");
 
-    let query = run_foundry(
-        &root,
+    let add = run_foundry(
+        root,
         &[
             "spec",
-            "search",
-            "query",
-            "checkout flow",
-            "--mode",
-            "lexical",
-            "--format",
-            "json",
-            "--top-k",
-            "2",
+            "link",
+            "add",
+            "--from",
+            "SPC-001",
+            "--to",
+            "SPC-003",
+            "--type",
+            "impacts",
+            "--rationale",
+            "billing counts throttled requests",
         ],
     );
-    assert!(query.status.success(), "query failed");
-    let output: serde_json::Value =
-        serde_json::from_slice(&query.stdout).expect("parse query output");
-    let hits = output["hits"].as_array().expect("hits array");
-    assert_eq!(hits[0]["id"], "SPC-001");
+    assert!(add.status.success(), "link add failed");
+    let semantic = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "propose",
+            "--node",
+            "SPC-001",
+            "--using",
+            "semantic",
+            "--type",
+            "depends_on",
+            "--limit",
+            "1",
+        ],
+    );
+    assert!(semantic.status.success(), "semantic propose failed");
+
+    let list = |source: &str| -> Vec<(String, String)> {
+        let list = run_foundry(
+            root,
+            &[
+                "spec", "link", "list", "--node", "SPC-001", "--source", source, "--format", "json",
+            ],
+        );
+        assert!(list.status.success(), "link list failed");
+        let output: serde_json::Value = serde_json::from_slice(&list.stdout).expect("parse list");
+        output["outgoing"]
+            .as_array()
+            .expect("outgoing")
+            .iter()
+            .map(|e| {
+                (
+                    e["to"].as_str().unwrap().to_string(),
+                    e["source"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    };
+    assert_eq!(
+        list("auto-*"),
+        vec![("SPC-002".to_string(), "auto-semantic".to_string())]
+    );
+    assert_eq!(
+        list("manual"),
+        vec![("SPC-003".to_string(), "manual".to_string())]
+    );
+
+    let prune = run_foundry(
+        root,
+        &["spec", "link", "prune", "--source", "auto-semantic"],
+    );
+    assert!(prune.status.success(), "prune failed");
+    assert!(String::from_utf8_lossy(&prune.stdout).contains("source=auto-semantic"));
+    let a_meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+            .expect("parse a");
+    let edges = a_meta["edges"].as_array().expect("edges array");
+    assert_eq!(edges.len(), 1, "{edges:?}");
+    assert_eq!(edges[0]["to"], "SPC-003");
 }
 
 #[test]
-fn ask_returns_citations_and_evidence() {
+fn search_index_honors_configured_chunk_size() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
+    let body = (1..=12)
+        .map(|n| format!("Paragraph {n} describes one retry rule for the session service."))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    fs::write(spec_dir.join("a.md"), format!("# Retries\n\n{body}")).expect("write a");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let chunk_count = || -> i64 {
+        let conn =
+            rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+        conn.query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))
+            .expect("count chunks")
+    };
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "default index failed");
+    let default_chunks = chunk_count();
+
+    let config = |value: serde_json::Value| {
+        fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+        fs::write(
+            root.join(".foundry/config.json"),
+            serde_json::json!({ "search": value }).to_string(),
+        )
+        .expect("write config");
+    };
+    config(serde_json::json!({ "chunk_target_len": 80, "chunk_overlap": 100 }));
+    let invalid = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(!invalid.status.success(), "overlap >= target should be rejected");
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("search.chunk_overlap"));
+
+    config(serde_json::json!({ "chunk_target_len": 80, "chunk_overlap": 10 }));
     fs::write(
         spec_dir.join("a.md"),
-        "# Auth Flow\n\nAuthentication flow validates user session.",
+        format!("# Retries\n\n{body}\n\nOne more rule."),
     )
-    .expect("write a");
-    fs::write(spec_dir.join("b.md"), "# Billing\n\nPayment update flow.").expect("write b");
-
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
-    assert!(init.status.success(), "init failed");
-    let index = run_foundry(&root, &["spec", "search", "index"]);
-    assert!(index.status.success(), "index failed");
-
-    let ask = run_foundry(
-        &root,
-        &[
-            "spec",
-            "ask",
-            "how does auth flow work?",
-            "--format",
-            "json",
-            "--top-k",
-            "3",
-        ],
-    );
+    .expect("edit a");
+    let sync = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(sync.status.success(), "sync failed");
+    let stale = run_foundry(root, &["spec", "search", "index"]);
+    assert!(stale.status.success(), "incremental index failed");
+    assert!(String::from_utf8_lossy(&stale.stderr).contains("--rebuild"));
+
+    let rebuild = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(rebuild.status.success(), "rebuild failed");
+    assert!(!String::from_utf8_lossy(&rebuild.stderr).contains("warning"));
     assert!(
-        ask.status.success(),
-        "ask failed:\nstdout={}\nstderr={}",
-        String::from_utf8_lossy(&ask.stdout),
-        String::from_utf8_lossy(&ask.stderr)
+        chunk_count() > default_chunks,
+        "expected more than {default_chunks} chunks with a tiny target"
     );
-    let output: serde_json::Value =
-        serde_json::from_slice(&ask.stdout).expect("parse ask output");
-    let obj = output.as_object().expect("ask output object");
-    let expected_keys = [
-        "question",
-        "mode",
-        "answer",
-        "confidence",
-        "citations",
-        "evidence",
-        "explanations",
-        "gaps",
-    ];
-    for key in expected_keys {
-        assert!(obj.contains_key(key), "missing key: {key}");
-    }
-    assert_eq!(obj.len(), 8, "unexpected top-level keys: {:?}", obj.keys());
-    assert!(matches!(output["mode"].as_str(), Some("lexical" | "hybrid")));
-    assert!(output["confidence"].as_f64().is_some());
-    assert!(output["answer"].is_string());
-    assert!(output["citations"].as_array().is_some_and(|a| !a.is_empty()));
-    assert!(output["evidence"].as_array().is_some_and(|a| !a.is_empty()));
-    assert!(output["explanations"].as_array().is_some_and(|a| a.is_empty()));
-    assert!(output["gaps"].as_array().is_some());
 }
 
 #[test]
-fn ask_reports_gap_when_no_hit() {
+fn search_reindex_vectors_refreshes_vectors_only() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(spec_dir.join("a.md"), "# Logging\n\nLog retention settings.")
-        .expect("write markdown");
-
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    fs::write(
+        spec_dir.join("a.md"),
+        "# Logging\n\nServices are logging requests and rotating logs daily.",
+    )
+    .expect("write a");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
-    let index = run_foundry(&root, &["spec", "search", "index"]);
+    let index = run_foundry(root, &["spec", "search", "index"]);
     assert!(index.status.success(), "index failed");
 
-    let ask = run_foundry(
-        &root,
-        &["spec", "ask", "zzzz-no-match-token", "--format", "json"],
+    let snapshot = || {
+        let conn =
+            rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+        let fts = conn
+            .prepare("SELECT chunk_id, text FROM fts_chunks ORDER BY chunk_id")
+            .expect("prepare fts")
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .expect("query fts")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("read fts");
+        let vectors = conn
+            .prepare("SELECT chunk_id, embedding FROM chunk_vectors ORDER BY chunk_id")
+            .expect("prepare vectors")
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?)))
+            .expect("query vectors")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("read vectors");
+        (fts, vectors)
+    };
+    let (fts_before, vectors_before) = snapshot();
+
+    // Turning on stemming changes the local embedding of the same text.
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{ "search": { "stemming": true } }"#,
+    )
+    .expect("write config");
+    let stale = run_foundry(root, &["spec", "search", "doctor", "--format", "json"]);
+    assert_eq!(stale.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&stale.stdout).contains(
+            "ranking token mismatch: index uses stopwords=off,stemming=off, config is stopwords=off,stemming=on"
+        )
+    );
+    let query = run_foundry(
+        root,
+        &["spec", "search", "query", "logging", "--mode", "hybrid", "--format", "json"],
     );
+    assert!(query.status.success(), "hybrid query failed");
+    assert!(String::from_utf8_lossy(&query.stderr).contains("--rebuild"));
+
+    let reindex = run_foundry(root, &["spec", "search", "reindex-vectors"]);
     assert!(
-        ask.status.success(),
-        "ask failed:\nstdout={}\nstderr={}",
-        String::from_utf8_lossy(&ask.stdout),
-        String::from_utf8_lossy(&ask.stderr)
+        reindex.status.success(),
+        "reindex-vectors failed: {}",
+        String::from_utf8_lossy(&reindex.stderr)
     );
-    let output: serde_json::Value =
-        serde_json::from_slice(&ask.stdout).expect("parse ask output");
-    assert_eq!(output["confidence"], 0.0);
-    assert!(output["gaps"].as_array().is_some_and(|a| !a.is_empty()));
+    let stdout = String::from_utf8_lossy(&reindex.stdout);
+    assert!(stdout.contains("chunks=1 models=local-hash-ngrams-v1:1"), "{stdout}");
+
+    let (fts_after, vectors_after) = snapshot();
+    assert_eq!(fts_before, fts_after);
+    assert_eq!(vectors_before.len(), vectors_after.len());
+    assert_eq!(vectors_before[0].0, vectors_after[0].0);
+    assert_ne!(vectors_before[0].1, vectors_after[0].1);
+
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert!(doctor.status.success(), "doctor should pass on a single-model index");
 }
 
 #[test]
-fn ask_includes_neighbor_citations_from_graph_edges() {
+fn search_index_and_query_json_work() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(
         spec_dir.join("a.md"),
-        "# Login Spec\n\nLogin flow with token validation.",
+        "# Login Flow\n\nUser logs in with email and password.",
     )
     .expect("write a");
     fs::write(
         spec_dir.join("b.md"),
-        "# Session Dependency\n\nSession lifecycle requirements.",
+        "# Billing Flow\n\nUser updates payment method.",
     )
     .expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
-    let add = run_foundry(
-        &root,
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = run_foundry(
+        root,
         &[
             "spec",
-            "link",
-            "add",
-            "--from",
-            "SPC-001",
-            "--to",
-            "SPC-002",
-            "--type",
-            "depends_on",
-            "--rationale",
-            "login depends on session",
+            "search",
+            "query",
+            "email password",
+            "--format",
+            "json",
+            "--top-k",
+            "5",
         ],
     );
-    assert!(add.status.success(), "link add failed");
-    let index = run_foundry(&root, &["spec", "search", "index", "--rebuild"]);
-    assert!(index.status.success(), "index failed");
-
-    let ask = run_foundry(
-        &root,
-        &["spec", "ask", "login flow", "--format", "json", "--top-k", "1"],
-    );
-    assert!(
-        ask.status.success(),
-        "ask failed:\nstdout={}\nstderr={}",
-        String::from_utf8_lossy(&ask.stdout),
-        String::from_utf8_lossy(&ask.stderr)
-    );
+    assert!(query.status.success(), "query failed");
     let output: serde_json::Value =
-        serde_json::from_slice(&ask.stdout).expect("parse ask output");
-    let citations = output["citations"].as_array().expect("citations array");
-    let ids = citations
-        .iter()
-        .filter_map(|v| v["id"].as_str().map(ToString::to_string))
-        .collect::<Vec<_>>();
-    assert!(ids.contains(&"SPC-001".to_string()));
-    assert!(ids.contains(&"SPC-002".to_string()));
+        serde_json::from_slice(&query.stdout).expect("parse query output");
+    let hits = output["hits"].as_array().expect("hits should be array");
+    assert!(!hits.is_empty(), "search should return at least one hit");
+    assert_eq!(hits[0]["id"], "SPC-001");
 }
 
 #[test]
-fn ask_explain_returns_reason_entries() {
+fn search_query_ndjson_prints_one_hit_per_line() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(spec_dir.join("a.md"), "# Auth\n\nAuthentication spec flow.").expect("write a");
-    fs::write(spec_dir.join("b.md"), "# Session\n\nSession dependency spec.").expect("write b");
+    fs::write(spec_dir.join("a.md"), "# Login Flow\n\nUser logs in with email.")
+        .expect("write a");
+    fs::write(spec_dir.join("b.md"), "# Signup Flow\n\nUser registers with email.")
+        .expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
-    let add = run_foundry(
-        &root,
-        &[
-            "spec",
-            "link",
-            "add",
-            "--from",
-            "SPC-001",
-            "--to",
-            "SPC-002",
-            "--type",
-            "depends_on",
-            "--rationale",
-            "auth depends on session",
-        ],
-    );
-    assert!(add.status.success(), "link failed");
-    let index = run_foundry(&root, &["spec", "search", "index", "--rebuild"]);
+    let index = run_foundry(root, &["spec", "search", "index"]);
     assert!(index.status.success(), "index failed");
 
-    let ask = run_foundry(
-        &root,
-        &[
-            "spec",
-            "ask",
-            "auth flow",
-            "--format",
-            "json",
-            "--top-k",
-            "1",
-            "--explain",
-        ],
+    let query = run_foundry(
+        root,
+        &["spec", "search", "query", "email", "--format", "ndjson"],
     );
-    assert!(
-        ask.status.success(),
-        "ask failed:\nstdout={}\nstderr={}",
-        String::from_utf8_lossy(&ask.stdout),
-        String::from_utf8_lossy(&ask.stderr)
+    assert!(query.status.success(), "query failed");
+    let stdout = String::from_utf8_lossy(&query.stdout);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    for line in lines {
+        let hit: serde_json::Value = serde_json::from_str(line).expect("parse ndjson line");
+        assert!(hit["id"].as_str().unwrap_or("").starts_with("SPC-"), "{line}");
+        assert!(hit["score"].is_number(), "{line}");
+    }
+}
+
+#[cfg(feature = "http-embeddings")]
+fn spawn_mock_embeddings_server(vector: &'static str) -> String {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+    let addr = listener.local_addr().expect("mock server addr");
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+            let mut content_length = 0usize;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = v.trim().parse().unwrap_or(0);
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            let _ = reader.read_exact(&mut body);
+            let payload = format!(r#"{{"data":[{{"index":0,"embedding":{vector}}}]}}"#);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                payload.len(),
+                payload
+            );
+        }
+    });
+    format!("http://{addr}/v1")
+}
+
+#[cfg(feature = "http-embeddings")]
+#[test]
+fn search_index_uses_http_embedding_provider() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    let url = spawn_mock_embeddings_server("[0.6, 0.8, 0.0]");
+    fs::write(
+        root.join(".foundry/config.json"),
+        serde_json::json!({
+            "embedding": { "provider": "http", "url": url, "model": "mock-embed-1" }
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/a.md", "--body", "# Session\n\nToken rotation."],
     );
-    let output: serde_json::Value =
-        serde_json::from_slice(&ask.stdout).expect("parse ask output");
-    let explanations = output["explanations"]
-        .as_array()
-        .expect("explanations should be array");
-    assert!(!explanations.is_empty());
-    let first = explanations.first().expect("has explanation");
-    assert!(first["id"].is_string());
-    assert!(first["reason"].is_string());
-    let reason_text = explanations
-        .iter()
-        .filter_map(|v| v["reason"].as_str())
-        .collect::<Vec<_>>()
-        .join(" | ");
-    assert!(
-        reason_text.contains("token match"),
-        "expected token match in explanations: {reason_text}"
+    assert!(write.status.success(), "write failed");
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let conn = rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+    let (model, dim): (String, i64) = conn
+        .query_row("SELECT model, dim FROM chunk_vectors LIMIT 1", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .expect("read chunk vector");
+    assert_eq!(model, "mock-embed-1");
+    assert_eq!(dim, 3);
+
+    // The query shares no tokens with the doc; only the provider vector can match it.
+    let query = run_foundry(
+        root,
+        &["spec", "search", "query", "unrelated", "--mode", "hybrid", "--format", "json"],
     );
+    assert!(query.status.success(), "query failed");
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    assert_eq!(output["hits"][0]["id"], "SPC-001");
+
+    fs::write(
+        root.join(".foundry/config.json"),
+        serde_json::json!({
+            "embedding": { "provider": "http", "url": "http://127.0.0.1:9/v1", "model": "mock-embed-1" }
+        })
+        .to_string(),
+    )
+    .expect("write config");
+    let rebuild = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(rebuild.status.success(), "fallback index failed");
+    assert!(String::from_utf8_lossy(&rebuild.stderr).contains("falling back to local embeddings"));
+    let model: String = conn
+        .query_row("SELECT model FROM chunk_vectors LIMIT 1", [], |row| row.get(0))
+        .expect("read chunk vector");
+    assert_eq!(model, "local-hash-ngrams-v1");
+}
+
+#[test]
+fn search_index_since_reindexes_only_changed_files() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let git = |args: &[&str]| {
+        let out = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()
+            .expect("run git");
+        assert!(out.status.success(), "git {args:?} failed");
+    };
+    git(&["init", "-q"]);
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+    for (path, body) in [
+        ("spec/a.md", "# Alpha\n\nOriginal alpha text."),
+        ("spec/b.md", "# Beta\n\nOriginal beta text."),
+    ] {
+        let write = run_foundry(root, &["spec", "write", "--path", path, "--body", body]);
+        assert!(write.status.success(), "write failed for {path}");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    git(&["add", "spec"]);
+    git(&["commit", "-q", "-m", "specs"]);
+
+    // Edit the markdown only; the meta hash is stale, so a plain index would skip it.
+    fs::write(root.join("spec/a.md"), "# Alpha\n\nRewritten with zeppelin.").expect("edit a");
+    let plain = run_foundry(root, &["spec", "search", "index"]);
+    assert!(String::from_utf8_lossy(&plain.stdout).contains("indexed=0 skipped=2"));
+
+    let since = run_foundry(root, &["spec", "search", "index", "--since", "HEAD"]);
+    assert!(since.status.success(), "index --since failed");
     assert!(
-        reason_text.contains("w=") || reason_text.contains("weighted_score="),
-        "expected weighted edge hints in explanations: {reason_text}"
+        String::from_utf8_lossy(&since.stdout).contains("indexed=1 skipped=1 deleted=0"),
+        "unexpected summary: {}",
+        String::from_utf8_lossy(&since.stdout)
     );
+    let query = run_foundry(root, &["spec", "search", "query", "zeppelin", "--format", "json"]);
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    assert_eq!(output["hits"][0]["id"], "SPC-001");
 }
 
 #[test]
-fn plan_ready_reports_ready_and_blocked_tasks() {
+fn search_query_snippet_windows_on_late_match() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+    let filler = "Background prose about unrelated onboarding steps. ".repeat(12);
+    let body = format!("# Long Spec\n\n{filler}Finally the quasar ledger is reconciled.");
+    let write = run_foundry(root, &["spec", "write", "--path", "spec/long.md", "--body", &body]);
+    assert!(write.status.success(), "write failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = run_foundry(root, &["spec", "search", "query", "quasar", "--format", "json"]);
+    assert!(query.status.success(), "query failed");
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    let hit = &output["hits"][0];
+    let snippet = hit["snippet"].as_str().expect("snippet");
+    assert!(snippet.contains("quasar ledger"), "snippet: {snippet}");
+    let range = hit["highlights"][0].as_array().expect("highlight range");
+    let (start, end) = (
+        range[0].as_u64().expect("start") as usize,
+        range[1].as_u64().expect("end") as usize,
+    );
+    assert_eq!(&snippet[start..end], "quasar");
+
+    let table = run_foundry(root, &["spec", "search", "query", "quasar"]);
+    assert!(String::from_utf8_lossy(&table.stdout).contains("**quasar**"));
+}
+
+#[test]
+fn search_query_filters_by_node_type_and_status() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join("spec")).expect("create spec dir");
+
+    for (path, body, node_type, status) in [
+        ("spec/a.md", "# Token API\n\nIssue token for session.", "api_contract", "active"),
+        ("spec/b.md", "# Token Rules\n\nToken expires daily.", "feature_requirement", "active"),
+        ("spec/c.md", "# Token Draft\n\nToken rotation idea.", "api_contract", "draft"),
+    ] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec", "write", "--path", path, "--body", body, "--type", node_type, "--status",
+                status,
+            ],
+        );
+        assert!(write.status.success(), "write failed for {path}");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query_ids = |extra: &[&str]| -> Vec<String> {
+        let mut args = vec!["spec", "search", "query", "token", "--format", "json"];
+        args.extend_from_slice(extra);
+        let query = run_foundry(root, &args);
+        assert!(
+            query.status.success(),
+            "query failed: {}",
+            String::from_utf8_lossy(&query.stderr)
+        );
+        let output: serde_json::Value =
+            serde_json::from_slice(&query.stdout).expect("parse query output");
+        let mut ids = output["hits"]
+            .as_array()
+            .expect("hits should be array")
+            .iter()
+            .map(|h| h["id"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(query_ids(&[]), vec!["SPC-001", "SPC-002", "SPC-003"]);
+    assert_eq!(
+        query_ids(&["--node-type", "api_contract"]),
+        vec!["SPC-001", "SPC-003"]
+    );
+    assert_eq!(
+        query_ids(&["--node-type", "api_contract", "--status", "active"]),
+        vec!["SPC-001"]
+    );
+
+    let write = run_foundry(root, &["spec", "write", "--id", "SPC-003", "--status", "active"]);
+    assert!(write.status.success(), "status update failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "reindex failed");
+    assert_eq!(
+        query_ids(&["--node-type", "api_contract", "--status", "active"]),
+        vec!["SPC-001", "SPC-003"]
+    );
+}
+
+#[test]
+fn search_doctor_reports_ok_after_index() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(spec_dir.join("t1.md"), "# Task 1").expect("write t1");
-    fs::write(spec_dir.join("t2.md"), "# Task 2").expect("write t2");
-    fs::write(spec_dir.join("t3.md"), "# Task 3").expect("write t3");
+    fs::write(spec_dir.join("a.md"), "# A\n\ntext").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B\n\ntext").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
 
-    let mut t1: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("t1.meta.json")).expect("read t1"))
-            .expect("parse t1");
-    let mut t2: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("t2.meta.json")).expect("read t2"))
-            .expect("parse t2");
-    let mut t3: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("t3.meta.json")).expect("read t3"))
-            .expect("parse t3");
-    t1["type"] = serde_json::json!("implementation_task");
-    t2["type"] = serde_json::json!("implementation_task");
-    t3["type"] = serde_json::json!("implementation_task");
-    t1["status"] = serde_json::json!("done");
-    t2["status"] = serde_json::json!("todo");
-    t3["status"] = serde_json::json!("todo");
-    t2["edges"] = serde_json::json!([
-      {
-        "to": "SPC-001",
-        "type": "depends_on",
-        "rationale": "needs task1",
-        "confidence": 1.0,
-        "status": "confirmed"
-      }
-    ]);
-    t3["edges"] = serde_json::json!([
-      {
-        "to": "SPC-002",
-        "type": "depends_on",
-        "rationale": "needs task2",
-        "confidence": 1.0,
-        "status": "confirmed"
-      }
-    ]);
-    fs::write(
-        spec_dir.join("t1.meta.json"),
-        serde_json::to_string_pretty(&t1).expect("serialize t1") + "\n",
-    )
-    .expect("write t1");
-    fs::write(
-        spec_dir.join("t2.meta.json"),
-        serde_json::to_string_pretty(&t2).expect("serialize t2") + "\n",
-    )
-    .expect("write t2");
-    fs::write(
-        spec_dir.join("t3.meta.json"),
-        serde_json::to_string_pretty(&t3).expect("serialize t3") + "\n",
-    )
-    .expect("write t3");
-
-    let out = run_foundry(&root, &["spec", "plan", "ready", "--format", "json"]);
-    assert!(out.status.success(), "plan ready failed");
-    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
-    let ready = json["ready"].as_array().expect("ready array");
-    let blocked = json["blocked"].as_array().expect("blocked array");
-    assert_eq!(ready.len(), 1);
-    assert_eq!(ready[0]["id"], "SPC-002");
-    assert_eq!(ready[0]["path"], "spec/t2.md");
-    assert_eq!(blocked.len(), 1);
-    assert_eq!(blocked[0]["id"], "SPC-003");
-    assert_eq!(blocked[0]["path"], "spec/t3.md");
-    assert_eq!(blocked[0]["blocked_by"][0], "SPC-002");
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert!(doctor.status.success(), "doctor command failed");
+    let stdout = String::from_utf8_lossy(&doctor.stdout);
+    assert!(stdout.contains("search doctor: ok"), "{stdout}");
 }
 
 #[test]
-fn plan_batches_groups_parallel_tasks() {
+fn search_doctor_json_reports_stale_index_with_exit_code_one() {
     let root = tempdir().expect("create temp dir");
     let root = root.path();
     let spec_dir = root.join("spec");
     fs::create_dir_all(&spec_dir).expect("create spec dir");
-    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
-    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
-    fs::write(spec_dir.join("c.md"), "# C").expect("write c");
-    fs::write(spec_dir.join("d.md"), "# D").expect("write d");
+    fs::write(spec_dir.join("a.md"), "# A\n\ntext").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B\n\ntext").expect("write b");
 
-    let init = run_foundry(&root, &["spec", "init", "--sync"]);
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
     assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
 
-    let mut a: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
-            .expect("parse a");
-    let mut b: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("b.meta.json")).expect("read b"))
-            .expect("parse b");
-    let mut c: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("c.meta.json")).expect("read c"))
-            .expect("parse c");
-    let mut d: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(spec_dir.join("d.meta.json")).expect("read d"))
-            .expect("parse d");
-    for meta in [&mut a, &mut b, &mut c, &mut d] {
-        meta["type"] = serde_json::json!("implementation_task");
-        meta["status"] = serde_json::json!("todo");
-    }
-    c["edges"] = serde_json::json!([
-      {
-        "to": "SPC-001",
-        "type": "depends_on",
-        "rationale": "c needs a",
-        "confidence": 1.0,
-        "status": "confirmed"
-      }
-    ]);
-    d["edges"] = serde_json::json!([
-      {
-        "to": "SPC-002",
-        "type": "depends_on",
-        "rationale": "d needs b",
-        "confidence": 1.0,
-        "status": "confirmed"
-      }
-    ]);
+    let clean = run_foundry(root, &["spec", "search", "doctor", "--format", "json"]);
+    assert_eq!(clean.status.code(), Some(0));
+    let clean_json: serde_json::Value =
+        serde_json::from_slice(&clean.stdout).expect("parse clean doctor json");
+    assert_eq!(clean_json["ok"], true);
+    assert_eq!(clean_json["issue_count"], 0);
+
+    fs::write(spec_dir.join("a.md"), "# A\n\nchanged text").expect("rewrite a");
+    let resync = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(resync.status.success(), "re-init failed");
+
+    let stale = run_foundry(root, &["spec", "search", "doctor", "--format", "json"]);
+    assert_eq!(stale.status.code(), Some(1));
+    let stale_json: serde_json::Value =
+        serde_json::from_slice(&stale.stdout).expect("parse stale doctor json");
+    assert_eq!(stale_json["ok"], false);
+    assert_eq!(stale_json["issue_count"], 1);
+    let issues = stale_json["issues"].as_array().expect("issues should be array");
+    assert!(
+        issues[0].as_str().unwrap_or("").contains("hash mismatch in index for SPC-001"),
+        "{issues:?}"
+    );
+}
+
+#[test]
+fn search_hybrid_handles_near_match_query() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
     fs::write(
-        spec_dir.join("a.meta.json"),
-        serde_json::to_string_pretty(&a).expect("serialize a") + "\n",
+        spec_dir.join("a.md"),
+        "# Authorization Policy\n\nThis spec defines authorization controls.",
     )
     .expect("write a");
     fs::write(
-        spec_dir.join("b.meta.json"),
-        serde_json::to_string_pretty(&b).expect("serialize b") + "\n",
+        spec_dir.join("b.md"),
+        "# Billing Rules\n\nInvoice and payment constraints.",
     )
     .expect("write b");
-    fs::write(
-        spec_dir.join("c.meta.json"),
-        serde_json::to_string_pretty(&c).expect("serialize c") + "\n",
-    )
-    .expect("write c");
-    fs::write(
-        spec_dir.join("d.meta.json"),
-        serde_json::to_string_pretty(&d).expect("serialize d") + "\n",
-    )
-    .expect("write d");
 
-    let out = run_foundry(&root, &["spec", "plan", "batches", "--format", "json"]);
-    assert!(out.status.success(), "plan batches failed");
-    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
-    let batches = json["batches"].as_array().expect("batches array");
-    assert_eq!(batches.len(), 2);
-    let b1 = batches[0]["task_ids"].as_array().expect("batch1 ids");
-    let b2 = batches[1]["task_ids"].as_array().expect("batch2 ids");
-    assert_eq!(b1.len(), 2);
-    assert_eq!(b2.len(), 2);
-    let b1_tasks = batches[0]["tasks"].as_array().expect("batch1 tasks");
-    let b2_tasks = batches[1]["tasks"].as_array().expect("batch2 tasks");
-    assert_eq!(b1_tasks.len(), 2);
-    assert_eq!(b2_tasks.len(), 2);
-    assert!(b1_tasks.iter().all(|t| t["path"].is_string()));
-    assert!(b2_tasks.iter().all(|t| t["path"].is_string()));
-    assert!(json["blocked_or_cyclic"]
-        .as_array()
-        .expect("blocked_or_cyclic")
-        .is_empty());
-    assert!(json["blocked_or_cyclic_tasks"]
-        .as_array()
-        .expect("blocked_or_cyclic_tasks")
-        .is_empty());
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let lexical = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "authorisation policy",
+            "--mode",
+            "lexical",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(lexical.status.success(), "lexical query failed");
+
+    let hybrid = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "authorisation policy",
+            "--mode",
+            "hybrid",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(hybrid.status.success(), "hybrid query failed");
+    let output: serde_json::Value =
+        serde_json::from_slice(&hybrid.stdout).expect("parse hybrid output");
+    assert_eq!(output["mode"], "hybrid");
+    let hits = output["hits"].as_array().expect("hits array");
+    assert!(!hits.is_empty(), "hybrid should return at least one hit");
+    assert_eq!(hits[0]["id"], "SPC-001");
+}
+
+#[test]
+fn search_lexical_boosts_title_match() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(
+        spec_dir.join("a.md"),
+        "# Checkout Flow\n\nSimple checkout process.",
+    )
+    .expect("write a");
+    fs::write(
+        spec_dir.join("b.md"),
+        "# Payment Domain\n\ncheckout checkout checkout for edge text weight.",
+    )
+    .expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "checkout flow",
+            "--mode",
+            "lexical",
+            "--format",
+            "json",
+            "--top-k",
+            "2",
+        ],
+    );
+    assert!(query.status.success(), "query failed");
+    let output: serde_json::Value =
+        serde_json::from_slice(&query.stdout).expect("parse query output");
+    let hits = output["hits"].as_array().expect("hits array");
+    assert_eq!(hits[0]["id"], "SPC-001");
+}
+
+#[test]
+fn ask_returns_citations_and_evidence() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(
+        spec_dir.join("a.md"),
+        "# Auth Flow\n\nAuthentication flow validates user session.",
+    )
+    .expect("write a");
+    fs::write(spec_dir.join("b.md"), "# Billing\n\nPayment update flow.").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = run_foundry(
+        root,
+        &[
+            "spec",
+            "ask",
+            "how does auth flow work?",
+            "--format",
+            "json",
+            "--top-k",
+            "3",
+        ],
+    );
+    assert!(
+        ask.status.success(),
+        "ask failed:\nstdout={}\nstderr={}",
+        String::from_utf8_lossy(&ask.stdout),
+        String::from_utf8_lossy(&ask.stderr)
+    );
+    let output: serde_json::Value =
+        serde_json::from_slice(&ask.stdout).expect("parse ask output");
+    let obj = output.as_object().expect("ask output object");
+    let expected_keys = [
+        "question",
+        "mode",
+        "answer",
+        "confidence",
+        "citations",
+        "evidence",
+        "explanations",
+        "gaps",
+    ];
+    for key in expected_keys {
+        assert!(obj.contains_key(key), "missing key: {key}");
+    }
+    assert_eq!(obj.len(), 8, "unexpected top-level keys: {:?}", obj.keys());
+    assert!(matches!(output["mode"].as_str(), Some("lexical" | "hybrid")));
+    assert!(output["confidence"].as_f64().is_some());
+    assert!(output["answer"].is_string());
+    assert!(output["citations"].as_array().is_some_and(|a| !a.is_empty()));
+    assert!(output["evidence"].as_array().is_some_and(|a| !a.is_empty()));
+    assert!(output["explanations"].as_array().is_some_and(|a| a.is_empty()));
+    assert!(output["gaps"].as_array().is_some());
+}
+
+#[test]
+fn ask_max_context_chars_caps_total_evidence_length() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for idx in 0..8 {
+        fs::write(
+            spec_dir.join(format!("cache-{idx}.md")),
+            format!(
+                "# Cache Policy {idx}\n\nCache eviction policy variant {idx} keeps hot entries warm and expires cold cache entries after a configurable window."
+            ),
+        )
+        .expect("write markdown");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = run_foundry(
+        root,
+        &[
+            "spec", "ask", "cache eviction policy", "--format", "json", "--top-k", "8",
+            "--max-context-chars", "250",
+        ],
+    );
+    assert!(ask.status.success(), "ask failed");
+    let output: serde_json::Value = serde_json::from_slice(&ask.stdout).expect("parse ask output");
+    let evidence = output["evidence"].as_array().expect("evidence array");
+    assert!(!evidence.is_empty());
+    let total = evidence
+        .iter()
+        .map(|e| e["snippet"].as_str().expect("snippet").chars().count())
+        .sum::<usize>();
+    assert!(total <= 250, "evidence chars {total} exceed budget");
+    let gaps = output["gaps"].as_array().expect("gaps array");
+    assert!(gaps
+        .iter()
+        .any(|g| g.as_str().unwrap_or_default().contains("max_context_chars=250")));
+}
+
+#[test]
+fn ask_cite_bodies_inlines_full_markdown_of_citations() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let body = "# Cache Policy\n\nCache eviction keeps hot entries warm.\n\n## Expiry\n\nCold entries expire after ten minutes.\n";
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/cache.md", "--body", body],
+    );
+    assert!(write.status.success(), "write failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = |extra: &[&str]| {
+        let mut args = vec!["spec", "ask", "cache eviction"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "ask failed");
+        out
+    };
+    let plain: serde_json::Value =
+        serde_json::from_slice(&ask(&["--format", "json"]).stdout).expect("parse ask output");
+    assert!(plain["citations"][0].get("body").is_none());
+
+    let cited: serde_json::Value =
+        serde_json::from_slice(&ask(&["--format", "json", "--cite-bodies"]).stdout)
+            .expect("parse ask output");
+    assert_eq!(cited["citations"][0]["id"], "SPC-001");
+    assert_eq!(cited["citations"][0]["body"], body);
+
+    let capped = ask(&[
+        "--format",
+        "json",
+        "--cite-bodies",
+        "--max-context-chars",
+        "14",
+    ]);
+    let capped: serde_json::Value =
+        serde_json::from_slice(&capped.stdout).expect("parse ask output");
+    assert_eq!(capped["citations"][0]["body"], "# Cache Policy");
+    assert_eq!(capped["citations"][0]["body_truncated"], true);
+
+    let markdown = ask(&["--format", "markdown", "--cite-bodies"]);
+    let markdown = String::from_utf8_lossy(&markdown.stdout);
+    assert!(markdown.contains("## Cited bodies"), "{markdown}");
+    assert!(markdown.contains("ten minutes."), "{markdown}");
+
+    fs::remove_file(root.join("spec/cache.md")).expect("remove markdown");
+    let missing: serde_json::Value =
+        serde_json::from_slice(&ask(&["--format", "json", "--cite-bodies"]).stdout)
+            .expect("parse ask output");
+    assert_eq!(missing["citations"][0]["body_unavailable"], true);
+    assert!(missing["citations"][0].get("body").is_none());
+}
+
+#[test]
+fn ask_offset_pages_through_citations() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for idx in 0..4 {
+        fs::write(
+            spec_dir.join(format!("cache-{idx}.md")),
+            format!("# Cache Policy {idx}\n\nCache eviction policy variant {idx}."),
+        )
+        .expect("write markdown");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let citation_ids = |extra: &[&str]| -> Vec<String> {
+        let mut args = vec![
+            "spec",
+            "ask",
+            "cache eviction policy",
+            "--format",
+            "json",
+            "--mode",
+            "lexical",
+        ];
+        args.extend_from_slice(extra);
+        let ask = run_foundry(root, &args);
+        assert!(ask.status.success(), "ask failed");
+        let output: serde_json::Value =
+            serde_json::from_slice(&ask.stdout).expect("parse ask output");
+        output["citations"]
+            .as_array()
+            .expect("citations array")
+            .iter()
+            .map(|c| c["id"].as_str().expect("id").to_string())
+            .collect()
+    };
+    let all = citation_ids(&["--top-k", "4"]);
+    assert_eq!(all.len(), 4);
+    let first_page = citation_ids(&["--top-k", "2"]);
+    let second_page = citation_ids(&["--top-k", "2", "--offset", "2"]);
+    assert_eq!(first_page, all[..2]);
+    assert_eq!(second_page, all[2..]);
+
+    let past_end = citation_ids(&["--top-k", "2", "--offset", "10"]);
+    assert!(past_end.is_empty());
+}
+
+#[test]
+fn ask_reports_gap_when_no_hit() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# Logging\n\nLog retention settings.")
+        .expect("write markdown");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = run_foundry(
+        root,
+        &["spec", "ask", "zzzz-no-match-token", "--format", "json"],
+    );
+    assert!(
+        ask.status.success(),
+        "ask failed:\nstdout={}\nstderr={}",
+        String::from_utf8_lossy(&ask.stdout),
+        String::from_utf8_lossy(&ask.stderr)
+    );
+    let output: serde_json::Value =
+        serde_json::from_slice(&ask.stdout).expect("parse ask output");
+    assert_eq!(output["confidence"], 0.0);
+    assert!(output["gaps"].as_array().is_some_and(|a| !a.is_empty()));
+}
+
+#[test]
+fn ask_includes_neighbor_citations_from_graph_edges() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(
+        spec_dir.join("a.md"),
+        "# Login Spec\n\nLogin flow with token validation.",
+    )
+    .expect("write a");
+    fs::write(
+        spec_dir.join("b.md"),
+        "# Session Dependency\n\nSession lifecycle requirements.",
+    )
+    .expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let add = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "add",
+            "--from",
+            "SPC-001",
+            "--to",
+            "SPC-002",
+            "--type",
+            "depends_on",
+            "--rationale",
+            "login depends on session",
+        ],
+    );
+    assert!(add.status.success(), "link add failed");
+    let index = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = run_foundry(
+        root,
+        &["spec", "ask", "login flow", "--format", "json", "--top-k", "1"],
+    );
+    assert!(
+        ask.status.success(),
+        "ask failed:\nstdout={}\nstderr={}",
+        String::from_utf8_lossy(&ask.stdout),
+        String::from_utf8_lossy(&ask.stderr)
+    );
+    let output: serde_json::Value =
+        serde_json::from_slice(&ask.stdout).expect("parse ask output");
+    let citations = output["citations"].as_array().expect("citations array");
+    let ids = citations
+        .iter()
+        .filter_map(|v| v["id"].as_str().map(ToString::to_string))
+        .collect::<Vec<_>>();
+    assert!(ids.contains(&"SPC-001".to_string()));
+    assert!(ids.contains(&"SPC-002".to_string()));
+}
+
+#[test]
+fn ask_no_graph_cites_only_retrieval_hits() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(
+        spec_dir.join("a.md"),
+        "# Login Spec\n\nLogin flow with token validation.",
+    )
+    .expect("write a");
+    fs::write(
+        spec_dir.join("b.md"),
+        "# Session Dependency\n\nSession lifecycle requirements.",
+    )
+    .expect("write b");
+    fs::write(spec_dir.join("c.md"), "# Legacy Auth\n\nOld password rules.").expect("write c");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    for (to, edge_type) in [("SPC-002", "depends_on"), ("SPC-003", "conflicts_with")] {
+        let add = run_foundry(
+            root,
+            &[
+                "spec", "link", "add", "--from", "SPC-001", "--to", to, "--type", edge_type,
+                "--rationale", "related to login",
+            ],
+        );
+        assert!(add.status.success(), "link add {to} failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = |extra: &[&str]| -> serde_json::Value {
+        let mut args = vec!["spec", "ask", "login flow", "--format", "json", "--top-k", "1"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "ask {extra:?} failed");
+        serde_json::from_slice(&out.stdout).expect("parse ask output")
+    };
+    let ids = |output: &serde_json::Value, key: &str| {
+        output[key]
+            .as_array()
+            .expect("array")
+            .iter()
+            .map(|v| v["id"].as_str().expect("id").to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let with_graph = ask(&[]);
+    assert!(ids(&with_graph, "citations").contains(&"SPC-002".to_string()));
+
+    let no_graph = ask(&["--no-graph", "--explain"]);
+    assert_eq!(ids(&no_graph, "citations"), vec!["SPC-001"]);
+    assert_eq!(ids(&no_graph, "evidence"), vec!["SPC-001"]);
+    assert_eq!(ids(&no_graph, "explanations"), vec!["SPC-001"]);
+    let answer = no_graph["answer"].as_str().expect("answer");
+    assert!(!answer.contains("SPC-003"), "{answer}");
+    assert!(
+        no_graph["confidence"].as_f64().expect("confidence")
+            > with_graph["confidence"].as_f64().expect("confidence"),
+        "conflict risk should no longer lower confidence"
+    );
+}
+
+#[test]
+fn ask_explain_returns_reason_entries() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# Auth\n\nAuthentication spec flow.").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# Session\n\nSession dependency spec.").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let add = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "add",
+            "--from",
+            "SPC-001",
+            "--to",
+            "SPC-002",
+            "--type",
+            "depends_on",
+            "--rationale",
+            "auth depends on session",
+        ],
+    );
+    assert!(add.status.success(), "link failed");
+    let index = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = run_foundry(
+        root,
+        &[
+            "spec",
+            "ask",
+            "auth flow",
+            "--format",
+            "json",
+            "--top-k",
+            "1",
+            "--explain",
+        ],
+    );
+    assert!(
+        ask.status.success(),
+        "ask failed:\nstdout={}\nstderr={}",
+        String::from_utf8_lossy(&ask.stdout),
+        String::from_utf8_lossy(&ask.stderr)
+    );
+    let output: serde_json::Value =
+        serde_json::from_slice(&ask.stdout).expect("parse ask output");
+    let explanations = output["explanations"]
+        .as_array()
+        .expect("explanations should be array");
+    assert!(!explanations.is_empty());
+    let first = explanations.first().expect("has explanation");
+    assert!(first["id"].is_string());
+    assert!(first["reason"].is_string());
+    let reason_text = explanations
+        .iter()
+        .filter_map(|v| v["reason"].as_str())
+        .collect::<Vec<_>>()
+        .join(" | ");
+    assert!(
+        reason_text.contains("token match"),
+        "expected token match in explanations: {reason_text}"
+    );
+    assert!(
+        reason_text.contains("w=") || reason_text.contains("weighted_score="),
+        "expected weighted edge hints in explanations: {reason_text}"
+    );
+}
+
+#[test]
+fn plan_ready_reports_ready_and_blocked_tasks() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("t1.md"), "# Task 1").expect("write t1");
+    fs::write(spec_dir.join("t2.md"), "# Task 2").expect("write t2");
+    fs::write(spec_dir.join("t3.md"), "# Task 3").expect("write t3");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let mut t1: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("t1.meta.json")).expect("read t1"))
+            .expect("parse t1");
+    let mut t2: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("t2.meta.json")).expect("read t2"))
+            .expect("parse t2");
+    let mut t3: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("t3.meta.json")).expect("read t3"))
+            .expect("parse t3");
+    t1["type"] = serde_json::json!("implementation_task");
+    t2["type"] = serde_json::json!("implementation_task");
+    t3["type"] = serde_json::json!("implementation_task");
+    t1["status"] = serde_json::json!("done");
+    t2["status"] = serde_json::json!("todo");
+    t3["status"] = serde_json::json!("todo");
+    t2["edges"] = serde_json::json!([
+      {
+        "to": "SPC-001",
+        "type": "depends_on",
+        "rationale": "needs task1",
+        "confidence": 1.0,
+        "status": "confirmed"
+      }
+    ]);
+    t3["edges"] = serde_json::json!([
+      {
+        "to": "SPC-002",
+        "type": "depends_on",
+        "rationale": "needs task2",
+        "confidence": 1.0,
+        "status": "confirmed"
+      }
+    ]);
+    fs::write(
+        spec_dir.join("t1.meta.json"),
+        serde_json::to_string_pretty(&t1).expect("serialize t1") + "\n",
+    )
+    .expect("write t1");
+    fs::write(
+        spec_dir.join("t2.meta.json"),
+        serde_json::to_string_pretty(&t2).expect("serialize t2") + "\n",
+    )
+    .expect("write t2");
+    fs::write(
+        spec_dir.join("t3.meta.json"),
+        serde_json::to_string_pretty(&t3).expect("serialize t3") + "\n",
+    )
+    .expect("write t3");
+
+    let out = run_foundry(root, &["spec", "plan", "ready", "--format", "json"]);
+    assert!(out.status.success(), "plan ready failed");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let ready = json["ready"].as_array().expect("ready array");
+    let blocked = json["blocked"].as_array().expect("blocked array");
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0]["id"], "SPC-002");
+    assert_eq!(ready[0]["path"], "spec/t2.md");
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0]["id"], "SPC-003");
+    assert_eq!(blocked[0]["path"], "spec/t3.md");
+    assert_eq!(blocked[0]["blocked_by"][0], "SPC-002");
+}
+
+#[test]
+fn plan_assign_persists_owner_and_filters_ready_tasks() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, title) in [("t1", "Task 1"), ("t2", "Task 2"), ("goal", "Goal")] {
+        let node_type = if name == "goal" {
+            "product_goal"
+        } else {
+            "implementation_task"
+        };
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {title}"),
+                "--type",
+                node_type,
+                "--status",
+                "todo",
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+
+    let assign = run_foundry(
+        root,
+        &[
+            "spec", "plan", "assign", "--id", "SPC-001", "--owner", "alice",
+        ],
+    );
+    assert!(assign.status.success(), "assign failed");
+    assert!(String::from_utf8_lossy(&assign.stdout).contains("plan assign: SPC-001 owner=alice"));
+    let assign = run_foundry(
+        root,
+        &[
+            "spec", "plan", "assign", "--id", "SPC-002", "--owner", "bob",
+        ],
+    );
+    assert!(assign.status.success(), "assign failed");
+    let goal = run_foundry(
+        root,
+        &[
+            "spec", "plan", "assign", "--id", "SPC-003", "--owner", "alice",
+        ],
+    );
+    assert_eq!(
+        goal.status.code(),
+        Some(2),
+        "non-task nodes cannot be assigned"
+    );
+
+    // Owner survives body edits picked up by init and metadata updates by write.
+    fs::write(root.join("spec/t1.md"), "# Task 1\n\nmore detail").expect("edit t1");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/t1.md", "--status", "doing"],
+    );
+    assert!(write.status.success(), "write failed");
+    let meta: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(root.join("spec/t1.meta.json")).expect("read t1 meta"),
+    )
+    .expect("parse t1 meta");
+    assert_eq!(meta["owner"], "alice");
+    assert_eq!(meta["status"], "doing");
+
+    let out = run_foundry(
+        root,
+        &[
+            "spec", "plan", "ready", "--owner", "alice", "--format", "json",
+        ],
+    );
+    assert!(out.status.success(), "plan ready failed");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let ready = json["ready"].as_array().expect("ready array");
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0]["id"], "SPC-001");
+    assert_eq!(ready[0]["owner"], "alice");
+
+    let out = run_foundry(root, &["spec", "plan", "ready", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let owners = json["ready"]
+        .as_array()
+        .expect("ready array")
+        .iter()
+        .map(|t| t["owner"].as_str().expect("owner"))
+        .collect::<Vec<_>>();
+    assert_eq!(owners, vec!["alice", "bob"]);
+}
+
+#[test]
+fn plan_ready_orders_ready_tasks_by_priority() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, priority) in [("t1", "low"), ("t2", "critical"), ("t3", "")] {
+        let mut args = vec![
+            "spec".to_string(),
+            "write".into(),
+            "--path".into(),
+            format!("spec/{name}.md"),
+            "--body".into(),
+            format!("# Task {name}"),
+            "--type".into(),
+            "implementation_task".into(),
+            "--status".into(),
+            "todo".into(),
+        ];
+        if !priority.is_empty() {
+            args.extend(["--priority".into(), priority.into()]);
+        }
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let write = run_foundry(root, &args);
+        assert!(write.status.success(), "write {name} failed");
+    }
+
+    let ready = run_foundry(root, &["spec", "plan", "ready", "--format", "json"]);
+    assert!(ready.status.success(), "plan ready failed");
+    let json: serde_json::Value = serde_json::from_slice(&ready.stdout).expect("parse ready json");
+    let order = json["ready"]
+        .as_array()
+        .expect("ready array")
+        .iter()
+        .map(|t| {
+            (
+                t["id"].as_str().expect("id"),
+                t["priority"].as_str().expect("priority"),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        order,
+        vec![("SPC-002", "critical"), ("SPC-001", "low"), ("SPC-003", "")]
+    );
+
+    let invalid = run_foundry(
+        root,
+        &["spec", "write", "--id", "SPC-003", "--priority", "urgent"],
+    );
+    assert_eq!(invalid.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("invalid node priority: urgent"));
+}
+
+#[test]
+fn plan_batches_groups_parallel_tasks() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    fs::write(spec_dir.join("c.md"), "# C").expect("write c");
+    fs::write(spec_dir.join("d.md"), "# D").expect("write d");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    let mut a: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("a.meta.json")).expect("read a"))
+            .expect("parse a");
+    let mut b: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("b.meta.json")).expect("read b"))
+            .expect("parse b");
+    let mut c: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("c.meta.json")).expect("read c"))
+            .expect("parse c");
+    let mut d: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("d.meta.json")).expect("read d"))
+            .expect("parse d");
+    for meta in [&mut a, &mut b, &mut c, &mut d] {
+        meta["type"] = serde_json::json!("implementation_task");
+        meta["status"] = serde_json::json!("todo");
+    }
+    c["edges"] = serde_json::json!([
+      {
+        "to": "SPC-001",
+        "type": "depends_on",
+        "rationale": "c needs a",
+        "confidence": 1.0,
+        "status": "confirmed"
+      }
+    ]);
+    d["edges"] = serde_json::json!([
+      {
+        "to": "SPC-002",
+        "type": "depends_on",
+        "rationale": "d needs b",
+        "confidence": 1.0,
+        "status": "confirmed"
+      }
+    ]);
+    fs::write(
+        spec_dir.join("a.meta.json"),
+        serde_json::to_string_pretty(&a).expect("serialize a") + "\n",
+    )
+    .expect("write a");
+    fs::write(
+        spec_dir.join("b.meta.json"),
+        serde_json::to_string_pretty(&b).expect("serialize b") + "\n",
+    )
+    .expect("write b");
+    fs::write(
+        spec_dir.join("c.meta.json"),
+        serde_json::to_string_pretty(&c).expect("serialize c") + "\n",
+    )
+    .expect("write c");
+    fs::write(
+        spec_dir.join("d.meta.json"),
+        serde_json::to_string_pretty(&d).expect("serialize d") + "\n",
+    )
+    .expect("write d");
+
+    let out = run_foundry(root, &["spec", "plan", "batches", "--format", "json"]);
+    assert!(out.status.success(), "plan batches failed");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let batches = json["batches"].as_array().expect("batches array");
+    assert_eq!(batches.len(), 2);
+    let b1 = batches[0]["task_ids"].as_array().expect("batch1 ids");
+    let b2 = batches[1]["task_ids"].as_array().expect("batch2 ids");
+    assert_eq!(b1.len(), 2);
+    assert_eq!(b2.len(), 2);
+    let b1_tasks = batches[0]["tasks"].as_array().expect("batch1 tasks");
+    let b2_tasks = batches[1]["tasks"].as_array().expect("batch2 tasks");
+    assert_eq!(b1_tasks.len(), 2);
+    assert_eq!(b2_tasks.len(), 2);
+    assert!(b1_tasks.iter().all(|t| t["path"].is_string()));
+    assert!(b2_tasks.iter().all(|t| t["path"].is_string()));
+    assert!(json["blocked_or_cyclic"]
+        .as_array()
+        .expect("blocked_or_cyclic")
+        .is_empty());
+    assert!(json["blocked_or_cyclic_tasks"]
+        .as_array()
+        .expect("blocked_or_cyclic_tasks")
+        .is_empty());
+}
+
+#[test]
+fn plan_batches_max_parallel_spills_and_owner_filters() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for idx in 1..=5 {
+        let path = format!("spec/t{idx}.md");
+        let body = format!("# Task {idx}");
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &path,
+                "--body",
+                &body,
+                "--type",
+                "implementation_task",
+                "--status",
+                "todo",
+            ],
+        );
+        assert!(write.status.success(), "write task {idx} failed");
+    }
+    let batch_ids = |extra: &[&str]| -> Vec<Vec<String>> {
+        let mut args = vec!["spec", "plan", "batches", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "plan batches failed");
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+        json["batches"]
+            .as_array()
+            .expect("batches array")
+            .iter()
+            .map(|b| {
+                b["task_ids"]
+                    .as_array()
+                    .expect("task ids")
+                    .iter()
+                    .map(|id| id.as_str().unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    };
+    assert_eq!(batch_ids(&[]).len(), 1);
+    assert_eq!(
+        batch_ids(&["--max-parallel", "2"]),
+        vec![
+            vec!["SPC-001", "SPC-002"],
+            vec!["SPC-003", "SPC-004"],
+            vec!["SPC-005"],
+        ]
+    );
+
+    for id in ["SPC-002", "SPC-004"] {
+        let assign = run_foundry(
+            root,
+            &["spec", "plan", "assign", "--id", id, "--owner", "alice"],
+        );
+        assert!(assign.status.success(), "assign {id} failed");
+    }
+    assert_eq!(
+        batch_ids(&["--owner", "alice"]),
+        vec![vec!["SPC-002", "SPC-004"]]
+    );
+}
+
+#[test]
+fn plan_critical_path_follows_longer_diamond_branch() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["a", "b", "c", "d"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    // a <- b (estimate 3) <- d, a <- c <- d
+    let edges = [
+        ("a", vec![], vec![]),
+        ("b", vec!["SPC-001"], vec!["estimate:3"]),
+        ("c", vec!["SPC-001"], vec![]),
+        ("d", vec!["SPC-002", "SPC-003"], vec![]),
+    ];
+    for (name, deps, terms) in edges {
+        let path = spec_dir.join(format!("{name}.meta.json"));
+        let mut meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).expect("read meta"))
+                .expect("parse meta");
+        meta["type"] = serde_json::json!("implementation_task");
+        meta["status"] = serde_json::json!("todo");
+        meta["terms"] = serde_json::json!(terms);
+        meta["edges"] = serde_json::Value::Array(
+            deps.iter()
+                .map(|to| {
+                    serde_json::json!({
+                        "to": to,
+                        "type": "depends_on",
+                        "rationale": "ordered work",
+                        "confidence": 1.0,
+                        "status": "confirmed"
+                    })
+                })
+                .collect(),
+        );
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&meta).expect("serialize meta") + "\n",
+        )
+        .expect("write meta");
+    }
+
+    let out = run_foundry(root, &["spec", "plan", "critical-path", "--format", "json"]);
+    assert!(out.status.success(), "plan critical-path failed");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let ids = json["path"]
+        .as_array()
+        .expect("path array")
+        .iter()
+        .map(|step| step["id"].as_str().expect("id").to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["SPC-001", "SPC-002", "SPC-004"]);
+    assert_eq!(json["length"], 5.0);
+    assert!(json["cyclic"].as_array().expect("cyclic").is_empty());
+
+    // Closing a cycle must be reported instead of looping.
+    let a_path = spec_dir.join("a.meta.json");
+    let mut a: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&a_path).expect("read a")).expect("parse a");
+    a["edges"] = serde_json::json!([
+      {
+        "to": "SPC-004",
+        "type": "depends_on",
+        "rationale": "cycle",
+        "confidence": 1.0,
+        "status": "confirmed"
+      }
+    ]);
+    fs::write(
+        &a_path,
+        serde_json::to_string_pretty(&a).expect("serialize a") + "\n",
+    )
+    .expect("write a");
+    let out = run_foundry(root, &["spec", "plan", "critical-path", "--format", "json"]);
+    assert_eq!(out.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    assert_eq!(json["cyclic"].as_array().expect("cyclic").len(), 4);
+}
+
+#[test]
+fn node_show_json_aggregates_meta_body_edges_and_impact() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A\n\nline two\nline three\n").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let link = run_foundry(
+        root,
+        &[
+            "spec", "link", "add", "--from", "SPC-002", "--to", "SPC-001", "--type", "depends_on",
+            "--rationale", "b builds on a",
+        ],
+    );
+    assert!(link.status.success(), "link add failed");
+    fs::write(spec_dir.join("a.md"), "# A\n\nline two\nedited\n").expect("edit a");
+
+    let show = run_foundry(
+        root,
+        &["spec", "node", "show", "SPC-001", "--body-lines", "2", "--format", "json"],
+    );
+    assert!(show.status.success(), "node show failed");
+    let output: serde_json::Value = serde_json::from_slice(&show.stdout).expect("parse json");
+    for key in ["meta", "body_preview", "edges", "impact"] {
+        assert!(output.get(key).is_some(), "missing key {key}: {output}");
+    }
+    assert_eq!(output["meta"]["id"], "SPC-001");
+    assert_eq!(output["body_preview"], serde_json::json!(["# A", ""]));
+    assert_eq!(output["body_line_count"], 4);
+    assert_eq!(output["edges"]["incoming"][0]["from"], "SPC-002");
+    assert_eq!(output["impact"]["depth"], 1);
+    assert_eq!(output["impact"]["reverse_dependents"], serde_json::json!(["SPC-002"]));
+    let rules = output["lint_errors"]
+        .as_array()
+        .expect("lint_errors array")
+        .iter()
+        .map(|e| e["rule"].as_str().expect("rule"))
+        .collect::<Vec<_>>();
+    assert_eq!(rules, vec!["hash-mismatch"]);
+
+    let missing = run_foundry(root, &["spec", "node", "show", "SPC-999"]);
+    assert_eq!(missing.status.code(), Some(2));
+}
+
+#[test]
+fn node_show_lint_errors_follow_recorded_node_ids() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("b.md"), "# B").expect("write b");
+    fs::write(spec_dir.join("c.md"), "# C").expect("write c");
+
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let meta_path = spec_dir.join("b.meta.json");
+    let mut b: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&meta_path).expect("read b")).expect("parse b");
+    let edge = serde_json::json!({
+        "to": "SPC-001",
+        "type": "depends_on",
+        "rationale": "b builds on a, unlike SPC-003",
+        "confidence": 1.0,
+        "status": "confirmed"
+    });
+    b["edges"] = serde_json::json!([edge.clone(), edge]);
+    fs::write(
+        &meta_path,
+        serde_json::to_string_pretty(&b).expect("serialize b") + "\n",
+    )
+    .expect("write b");
+
+    let rules_for = |id: &str| {
+        let show = run_foundry(root, &["spec", "node", "show", id, "--format", "json"]);
+        assert!(show.status.success(), "node show {id} failed");
+        let output: serde_json::Value = serde_json::from_slice(&show.stdout).expect("parse json");
+        output["lint_errors"]
+            .as_array()
+            .expect("lint_errors array")
+            .iter()
+            .map(|e| e["rule"].as_str().expect("rule").to_string())
+            .collect::<Vec<_>>()
+    };
+    // The duplicate-edge message names SPC-001 as its target, but the
+    // finding belongs to SPC-002 only.
+    assert!(rules_for("SPC-001").is_empty());
+    assert_eq!(rules_for("SPC-002"), vec!["duplicate-edge"]);
+    assert_eq!(rules_for("SPC-003"), vec!["orphan"]);
+
+    let lint = run_foundry(root, &["spec", "lint", "--format", "json"]);
+    let output: serde_json::Value = serde_json::from_slice(&lint.stdout).expect("parse lint json");
+    let orphan = output["details"]
+        .as_array()
+        .expect("details array")
+        .iter()
+        .find(|d| d["rule"] == "orphan")
+        .expect("orphan detail present");
+    assert_eq!(orphan["nodes"], serde_json::json!(["SPC-003"]));
+}
+
+#[test]
+fn migrate_backfills_schema_version_and_is_idempotent() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(spec_dir.join("sub")).expect("create spec dir");
+    fs::write(spec_dir.join("a.md"), "# A").expect("write a");
+    fs::write(spec_dir.join("sub/b.md"), "# B").expect("write b");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    // Rewrite b as a pre-versioning file with a Windows-style path.
+    let b_path = spec_dir.join("sub/b.meta.json");
+    let mut legacy: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&b_path).expect("read b")).expect("parse b");
+    let legacy = legacy.as_object_mut().expect("object");
+    assert_eq!(legacy.remove("schema_version"), Some(serde_json::json!(1)));
+    legacy.insert("body_md_path".to_string(), serde_json::json!("spec\\sub\\b.md"));
+    fs::write(&b_path, serde_json::to_string_pretty(&legacy).expect("serialize b"))
+        .expect("write b");
+
+    let migrate = run_foundry(root, &["spec", "migrate"]);
+    assert!(migrate.status.success(), "migrate failed");
+    let stdout = String::from_utf8_lossy(&migrate.stdout);
+    assert!(stdout.contains("migrate summary: migrated=1 unchanged=1"), "{stdout}");
+    let migrated: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&b_path).expect("read b")).expect("parse b");
+    assert_eq!(migrated["schema_version"], 1);
+    assert_eq!(migrated["body_md_path"], "spec/sub/b.md");
+
+    let again = run_foundry(root, &["spec", "migrate"]);
+    assert!(again.status.success(), "second migrate failed");
+    assert!(
+        String::from_utf8_lossy(&again.stdout).contains("migrate summary: migrated=0 unchanged=2")
+    );
+}
+
+#[test]
+fn hybrid_query_matches_between_parallel_and_sequential_execution() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(
+        spec_dir.join("auth.md"),
+        "# Auth Session\n\nSession tokens are rotated after login.",
+    )
+    .expect("write auth");
+    fs::write(
+        spec_dir.join("billing.md"),
+        "# Billing\n\nInvoices are issued monthly for each account.",
+    )
+    .expect("write billing");
+    fs::write(
+        spec_dir.join("audit.md"),
+        "# Audit Log\n\nLogin events and token rotation are recorded.",
+    )
+    .expect("write audit");
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = |parallel: bool| {
+        fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+        fs::write(
+            root.join(".foundry/config.json"),
+            format!(r#"{{"search":{{"parallel_hybrid":{parallel}}}}}"#),
+        )
+        .expect("write config");
+        let out = run_foundry(
+            root,
+            &[
+                "spec", "search", "query", "session token rotation", "--mode", "hybrid",
+                "--format", "json",
+            ],
+        );
+        assert!(out.status.success(), "hybrid query failed");
+        String::from_utf8(out.stdout).expect("utf8 output")
+    };
+    let sequential = query(false);
+    let parallel = query(true);
+    assert!(sequential.contains("SPC-"), "expected hits: {sequential}");
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn impact_reverse_via_follows_impacts_edges_only_when_requested() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["core", "consumer", "reporter"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    for (from, edge_type) in [("SPC-002", "depends_on"), ("SPC-003", "impacts")] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec", "link", "add", "--from", from, "--to", "SPC-001", "--type", edge_type,
+                "--rationale", "touches core",
+            ],
+        );
+        assert!(link.status.success(), "link add from {from} failed");
+    }
+
+    let reverse = |extra: &[&str]| -> serde_json::Value {
+        let mut args = vec!["spec", "impact", "SPC-001", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "impact failed");
+        let output: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+        output["reverse_dependents"].clone()
+    };
+    assert_eq!(reverse(&[]), serde_json::json!(["SPC-002"]));
+    assert_eq!(
+        reverse(&["--reverse-via", "impacts"]),
+        serde_json::json!(["SPC-002", "SPC-003"])
+    );
+
+    let bad = run_foundry(root, &["spec", "impact", "SPC-001", "--reverse-via", "blocks"]);
+    assert_eq!(bad.status.code(), Some(2));
+}
+
+#[test]
+fn impact_direction_limits_report_to_one_side() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["base", "mid", "top"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    for (from, to) in [("SPC-002", "SPC-001"), ("SPC-003", "SPC-002")] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                to,
+                "--type",
+                "depends_on",
+                "--rationale",
+                "builds on it",
+            ],
+        );
+        assert!(link.status.success(), "link add from {from} failed");
+    }
+
+    let impact = |direction: &str| -> serde_json::Value {
+        let out = run_foundry(
+            root,
+            &[
+                "spec",
+                "impact",
+                "SPC-002",
+                "--direction",
+                direction,
+                "--format",
+                "json",
+            ],
+        );
+        assert!(
+            out.status.success(),
+            "impact --direction {direction} failed"
+        );
+        serde_json::from_slice(&out.stdout).expect("parse json")
+    };
+    let reverse = impact("reverse");
+    assert_eq!(reverse["direct_dependencies"], serde_json::json!([]));
+    assert_eq!(
+        reverse["reverse_dependents"],
+        serde_json::json!(["SPC-003"])
+    );
+    assert_eq!(
+        reverse["recommended_review_order"],
+        serde_json::json!(["SPC-002", "SPC-003"])
+    );
+
+    let forward = impact("forward");
+    assert_eq!(forward["direct_dependencies"][0]["to"], "SPC-001");
+    assert_eq!(forward["reverse_dependents"], serde_json::json!([]));
+    assert_eq!(
+        forward["recommended_review_order"],
+        serde_json::json!(["SPC-002", "SPC-001"])
+    );
+
+    let both = impact("both");
+    assert_eq!(both["reverse_dependents"], serde_json::json!(["SPC-003"]));
+    assert_eq!(
+        both["recommended_review_order"]
+            .as_array()
+            .expect("review order")
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn plan_ready_csv_escapes_fields_and_joins_blockers() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, title) in [("t1", "Parse \"quoted\", input"), ("t2", "Task 2"), ("t3", "Task 3")] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {title}"),
+                "--type",
+                "implementation_task",
+                "--status",
+                "todo",
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    for to in ["SPC-001", "SPC-002"] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec", "link", "add", "--from", "SPC-003", "--to", to, "--type", "depends_on",
+                "--rationale", "needs upstream work",
+            ],
+        );
+        assert!(link.status.success(), "link add to {to} failed");
+    }
+    let assign = run_foundry(
+        root,
+        &["spec", "plan", "assign", "--id", "SPC-002", "--owner", "bob"],
+    );
+    assert!(assign.status.success(), "assign failed");
+
+    let out = run_foundry(root, &["spec", "plan", "ready", "--format", "csv"]);
+    assert!(out.status.success(), "plan ready csv failed");
+    assert!(out.stdout.starts_with(b"id,title,status,owner,blocked_by\r\n"));
+    let mut reader = csv::Reader::from_reader(out.stdout.as_slice());
+    let headers = reader.headers().expect("read csv header").clone();
+    assert_eq!(
+        headers.iter().collect::<Vec<_>>(),
+        ["id", "title", "status", "owner", "blocked_by"]
+    );
+    let rows = reader
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("parse csv rows");
+    assert_eq!(rows.len(), 3);
+    assert_eq!(&rows[0][1], "Parse \"quoted\", input");
+    assert_eq!(&rows[1][3], "bob");
+    assert_eq!(&rows[2][0], "SPC-003");
+    assert_eq!(&rows[2][4], "SPC-001;SPC-002");
+}
+
+#[test]
+fn plan_ready_and_batches_ignore_proposed_dependencies_unless_included() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for name in ["t1", "t2"] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# Task {name}"),
+                "--type",
+                "implementation_task",
+                "--status",
+                "todo",
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let link = run_foundry(
+        root,
+        &[
+            "spec", "link", "add", "--from", "SPC-002", "--to", "SPC-001", "--type", "depends_on",
+            "--rationale", "maybe needs task 1",
+        ],
+    );
+    assert!(link.status.success(), "link add failed");
+    let meta_path = root.join("spec/t2.meta.json");
+    let mut t2: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&meta_path).expect("read t2")).expect("parse t2");
+    t2["edges"][0]["status"] = serde_json::json!("proposed");
+    fs::write(
+        &meta_path,
+        serde_json::to_string_pretty(&t2).expect("serialize t2") + "\n",
+    )
+    .expect("write t2");
+
+    let out = run_foundry(root, &["spec", "plan", "ready", "--format", "json"]);
+    assert!(out.status.success(), "plan ready failed");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let ready = json["ready"]
+        .as_array()
+        .expect("ready array")
+        .iter()
+        .map(|t| t["id"].as_str().expect("id"))
+        .collect::<Vec<_>>();
+    assert_eq!(ready, vec!["SPC-001", "SPC-002"]);
+    assert!(json["blocked"].as_array().expect("blocked array").is_empty());
+
+    let out = run_foundry(
+        root,
+        &["spec", "plan", "ready", "--include-proposed", "--format", "json"],
+    );
+    assert!(out.status.success(), "plan ready --include-proposed failed");
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+    let ready = json["ready"].as_array().expect("ready array");
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0]["id"], "SPC-001");
+    let blocked = json["blocked"].as_array().expect("blocked array");
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0]["id"], "SPC-002");
+    assert_eq!(blocked[0]["blocked_by"], serde_json::json!(["SPC-001"]));
+
+    let batch_ids = |extra: &[&str]| {
+        let mut args = vec!["spec", "plan", "batches", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "plan batches {extra:?} failed");
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse output");
+        json["batches"]
+            .as_array()
+            .expect("batches array")
+            .iter()
+            .map(|b| b["task_ids"].clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(batch_ids(&[]), vec![serde_json::json!(["SPC-001", "SPC-002"])]);
+    assert_eq!(
+        batch_ids(&["--include-proposed"]),
+        vec![serde_json::json!(["SPC-001"]), serde_json::json!(["SPC-002"])]
+    );
+}
+
+#[test]
+fn term_rename_collapses_drifted_variants_so_lint_passes() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let nodes: [(&str, &[&str]); 3] = [
+        ("a", &["User_ID", "Order Total"]),
+        ("b", &["user-id", "order_total"]),
+        ("c", &["order_total", "userId"]),
+    ];
+    for (name, terms) in nodes {
+        let mut args = vec![
+            "spec".to_string(),
+            "write".to_string(),
+            "--path".to_string(),
+            format!("spec/{name}.md"),
+            "--body".to_string(),
+            format!("# {name}"),
+            "--type".to_string(),
+            "product_goal".to_string(),
+        ];
+        for term in terms {
+            args.extend(["--term".to_string(), term.to_string()]);
+        }
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let write = run_foundry(root, &args);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let lint = run_foundry(root, &["spec", "lint"]);
+    assert_eq!(lint.status.code(), Some(1), "drift should fail lint");
+
+    let rename = run_foundry(
+        root,
+        &[
+            "spec", "term", "rename", "--from", "User_ID", "--to", "user_id",
+        ],
+    );
+    assert!(rename.status.success(), "rename failed");
+    let stdout = String::from_utf8_lossy(&rename.stdout);
+    assert!(
+        stdout.contains("term rename summary: key=userid canonical=user_id updated=3"),
+        "{stdout}"
+    );
+
+    let canonicalize = run_foundry(
+        root,
+        &["spec", "term", "rename", "--canonicalize", "order total"],
+    );
+    assert!(canonicalize.status.success(), "canonicalize failed");
+    assert!(
+        String::from_utf8_lossy(&canonicalize.stdout)
+            .contains("term rename summary: key=ordertotal canonical=order_total updated=1")
+    );
+
+    let terms = |name: &str| -> serde_json::Value {
+        let meta: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(root.join(format!("spec/{name}.meta.json"))).expect("read meta"),
+        )
+        .expect("parse meta");
+        meta["terms"].clone()
+    };
+    assert_eq!(terms("a"), serde_json::json!(["user_id", "order_total"]));
+    assert_eq!(terms("c"), serde_json::json!(["order_total", "user_id"]));
+
+    let lint = run_foundry(root, &["spec", "lint"]);
+    assert!(
+        lint.status.success(),
+        "{}",
+        String::from_utf8_lossy(&lint.stdout)
+    );
+}
+
+#[test]
+fn search_query_snippet_comes_from_best_matching_chunk() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        serde_json::json!({ "search": { "chunk_target_len": 80, "chunk_overlap": 10 } })
+            .to_string(),
+    )
+    .expect("write config");
+    let body = [
+        "# Deep Spec",
+        "Intro paragraph describing the onboarding checklist for new staff.",
+        "Second paragraph covers payroll schedules and holiday calendars.",
+        "Third paragraph explains how the zephyr cache warms at startup.",
+    ]
+    .join("\n\n");
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/deep.md", "--body", &body],
+    );
+    assert!(write.status.success(), "write failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let conn = rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+    let chunks: i64 = conn
+        .query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))
+        .expect("count chunks");
+    assert!(
+        chunks >= 3,
+        "expected one chunk per paragraph, got {chunks}"
+    );
+
+    for mode in ["lexical", "hybrid"] {
+        let query = run_foundry(
+            root,
+            &[
+                "spec", "search", "query", "zephyr", "--mode", mode, "--format", "json",
+            ],
+        );
+        assert!(query.status.success(), "{mode} query failed");
+        let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+        let snippet = output["hits"][0]["snippet"].as_str().expect("snippet");
+        assert!(
+            snippet.contains("zephyr cache"),
+            "{mode} snippet: {snippet}"
+        );
+        assert!(!snippet.contains("onboarding"), "{mode} snippet: {snippet}");
+    }
+}
+
+#[test]
+fn search_query_limits_candidates_per_node_not_per_chunk() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        serde_json::json!({ "search": { "chunk_target_len": 40, "chunk_overlap": 5 } })
+            .to_string(),
+    )
+    .expect("write config");
+    // Twenty short chunks that each match strongly, more than the
+    // `top_k * 8` rows the per-chunk query used to fetch for `--top-k 2`.
+    let flood = (1..=20)
+        .map(|n| format!("Zephyr zephyr zephyr rule {n}."))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let sparse = "A long paragraph about billing exports that mentions zephyr only once.";
+    for (name, title, body) in [("flood", "Flood", flood.as_str()), ("sparse", "Sparse", sparse)] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {title}\n\n{body}"),
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let conn = rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+    let flood_chunks: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM chunks WHERE node_id = 'SPC-001'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("count chunks");
+    assert!(flood_chunks > 16, "expected many flood chunks, got {flood_chunks}");
+
+    let query = run_foundry(
+        root,
+        &[
+            "spec", "search", "query", "zephyr", "--top-k", "2", "--format", "json",
+        ],
+    );
+    assert!(query.status.success(), "query failed");
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    let ids = output["hits"]
+        .as_array()
+        .expect("hits array")
+        .iter()
+        .map(|hit| hit["id"].as_str().expect("id"))
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["SPC-001", "SPC-002"]);
+    assert!(
+        output["hits"][1]["snippet"]
+            .as_str()
+            .expect("snippet")
+            .contains("mentions zephyr"),
+        "{output}"
+    );
+}
+
+#[test]
+fn search_index_include_title_terms_matches_declared_terms() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let nodes = [
+        ("release", "# Release Checklist\n\nSteps for shipping builds.", Some("kubernetes")),
+        ("cluster", "# Cluster Ops\n\nKubernetes upgrades happen monthly.", None),
+    ];
+    for (name, body, term) in nodes {
+        let path = format!("spec/{name}.md");
+        let mut args = vec!["spec", "write", "--path", path.as_str(), "--body", body];
+        if let Some(term) = term {
+            args.extend(["--term", term]);
+        }
+        let write = run_foundry(root, &args);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let query = || -> serde_json::Value {
+        let out = run_foundry(
+            root,
+            &["spec", "search", "query", "kubernetes", "--format", "json"],
+        );
+        assert!(out.status.success(), "query failed");
+        serde_json::from_slice(&out.stdout).expect("parse query")
+    };
+    let hit_ids = |output: &serde_json::Value| {
+        output["hits"]
+            .as_array()
+            .expect("hits array")
+            .iter()
+            .map(|hit| hit["id"].as_str().expect("id").to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    assert_eq!(hit_ids(&query()), vec!["SPC-002"]);
+
+    let index = run_foundry(
+        root,
+        &["spec", "search", "index", "--include-title-terms", "--rebuild"],
+    );
+    assert!(index.status.success(), "index --include-title-terms failed");
+    let output = query();
+    let mut ids = hit_ids(&output);
+    ids.sort();
+    assert_eq!(ids, vec!["SPC-001", "SPC-002"]);
+    for hit in output["hits"].as_array().expect("hits array") {
+        assert!(hit.get("fuzzy").is_none(), "{hit}");
+        let snippet = hit["snippet"].as_str().expect("snippet");
+        if hit["id"] == "SPC-002" {
+            assert!(snippet.contains("upgrades happen monthly"), "{snippet}");
+        } else {
+            assert!(snippet.contains("kubernetes"), "{snippet}");
+        }
+    }
+
+    // Later incremental runs keep the synthetic chunk for re-indexed nodes.
+    fs::write(
+        root.join("spec/release.md"),
+        "# Release Checklist\n\nSteps for shipping signed builds.",
+    )
+    .expect("edit release");
+    let sync = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(sync.status.success(), "sync failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "incremental index failed");
+    assert!(hit_ids(&query()).contains(&"SPC-001".to_string()));
+}
+
+#[test]
+fn search_query_context_returns_neighboring_chunks() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        serde_json::json!({ "search": { "chunk_target_len": 80, "chunk_overlap": 10 } })
+            .to_string(),
+    )
+    .expect("write config");
+    let body = [
+        "# Deep Spec",
+        "Intro paragraph describing the onboarding checklist for new staff.",
+        "Second paragraph covers payroll schedules and holiday calendars.",
+        "Third paragraph explains how the zephyr cache warms at startup.",
+    ]
+    .join("\n\n");
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/deep.md", "--body", &body],
+    );
+    assert!(write.status.success(), "write failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let plain = run_foundry(
+        root,
+        &["spec", "search", "query", "payroll", "--format", "json"],
+    );
+    let output: serde_json::Value = serde_json::from_slice(&plain.stdout).expect("parse query");
+    assert!(output["hits"][0].get("context").is_none());
+
+    let query = run_foundry(
+        root,
+        &[
+            "spec", "search", "query", "payroll", "--context", "1", "--format", "json",
+        ],
+    );
+    assert!(query.status.success(), "context query failed");
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    let context = output["hits"][0]["context"].as_array().expect("context");
+    assert_eq!(context.len(), 3, "{context:?}");
+    let text = |idx: usize| context[idx]["text"].as_str().expect("text");
+    assert!(text(0).contains("onboarding"), "{context:?}");
+    assert!(text(1).contains("payroll"), "{context:?}");
+    assert_eq!(context[1]["matched"], true);
+    assert!(text(2).contains("zephyr"), "{context:?}");
+}
+
+#[test]
+fn search_query_normalize_rescales_scores_within_results() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, body) in [
+        (
+            "cache",
+            "# Cache Eviction\n\nCache eviction policy for the cache layer.",
+        ),
+        (
+            "policy",
+            "# Retention Policy\n\nRetention policy mentions cache once.",
+        ),
+        ("notes", "# Notes\n\nMisc notes about a cache."),
+    ] {
+        let path = format!("spec/{name}.md");
+        let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", body]);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let hits = |extra: &[&str]| -> Vec<serde_json::Value> {
+        let mut args = vec!["spec", "search", "query", "cache", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "query failed");
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse query");
+        json["hits"].as_array().expect("hits").clone()
+    };
+    assert!(hits(&[])[0].get("normalized_score").is_none());
+
+    let minmax = hits(&["--normalize", "minmax"]);
+    assert_eq!(minmax.len(), 3);
+    let normalized = minmax
+        .iter()
+        .map(|h| h["normalized_score"].as_f64().expect("normalized_score"))
+        .collect::<Vec<_>>();
+    assert!(
+        normalized.iter().all(|v| (0.0..=1.0).contains(v)),
+        "{normalized:?}"
+    );
+    assert_eq!(normalized[0], 1.0);
+    assert_eq!(normalized[2], 0.0);
+    assert!(minmax[0]["score"].as_f64().expect("raw score") > 1.0);
+
+    let softmax = hits(&["--normalize", "softmax"]);
+    let normalized = softmax
+        .iter()
+        .map(|h| h["normalized_score"].as_f64().expect("normalized_score"))
+        .collect::<Vec<_>>();
+    assert!(
+        normalized.iter().all(|v| (0.0..=1.0).contains(v)),
+        "{normalized:?}"
+    );
+    assert!((normalized.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn search_query_rerank_reorders_lexical_hits_by_similarity() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, body) in [
+        (
+            "lifecycle",
+            "# Session token\n\nThe session token lifecycle spans login audit billing export \
+             webhook retention quota pagination caching throttling migration rollout.",
+        ),
+        ("notes", "# Notes\n\nsession token"),
+    ] {
+        let path = format!("spec/{name}.md");
+        let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", body]);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = |mode: &str| -> serde_json::Value {
+        let out = run_foundry(
+            root,
+            &[
+                "spec",
+                "search",
+                "query",
+                "session token",
+                "--mode",
+                mode,
+                "--explain",
+                "--format",
+                "json",
+            ],
+        );
+        assert!(out.status.success(), "{mode} query failed");
+        serde_json::from_slice(&out.stdout).expect("parse query")
+    };
+    let ids = |output: &serde_json::Value| {
+        output["hits"]
+            .as_array()
+            .expect("hits")
+            .iter()
+            .map(|h| h["id"].as_str().expect("id").to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let lexical = query("lexical");
+    assert_eq!(ids(&lexical), vec!["SPC-001", "SPC-002"]);
+
+    let rerank = query("rerank");
+    assert_eq!(rerank["mode"], "rerank");
+    assert_eq!(ids(&rerank), vec!["SPC-002", "SPC-001"]);
+    let hits = rerank["hits"].as_array().expect("hits");
+    assert!(hits[0]["score"].as_f64() > hits[1]["score"].as_f64());
+    assert_eq!(hits[0]["explanation"]["rerank"]["lexical_rank"], 2);
+    assert_eq!(hits[1]["explanation"]["rerank"]["lexical_rank"], 1);
+}
+
+#[test]
+fn search_query_field_restricts_matching_to_titles_or_terms() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, body, terms) in [
+        (
+            "gateway",
+            "# Payment Gateway\n\nRoutes card charges.",
+            &[][..],
+        ),
+        (
+            "refunds",
+            "# Refunds\n\nRefunds go back through the payment gateway.",
+            &[][..],
+        ),
+        (
+            "ledger",
+            "# Ledger\n\nDouble entry books.",
+            &["gateway"][..],
+        ),
+    ] {
+        let path = format!("spec/{name}.md");
+        let mut args = vec!["spec", "write", "--path", &path, "--body", body];
+        for term in terms {
+            args.extend(["--term", term]);
+        }
+        let write = run_foundry(root, &args);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = |field: &str| -> Vec<serde_json::Value> {
+        let out = run_foundry(
+            root,
+            &[
+                "spec",
+                "search",
+                "query",
+                "payment gateway",
+                "--field",
+                field,
+                "--format",
+                "json",
+            ],
+        );
+        assert!(
+            out.status.success(),
+            "{field} query failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let output: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse query");
+        output["hits"].as_array().expect("hits").clone()
+    };
+    let ids = |hits: &[serde_json::Value]| {
+        hits.iter()
+            .map(|h| h["id"].as_str().expect("id").to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids(&query("body")), vec!["SPC-001", "SPC-002"]);
+    let title = query("title");
+    assert_eq!(ids(&title), vec!["SPC-001"], "body-only match is excluded");
+    assert_eq!(title[0]["snippet"], "Payment Gateway");
+    assert_eq!(title[0]["highlights"], serde_json::json!([[0, 7], [8, 15]]));
+    let terms = query("terms");
+    assert_eq!(ids(&terms), vec!["SPC-003"]);
+    assert_eq!(terms[0]["snippet"], "gateway");
+
+    let hybrid = run_foundry(
+        root,
+        &[
+            "spec", "search", "query", "payment", "--field", "title", "--mode", "hybrid",
+        ],
+    );
+    assert_eq!(hybrid.status.code(), Some(2));
+}
+
+#[test]
+fn search_query_modified_after_skips_nodes_indexed_before_cutoff() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = |name: &str| {
+        let path = format!("spec/{name}.md");
+        let body = format!("# {name}\n\nsession token rotation");
+        let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", &body]);
+        assert!(write.status.success(), "write {name} failed");
+        let index = run_foundry(root, &["spec", "search", "index"]);
+        assert!(index.status.success(), "index failed");
+    };
+    let query = |extra: &[&str]| -> Vec<(String, i64)> {
+        let mut args = vec!["spec", "search", "query", "session token", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(
+            out.status.success(),
+            "query failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let output: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse query");
+        let mut hits = output["hits"]
+            .as_array()
+            .expect("hits")
+            .iter()
+            .map(|h| {
+                let id = h["id"].as_str().expect("id").to_string();
+                (id, h["updated_at"].as_i64().expect("updated_at"))
+            })
+            .collect::<Vec<_>>();
+        hits.sort();
+        hits
+    };
+
+    write("old");
+    let old_ts = query(&[])[0].1;
+    // `updated_at` has one-second resolution.
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    write("new");
+    let hits = query(&[]);
+    assert_eq!(hits.len(), 2);
+    assert_eq!(
+        hits[0],
+        ("SPC-001".to_string(), old_ts),
+        "unchanged node keeps its time"
+    );
+    assert!(hits[1].1 > old_ts);
+
+    let cutoff = old_ts.to_string();
+    let recent = query(&["--modified-after", &cutoff]);
+    assert_eq!(recent, vec![hits[1].clone()]);
+    assert_eq!(query(&["--since", "2001-09-09T01:46:40Z"]).len(), 2);
+    assert!(query(&["--modified-after", "9999-12-31T23:59:59+01:00"]).is_empty());
+
+    let bad = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "session",
+            "--modified-after",
+            "yesterday",
+        ],
+    );
+    assert_eq!(bad.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("invalid --modified-after: yesterday"));
+}
+
+#[test]
+fn ask_markdown_fences_each_evidence_item_and_links_citations() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for idx in 0..3 {
+        fs::write(
+            spec_dir.join(format!("cache-{idx}.md")),
+            format!("# Cache Policy {idx}\n\nCache eviction policy variant {idx}."),
+        )
+        .expect("write markdown");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let ask = |format: &str| {
+        let out = run_foundry(
+            root,
+            &[
+                "spec",
+                "ask",
+                "cache eviction policy",
+                "--mode",
+                "lexical",
+                "--format",
+                format,
+            ],
+        );
+        assert!(out.status.success(), "ask --format {format} failed");
+        out.stdout
+    };
+    let json: serde_json::Value = serde_json::from_slice(&ask("json")).expect("parse ask output");
+    let markdown = String::from_utf8(ask("markdown")).expect("utf8 markdown");
+
+    let evidence = json["evidence"].as_array().expect("evidence array");
+    assert!(!evidence.is_empty());
+    assert_eq!(
+        markdown
+            .lines()
+            .filter(|l| l.starts_with("```text id="))
+            .count(),
+        evidence.len(),
+        "{markdown}"
+    );
+    for e in evidence {
+        let id = e["id"].as_str().expect("evidence id");
+        assert!(
+            markdown.contains(&format!("```text id={id} ")),
+            "{markdown}"
+        );
+    }
+    for c in json["citations"].as_array().expect("citations array") {
+        let link = format!(
+            "- [{}: {}]({})",
+            c["id"].as_str().expect("id"),
+            c["title"].as_str().expect("title"),
+            c["path"].as_str().expect("path")
+        );
+        assert!(markdown.contains(&link), "missing {link} in {markdown}");
+    }
+    assert!(markdown.starts_with("## Question\n\ncache eviction policy\n"));
+    let context = markdown
+        .split("<!-- BEGIN CONTEXT -->")
+        .nth(1)
+        .expect("context section");
+    assert!(context.contains("<!-- END CONTEXT -->"));
+}
+
+#[test]
+fn link_update_preserves_created_at_and_bumps_updated_at() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["a", "b", "c"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let add = |to: &str, rationale: &str| {
+        let out = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                "SPC-001",
+                "--to",
+                to,
+                "--type",
+                "depends_on",
+                "--rationale",
+                rationale,
+            ],
+        );
+        assert!(out.status.success(), "link add failed");
+    };
+    add("SPC-002", "first draft");
+    add("SPC-003", "newer link");
+
+    // Backdate the first edge so the update is observably newer.
+    let meta_path = spec_dir.join("a.meta.json");
+    let mut meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&meta_path).expect("read meta")).expect("parse");
+    meta["edges"][0]["created_at"] = serde_json::json!(1_000);
+    meta["edges"][0]["updated_at"] = serde_json::json!(1_000);
+    fs::write(
+        &meta_path,
+        serde_json::to_string_pretty(&meta).expect("serialize") + "\n",
+    )
+    .expect("write meta");
+    add("SPC-002", "revised rationale");
+
+    let list = run_foundry(
+        root,
+        &[
+            "spec", "link", "list", "--node", "SPC-001", "--sort", "age", "--format", "json",
+        ],
+    );
+    assert!(list.status.success(), "link list failed");
+    let output: serde_json::Value = serde_json::from_slice(&list.stdout).expect("parse list");
+    let edge = &output["outgoing"][0];
+    assert_eq!(edge["to"], "SPC-002");
+    assert_eq!(edge["rationale"], "revised rationale");
+    assert_eq!(edge["created_at"], 1_000);
+    assert!(edge["updated_at"].as_i64().expect("updated_at") > 1_000);
+    assert!(
+        output["outgoing"][1]["created_at"]
+            .as_i64()
+            .expect("created_at")
+            > 1_000
+    );
+
+    let incoming = run_foundry(
+        root,
+        &[
+            "spec", "link", "list", "--node", "SPC-002", "--format", "json",
+        ],
+    );
+    let incoming: serde_json::Value =
+        serde_json::from_slice(&incoming.stdout).expect("parse incoming");
+    assert_eq!(incoming["incoming"][0]["from"], "SPC-001");
+    assert_eq!(incoming["incoming"][0]["created_at"], 1_000);
+
+    let validate = run_foundry(root, &["spec", "validate", "schema"]);
+    assert!(
+        validate.status.success(),
+        "timestamps must pass the meta schema"
+    );
+}
+
+#[test]
+fn link_list_all_dumps_every_edge_sorted() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["a", "b", "c"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+
+    for (from, to, edge_type) in [
+        ("SPC-003", "SPC-001", "refines"),
+        ("SPC-001", "SPC-003", "impacts"),
+        ("SPC-001", "SPC-002", "depends_on"),
+    ] {
+        let add = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                to,
+                "--type",
+                edge_type,
+                "--rationale",
+                "dump fixture",
+            ],
+        );
+        assert!(add.status.success(), "add {from} -> {to} failed");
+    }
+
+    let list = run_foundry(root, &["spec", "link", "list", "--all", "--format", "json"]);
+    assert!(list.status.success(), "link list --all failed");
+    let rows: serde_json::Value = serde_json::from_slice(&list.stdout).expect("parse dump");
+    let rows = rows.as_array().expect("dump is an array");
+    let edges = rows
+        .iter()
+        .map(|row| {
+            (
+                row["from"].as_str().expect("from"),
+                row["to"].as_str().expect("to"),
+                row["type"].as_str().expect("type"),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edges,
+        vec![
+            ("SPC-001", "SPC-002", "depends_on"),
+            ("SPC-001", "SPC-003", "impacts"),
+            ("SPC-003", "SPC-001", "refines"),
+        ]
+    );
+    assert_eq!(rows[0]["status"], "confirmed");
+    assert_eq!(rows[0]["confidence"], 1.0);
+    assert_eq!(rows[0]["rationale"], "dump fixture");
+
+    let both = run_foundry(
+        root,
+        &["spec", "link", "list", "--all", "--node", "SPC-001"],
+    );
+    assert!(!both.status.success(), "--all must conflict with --node");
+}
+
+#[test]
+fn doctor_aggregates_lint_search_and_agent_checks() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    fs::write(spec_dir.join("goal.md"), "# Goal\n\ncontent").expect("write markdown");
+    let init = run_foundry(
+        root,
+        &[
+            "spec",
+            "init",
+            "--sync",
+            "--template-source",
+            "local",
+            "--agent",
+            "codex",
+        ],
+    );
+    assert!(init.status.success(), "init failed");
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/goal.md",
+            "--type",
+            "product_goal",
+        ],
+    );
+    assert!(write.status.success(), "write failed");
+
+    let doctor = |extra: &[&str]| {
+        let mut args = vec![
+            "spec",
+            "doctor",
+            "--template-source",
+            "local",
+            "--agent",
+            "codex",
+            "--format",
+            "json",
+        ];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        let output: serde_json::Value =
+            serde_json::from_slice(&out.stdout).expect("parse doctor output");
+        (out.status.code(), output)
+    };
+
+    // No search index yet: only the search section fails.
+    let (code, output) = doctor(&[]);
+    assert_eq!(code, Some(1));
+    assert_eq!(output["ok"], false);
+    assert_eq!(output["lint"]["ok"], true);
+    assert_eq!(output["agent"]["ok"], true);
+    assert_eq!(output["search"]["ok"], false);
+    assert_eq!(
+        output["search"]["issues"],
+        serde_json::json!(["missing indexed node: SPC-001"])
+    );
+    assert_eq!(output["search"]["issue_count"], 1);
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let (code, output) = doctor(&[]);
+    assert_eq!(code, Some(0), "{output}");
+    assert_eq!(output["ok"], true);
+
+    fs::write(
+        root.join("docs/agents/codex/commands/spec-plan.md"),
+        "BROKEN\n",
+    )
+    .expect("break generated template");
+    let (code, output) = doctor(&["--skip", "search,agent"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(output["agent"]["skipped"], true);
+    assert_eq!(output["search"]["skipped"], true);
+    assert_eq!(output["lint"]["skipped"], false);
+    let (code, output) = doctor(&["--skip", "search"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(output["agent"]["ok"], false);
+}
+
+#[test]
+fn link_graph_metrics_ranks_most_depended_upon_node_first() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["core", "api", "cli", "docs", "extra"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    // SPC-001 (core) is depended upon by three nodes; SPC-002 (api) by one.
+    for (from, to, edge_type) in [
+        ("SPC-002", "SPC-001", "depends_on"),
+        ("SPC-003", "SPC-001", "depends_on"),
+        ("SPC-004", "SPC-001", "refines"),
+        ("SPC-003", "SPC-002", "depends_on"),
+        ("SPC-005", "SPC-004", "impacts"),
+    ] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                to,
+                "--type",
+                edge_type,
+                "--rationale",
+                "graph fixture",
+            ],
+        );
+        assert!(link.status.success(), "link add {from} -> {to} failed");
+    }
+
+    let metrics = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "graph-metrics",
+            "--top",
+            "3",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(metrics.status.success(), "graph-metrics failed");
+    let output: serde_json::Value = serde_json::from_slice(&metrics.stdout).expect("parse json");
+    assert_eq!(output["node_count"], 5);
+    let nodes = output["nodes"].as_array().expect("nodes array");
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(nodes[0]["id"], "SPC-001");
+    assert_eq!(nodes[0]["in_degree"], 3);
+    assert_eq!(nodes[0]["out_degree"], 0);
+    assert_eq!(nodes[1]["id"], "SPC-002");
+    let ranks = nodes
+        .iter()
+        .map(|n| n["page_rank"].as_f64().expect("page_rank"))
+        .collect::<Vec<_>>();
+    assert!(ranks[0] > ranks[1] && ranks[1] >= ranks[2], "{ranks:?}");
+}
+
+#[test]
+fn search_db_flag_and_env_relocate_the_index() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/cache.md",
+            "--body",
+            "# Cache\n\nEviction policy.",
+        ],
+    );
+    assert!(write.status.success(), "write failed");
+
+    let index = run_foundry(
+        root,
+        &["spec", "search", "index", "--db", "ci/job-1/index.db"],
+    );
+    assert!(index.status.success(), "index failed");
+    assert!(root.join("ci/job-1/index.db").is_file());
+    assert!(!root.join(".foundry/search/index.db").exists());
+
+    let query = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "--db",
+            "ci/job-1/index.db",
+            "query",
+            "eviction",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(query.status.success(), "query failed");
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    assert_eq!(output["hits"][0]["id"], "SPC-001");
+
+    let bin = assert_cmd::cargo::cargo_bin!("foundry");
+    let ask = Command::new(bin)
+        .args([
+            "spec",
+            "ask",
+            "eviction policy",
+            "--mode",
+            "lexical",
+            "--format",
+            "json",
+        ])
+        .env("FOUNDRY_SEARCH_DB", "ci/job-1/index.db")
+        .current_dir(root)
+        .output()
+        .expect("run foundry");
+    assert!(ask.status.success(), "ask failed");
+    let output: serde_json::Value = serde_json::from_slice(&ask.stdout).expect("parse ask");
+    assert_eq!(output["citations"][0]["id"], "SPC-001");
+
+    let doctor = run_foundry(
+        root,
+        &["spec", "search", "doctor", "--db", "ci/job-1/index.db"],
+    );
+    assert!(
+        doctor.status.success(),
+        "doctor against relocated index failed"
+    );
+    assert!(!root.join(".foundry/search/index.db").exists());
+
+    fs::write(root.join("blocker"), "not a directory").expect("write blocker file");
+    let bad = run_foundry(
+        root,
+        &["spec", "search", "index", "--db", "blocker/index.db"],
+    );
+    assert_eq!(bad.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("cannot create search index directory"));
+}
+
+#[test]
+fn search_query_before_index_reports_empty_index() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/cache.md",
+            "--body",
+            "# Cache\n\nEviction policy.",
+        ],
+    );
+    assert!(write.status.success(), "write failed");
+
+    let table = run_foundry(root, &["spec", "search", "query", "eviction"]);
+    assert_eq!(table.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&table.stderr);
+    assert!(
+        stderr.contains("search index is empty; run `foundry spec search index`"),
+        "missing hint: {stderr}"
+    );
+
+    let json = run_foundry(
+        root,
+        &["spec", "search", "query", "eviction", "--format", "json"],
+    );
+    assert!(json.status.success(), "json query should not fail");
+    let output: serde_json::Value = serde_json::from_slice(&json.stdout).expect("parse query");
+    assert_eq!(output["index_empty"], true);
+    assert_eq!(output["hits"], serde_json::json!([]));
+    assert!(String::from_utf8_lossy(&json.stderr).contains("warning: search index is empty"));
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let json = run_foundry(
+        root,
+        &["spec", "search", "query", "eviction", "--format", "json"],
+    );
+    let output: serde_json::Value = serde_json::from_slice(&json.stdout).expect("parse query");
+    assert_eq!(output["index_empty"], false);
+    assert_eq!(output["hits"][0]["id"], "SPC-001");
+}
+
+#[test]
+fn search_query_falls_back_to_fuzzy_title_match() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (path, body) in [
+        (
+            "spec/authz.md",
+            "# Authorization\n\nRole checks for every request.",
+        ),
+        ("spec/cache.md", "# Cache\n\nEviction policy."),
+    ] {
+        let write = run_foundry(root, &["spec", "write", "--path", path, "--body", body]);
+        assert!(write.status.success(), "write failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let query = |text: &str| {
+        let out = run_foundry(root, &["spec", "search", "query", text, "--format", "json"]);
+        assert!(out.status.success(), "query failed");
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).expect("parse query")
+    };
+
+    let exact = query("authorization");
+    assert_eq!(exact["hits"][0]["id"], "SPC-001");
+    assert!(exact["hits"][0].get("fuzzy").is_none());
+
+    let typo = query("authorisation");
+    let hits = typo["hits"].as_array().expect("hits");
+    assert_eq!(hits.len(), 1, "only the near match: {typo}");
+    assert_eq!(hits[0]["id"], "SPC-001");
+    assert_eq!(hits[0]["fuzzy"], true);
+    let exact_score = exact["hits"][0]["score"].as_f64().expect("score");
+    let fuzzy_score = hits[0]["score"].as_f64().expect("score");
+    assert!(fuzzy_score > 0.0 && fuzzy_score < exact_score);
+
+    let table = run_foundry(root, &["spec", "search", "query", "authorisation"]);
+    assert!(String::from_utf8_lossy(&table.stdout).contains("(fuzzy)"));
+}
+
+#[test]
+fn write_append_and_front_matter_round_trip() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = |args: &[&str]| {
+        let mut full = vec!["spec", "write", "--path", "spec/auth.md"];
+        full.extend_from_slice(args);
+        let out = run_foundry(root, &full);
+        assert!(
+            out.status.success(),
+            "write failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+    };
+    let read_md = || fs::read_to_string(root.join("spec/auth.md")).expect("read markdown");
+    let read_meta = || {
+        let raw = fs::read_to_string(root.join("spec/auth.meta.json")).expect("read meta");
+        serde_json::from_str::<serde_json::Value>(&raw).expect("parse meta")
+    };
+
+    write(&["--body", "# Auth\n\nLogin flow.\n"]);
+    let first_hash = read_meta()["hash"].clone();
+
+    write(&["--append", "--body", "## Changelog\n\n- added MFA\n"]);
+    assert_eq!(
+        read_md(),
+        "# Auth\n\nLogin flow.\n\n## Changelog\n\n- added MFA\n"
+    );
+    let meta = read_meta();
+    assert_eq!(meta["title"], "Auth");
+    assert_ne!(meta["hash"], first_hash);
+
+    write(&[
+        "--front-matter",
+        "owner=team-auth",
+        "--front-matter",
+        "summary=login: password and MFA",
+    ]);
+    assert_eq!(
+        read_md(),
+        "---\nowner: team-auth\nsummary: \"login: password and MFA\"\n---\n# Auth\n\nLogin flow.\n\n## Changelog\n\n- added MFA\n"
+    );
+    assert_eq!(read_meta()["title"], "Auth");
+
+    write(&["--front-matter", "owner=team-identity"]);
+    write(&["--body", "# Authentication\n\nRewritten.\n"]);
+    assert_eq!(
+        read_md(),
+        "---\nowner: team-identity\nsummary: \"login: password and MFA\"\n---\n# Authentication\n\nRewritten.\n"
+    );
+    assert_eq!(read_meta()["title"], "Authentication");
+
+    let bad = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/auth.md", "--append"],
+    );
+    assert_eq!(bad.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("--append requires --body"));
+}
+
+#[test]
+fn search_index_reports_progress_unless_quiet() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for name in ["a", "b", "c"] {
+        let path = format!("spec/{name}.md");
+        let body = format!("# Node {name}\n\nBody.");
+        let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", &body]);
+        assert!(write.status.success(), "write failed");
+    }
+
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let stdout = String::from_utf8_lossy(&index.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(
+        stdout.starts_with("search index summary: indexed=3 skipped=0 deleted=0 db_bytes="),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&index.stderr);
+    assert!(
+        stderr
+            .lines()
+            .any(|l| l.starts_with("search index: progress 3/3 SPC-")),
+        "missing progress: {stderr}"
+    );
+
+    let quiet = run_foundry(root, &["spec", "search", "index", "--rebuild", "--quiet"]);
+    assert!(quiet.status.success(), "quiet index failed");
+    let stdout = String::from_utf8_lossy(&quiet.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(
+        stdout.starts_with("search index summary: indexed=3 skipped=0 deleted=0 db_bytes="),
+        "{stdout}"
+    );
+    assert!(!String::from_utf8_lossy(&quiet.stderr).contains("progress"));
+}
+
+#[test]
+fn search_index_checkpoints_wal_between_runs() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    // Without the automatic checkpoint only the explicit one keeps the WAL small.
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"search":{"wal_autocheckpoint":0}}"#,
+    )
+    .expect("write config");
+    let write_nodes = |round: usize| {
+        for name in ["a", "b", "c"] {
+            let words = (0..2000)
+                .map(|k| format!("w{name}{round}x{k}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let path = format!("spec/{name}.md");
+            let body = format!("# Node {name}\n\n{words}");
+            let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", &body]);
+            assert!(write.status.success(), "write {name} failed");
+        }
+    };
+    write_nodes(0);
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    // An idle reader keeps SQLite from deleting the WAL when foundry exits.
+    let db = root.join(".foundry/search/index.db");
+    let reader = rusqlite::Connection::open(&db).expect("open db");
+    let nodes: i64 = reader
+        .query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))
+        .expect("count nodes");
+    assert_eq!(nodes, 3);
+    let wal = root.join(".foundry/search/index.db-wal");
+    let wal_len = || fs::metadata(&wal).map(|m| m.len()).unwrap_or(0);
+    let mut sizes = Vec::new();
+    for round in 1..=4 {
+        write_nodes(round);
+        let index = run_foundry(root, &["spec", "search", "index"]);
+        assert!(index.status.success(), "index round {round} failed");
+        sizes.push(wal_len());
+    }
+    drop(reader);
+    assert!(sizes[0] > 0, "{sizes:?}");
+    assert!(
+        sizes.iter().all(|size| *size <= sizes[0] * 3 / 2),
+        "WAL kept growing: {sizes:?}"
+    );
+}
+
+#[test]
+fn link_verify_fix_adds_reciprocal_conflict_edge() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["a", "b", "c"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let add = |from: &str, to: &str, edge_type: &str| {
+        let out = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                to,
+                "--type",
+                edge_type,
+                "--rationale",
+                "both own session state",
+                "--confidence",
+                "0.7",
+            ],
+        );
+        assert!(out.status.success(), "link add failed");
+    };
+    add("SPC-001", "SPC-002", "conflicts_with");
+    add("SPC-003", "SPC-001", "depends_on");
+
+    let verify = run_foundry(root, &["spec", "link", "verify"]);
+    assert_eq!(verify.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&verify.stdout);
+    assert!(stdout.contains(
+        "link verify: one-sided conflicts_with: SPC-001 -> SPC-002 (missing SPC-002 -> SPC-001)"
+    ));
+    assert!(
+        stdout.contains(
+            "link verify: info: SPC-003 depends_on SPC-001 without SPC-001 impacts SPC-003"
+        )
+    );
+
+    let fix = run_foundry(root, &["spec", "link", "verify", "--fix"]);
+    assert!(fix.status.success(), "verify --fix failed");
+    assert!(
+        String::from_utf8_lossy(&fix.stdout).contains(
+            "link verify summary: one_sided_conflicts=1 fixed=1 asymmetric_dependencies=1"
+        )
+    );
+    let meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(spec_dir.join("b.meta.json")).expect("read meta"))
+            .expect("parse meta");
+    assert_eq!(meta["edges"][0]["to"], "SPC-001");
+    assert_eq!(meta["edges"][0]["type"], "conflicts_with");
+    assert_eq!(meta["edges"][0]["rationale"], "both own session state");
+    assert_eq!(meta["edges"][0]["confidence"], 0.7);
+
+    let again = run_foundry(root, &["spec", "link", "verify"]);
+    assert!(again.status.success(), "verify after fix should pass");
+
+    for (seed, other) in [("SPC-001", "SPC-002"), ("SPC-002", "SPC-001")] {
+        let impact = run_foundry(root, &["spec", "impact", seed, "--format", "json"]);
+        assert!(impact.status.success(), "impact failed");
+        let report: serde_json::Value =
+            serde_json::from_slice(&impact.stdout).expect("parse impact");
+        assert_eq!(report["conflict_risks"], serde_json::json!([other]));
+    }
+}
+
+#[test]
+fn search_index_vacuum_shrinks_db_after_deletes() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    let paragraph = "Retry budgets bound how often a client may resend a request. ".repeat(40);
+    for idx in 0..30 {
+        fs::write(
+            spec_dir.join(format!("n{idx}.md")),
+            format!("# Node {idx}\n\n{paragraph}\n\n{paragraph}"),
+        )
+        .expect("write body");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    let index = run_foundry(root, &["spec", "search", "index", "--quiet"]);
+    assert!(index.status.success(), "index failed");
+
+    for entry in fs::read_dir(&spec_dir).expect("read spec dir") {
+        let path = entry.expect("entry").path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if !name.starts_with("n0.") {
+            fs::remove_file(&path).expect("remove node file");
+        }
+    }
+    let db_bytes = |output: &std::process::Output| -> u64 {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .split("db_bytes=")
+            .nth(1)
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or_else(|| panic!("no db_bytes in {stdout}"))
+    };
+    let pruned = run_foundry(root, &["spec", "search", "index", "--quiet"]);
+    assert!(pruned.status.success(), "pruning index failed");
+    assert!(String::from_utf8_lossy(&pruned.stdout).contains("deleted=29"));
+    let before = db_bytes(&pruned);
+
+    let vacuumed = run_foundry(root, &["spec", "search", "index", "--quiet", "--vacuum"]);
+    assert!(vacuumed.status.success(), "vacuum failed");
+    let after = db_bytes(&vacuumed);
+    assert!(after < before, "expected {after} < {before}");
+
+    let query = run_foundry(
+        root,
+        &["spec", "search", "query", "retry", "--format", "json"],
+    );
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    assert_eq!(output["hits"].as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn search_tokenizer_keeps_identifiers_whole_after_rebuild() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, body) in [
+        (
+            "ident",
+            "# Identity\n\nEvery order stores the user_id of its buyer.",
+        ),
+        ("words", "# Words\n\nThe user picks an id from the list."),
+    ] {
+        let path = format!("spec/{name}.md");
+        let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", body]);
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    let hit_ids = || -> Vec<String> {
+        let query = run_foundry(
+            root,
+            &["spec", "search", "query", "user_id", "--format", "json"],
+        );
+        assert!(query.status.success(), "query failed");
+        let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+        let mut ids = output["hits"]
+            .as_array()
+            .expect("hits")
+            .iter()
+            .map(|h| h["id"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+    assert_eq!(
+        hit_ids(),
+        vec!["SPC-001", "SPC-002"],
+        "unicode61 splits on _"
+    );
+
+    fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+    fs::write(
+        root.join(".foundry/config.json"),
+        r#"{"search":{"tokenizer":"unicode61 tokenchars '_'"}}"#,
+    )
+    .expect("write config");
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert_eq!(doctor.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&doctor.stdout).contains("fts tokenizer mismatch"));
+
+    let rebuild = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(rebuild.status.success(), "rebuild failed");
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert!(
+        doctor.status.success(),
+        "{}",
+        String::from_utf8_lossy(&doctor.stdout)
+    );
+    assert_eq!(hit_ids(), vec!["SPC-001"]);
+}
+
+#[test]
+fn search_embedding_dim_is_configurable_and_checked_by_doctor() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let set_dim = |dim: usize| {
+        fs::create_dir_all(root.join(".foundry")).expect("create config dir");
+        fs::write(
+            root.join(".foundry/config.json"),
+            serde_json::json!({ "search": { "embedding_dim": dim } }).to_string(),
+        )
+        .expect("write config");
+    };
+    let vector_dims = || -> Vec<i64> {
+        let conn =
+            rusqlite::Connection::open(root.join(".foundry/search/index.db")).expect("open db");
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT dim FROM chunk_vectors")
+            .expect("prepare");
+        stmt.query_map([], |row| row.get(0))
+            .expect("query dims")
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .expect("read dims")
+    };
+    for (name, body) in [
+        (
+            "auth",
+            "# Auth\n\nLogin sessions expire after idle timeout.",
+        ),
+        ("billing", "# Billing\n\nInvoices are issued monthly."),
+    ] {
+        let path = format!("spec/{name}.md");
+        let write = run_foundry(root, &["spec", "write", "--path", &path, "--body", body]);
+        assert!(write.status.success(), "write {name} failed");
+    }
+
+    set_dim(64);
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    assert_eq!(vector_dims(), vec![64]);
+    let query = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "login session",
+            "--mode",
+            "hybrid",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(query.status.success(), "hybrid query failed");
+    let output: serde_json::Value = serde_json::from_slice(&query.stdout).expect("parse query");
+    assert_eq!(output["hits"][0]["id"], "SPC-001");
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert!(doctor.status.success(), "doctor failed");
+
+    set_dim(128);
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert_eq!(doctor.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&doctor.stdout).contains("embedding dim mismatch"));
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(String::from_utf8_lossy(&index.stderr).contains("embedding dim 64"));
+    assert_eq!(vector_dims(), vec![64]);
+
+    let rebuild = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert!(rebuild.status.success(), "rebuild failed");
+    assert_eq!(vector_dims(), vec![128]);
+    let doctor = run_foundry(root, &["spec", "search", "doctor"]);
+    assert!(doctor.status.success(), "doctor after rebuild failed");
+
+    set_dim(0);
+    let index = run_foundry(root, &["spec", "search", "index", "--rebuild"]);
+    assert_eq!(index.status.code(), Some(2));
+}
+
+#[test]
+fn search_query_explain_breaks_down_hit_scores() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/checkout.md",
+            "--body",
+            "# Checkout Flow\n\nThe checkout flow collects payment.",
+        ],
+    );
+    assert!(write.status.success(), "write failed");
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+
+    let plain = run_foundry(
+        root,
+        &["spec", "search", "query", "checkout", "--format", "json"],
+    );
+    let output: serde_json::Value = serde_json::from_slice(&plain.stdout).expect("parse query");
+    assert!(output["hits"][0].get("explanation").is_none());
+
+    let explained = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "checkout",
+            "--format",
+            "json",
+            "--explain",
+        ],
+    );
+    assert!(explained.status.success(), "explain query failed");
+    let output: serde_json::Value = serde_json::from_slice(&explained.stdout).expect("parse query");
+    let explanation = &output["hits"][0]["explanation"];
+    assert_eq!(explanation["boost"]["title_overlap"], 3.0);
+    let base = explanation["bm25_base"].as_f64().expect("bm25 base");
+    let boost = &explanation["boost"];
+    let total = base
+        + boost["title_overlap"].as_f64().unwrap()
+        + boost["term_overlap"].as_f64().unwrap()
+        + boost["exact_phrase"].as_f64().unwrap();
+    assert!((total - output["hits"][0]["score"].as_f64().unwrap()).abs() < 1e-9);
+    assert!(explanation.get("hybrid").is_none());
+
+    let hybrid = run_foundry(
+        root,
+        &[
+            "spec",
+            "search",
+            "query",
+            "checkout",
+            "--mode",
+            "hybrid",
+            "--explain",
+        ],
+    );
+    assert!(hybrid.status.success(), "hybrid explain failed");
+    let stdout = String::from_utf8_lossy(&hybrid.stdout);
+    assert!(stdout.contains("explain: bm25_base="), "{stdout}");
+    assert!(stdout.contains("title_overlap=3.0000"), "{stdout}");
+    assert!(
+        stdout.contains("explain: lexical_rank=1 lexical_rrf=0.0164"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn lint_resolves_bodies_from_project_root_outside_cwd() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = run_foundry(
+        root,
+        &[
+            "spec",
+            "write",
+            "--path",
+            "spec/auth/login.md",
+            "--body",
+            "# Login\n\nPassword flow.",
+        ],
+    );
+    assert!(write.status.success(), "write failed");
+    let nested = root.join("spec/auth");
+    let elsewhere = tempdir().expect("create second temp dir");
+
+    let from_root = run_foundry(root, &["spec", "lint"]);
+    let from_subdir = run_foundry(&nested, &["spec", "lint"]);
+    assert_eq!(from_subdir.status.code(), from_root.status.code());
+    assert_eq!(from_subdir.stdout, from_root.stdout);
+    let root_arg = root.to_string_lossy().to_string();
+    let with_root = run_foundry(elsewhere.path(), &["spec", "lint", "--root", &root_arg]);
+    assert_eq!(with_root.stdout, from_root.stdout);
+    assert!(!String::from_utf8_lossy(&from_root.stdout).contains("hash mismatch"));
+
+    // The body really is read from the root: an edit shows up as a hash mismatch.
+    fs::write(nested.join("login.md"), "# Login\n\nChanged.").expect("edit body");
+    let stale = run_foundry(&nested, &["spec", "lint"]);
+    assert_eq!(stale.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&stale.stdout).contains("hash mismatch for spec/auth/login.md")
+    );
+
+    let missing = run_foundry(elsewhere.path(), &["spec", "lint", "--root", "no/such/dir"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("--root is not a directory"));
+}
+
+#[test]
+fn file_arguments_resolve_from_invocation_dir_and_discovery_stops_at_repo_marker() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let write = run_foundry(
+        root,
+        &["spec", "write", "--path", "spec/auth/login.md", "--body", "# Login"],
+    );
+    assert!(write.status.success(), "write failed");
+
+    let nested = root.join("spec/auth");
+    fs::write(nested.join("notes.md"), "# Login\n\nFrom notes.").expect("write notes");
+    let from_subdir = run_foundry(
+        &nested,
+        &["spec", "write", "--id", "SPC-001", "--body-file", "notes.md"],
+    );
+    assert!(
+        from_subdir.status.success(),
+        "{}",
+        String::from_utf8_lossy(&from_subdir.stderr)
+    );
+    let body = fs::read_to_string(nested.join("login.md")).expect("read body");
+    assert!(body.contains("From notes."), "{body}");
+
+    // Agent directories and a relative FOUNDRY_SEARCH_DB follow the same rule.
+    let root_arg = root.to_str().expect("utf8 path");
+    let init = run_foundry(
+        &nested,
+        &[
+            "spec",
+            "init",
+            "--root",
+            root_arg,
+            "--template-source",
+            "local",
+            "--agent-dir",
+            "cursor:tools/cursor",
+        ],
+    );
+    assert!(init.status.success(), "{}", String::from_utf8_lossy(&init.stderr));
+    assert!(nested.join("tools/cursor/commands/implement.md").is_file());
+    assert!(!root.join("tools/cursor").exists());
+
+    let bin = assert_cmd::cargo::cargo_bin!("foundry");
+    let index = Command::new(bin)
+        .args(["spec", "search", "index"])
+        .env("FOUNDRY_SEARCH_DB", "local.db")
+        .current_dir(&nested)
+        .output()
+        .expect("run foundry");
+    assert!(index.status.success(), "{}", String::from_utf8_lossy(&index.stderr));
+    assert!(nested.join("local.db").is_file());
+    assert!(!root.join("local.db").exists());
+
+    // A nested repository without spec/ must not latch onto the outer tree.
+    let inner = root.join("vendor/other");
+    fs::create_dir_all(inner.join(".git")).expect("create inner repo");
+    let lint = run_foundry(&inner, &["spec", "lint", "--format", "json"]);
+    let stdout = String::from_utf8_lossy(&lint.stdout);
+    assert!(!stdout.contains("spec/auth/login.md"), "{stdout}");
+}
+
+#[test]
+fn history_lists_commits_touching_node_files() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let git = |args: &[&str]| {
+        let out = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()
+            .expect("run git");
+        assert!(out.status.success(), "git {args:?} failed");
+    };
+    let write = |args: &[&str]| {
+        let mut full = vec!["spec", "write", "--path", "spec/auth.md"];
+        full.extend_from_slice(args);
+        assert!(run_foundry(root, &full).status.success(), "write failed");
+    };
+
+    // Outside a repository the command still succeeds, without commits.
+    write(&["--body", "# Auth\n\nLogin."]);
+    let no_repo = run_foundry(root, &["spec", "history", "SPC-001", "--format", "json"]);
+    assert!(no_repo.status.success(), "history outside git failed");
+    let output: serde_json::Value = serde_json::from_slice(&no_repo.stdout).expect("parse");
+    assert_eq!(output["git"], false);
+    assert_eq!(output["commits"], serde_json::json!([]));
+    assert!(String::from_utf8_lossy(&no_repo.stderr).contains("not inside a git repository"));
+
+    git(&["init", "-q"]);
+    git(&["add", "spec"]);
+    git(&["commit", "-q", "-m", "Add auth spec"]);
+    write(&["--status", "active"]);
+    git(&["commit", "-q", "-am", "Activate auth"]);
+
+    let history = run_foundry(
+        root,
+        &["spec", "history", "SPC-001", "--format", "json", "--diff"],
+    );
+    assert!(history.status.success(), "history failed");
+    let output: serde_json::Value = serde_json::from_slice(&history.stdout).expect("parse");
+    assert_eq!(output["git"], true);
+    assert_eq!(output["meta_path"], "spec/auth.meta.json");
+    let commits = output["commits"].as_array().expect("commits");
+    let subjects = commits
+        .iter()
+        .map(|c| c["subject"].as_str().expect("subject"))
+        .collect::<Vec<_>>();
+    assert_eq!(subjects, ["Activate auth", "Add auth spec"]);
+    assert_eq!(commits[0]["files"], serde_json::json!(["meta"]));
+    assert_eq!(commits[1]["files"], serde_json::json!(["meta", "markdown"]));
+    assert_eq!(output["diff"]["commit"], commits[0]["commit"]);
+    assert!(
+        output["diff"]["patch"]
+            .as_str()
+            .expect("patch")
+            .contains("+  \"status\": \"active\"")
+    );
+
+    let table = run_foundry(root, &["spec", "history", "SPC-001"]);
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    assert!(stdout.contains("[meta] Activate auth"), "{stdout}");
+    assert!(stdout.contains("[meta,markdown] Add auth spec"), "{stdout}");
+}
+
+#[test]
+fn coverage_reports_requirements_without_tests() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (name, node_type) in [
+        ("login", "feature_requirement"),
+        ("logout", "feature_requirement"),
+        ("login-tests", "test_spec"),
+    ] {
+        let write = run_foundry(
+            root,
+            &[
+                "spec",
+                "write",
+                "--path",
+                &format!("spec/{name}.md"),
+                "--body",
+                &format!("# {name}"),
+                "--type",
+                node_type,
+            ],
+        );
+        assert!(write.status.success(), "write {name} failed");
+    }
+    let link = run_foundry(
+        root,
+        &[
+            "spec",
+            "link",
+            "add",
+            "--from",
+            "SPC-003",
+            "--to",
+            "SPC-001",
+            "--type",
+            "tests",
+            "--rationale",
+            "login scenarios",
+        ],
+    );
+    assert!(link.status.success(), "link add failed");
+
+    let coverage = run_foundry(root, &["spec", "coverage", "--format", "json"]);
+    assert!(coverage.status.success(), "coverage failed");
+    let output: serde_json::Value = serde_json::from_slice(&coverage.stdout).expect("parse json");
+    assert_eq!(output["total"], 2);
+    assert_eq!(output["covered"], 1);
+    assert_eq!(output["coverage_percent"], 50.0);
+    let uncovered = output["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .filter(|n| n["covered"] == false)
+        .map(|n| n["id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(uncovered, vec!["SPC-002"]);
+    assert_eq!(
+        output["nodes"][0]["tested_by"],
+        serde_json::json!(["SPC-003"])
+    );
+
+    let table = run_foundry(root, &["spec", "coverage"]);
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    assert!(
+        stdout.contains("coverage: SPC-002 [feature_requirement] uncovered"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("coverage summary: covered=1 total=2 percent=50.0"),
+        "{stdout}"
+    );
+
+    let filtered = run_foundry(
+        root,
+        &[
+            "spec",
+            "coverage",
+            "--type",
+            "api_contract",
+            "--format",
+            "json",
+        ],
+    );
+    let output: serde_json::Value = serde_json::from_slice(&filtered.stdout).expect("parse json");
+    assert_eq!(output["total"], 0);
+}
+
+#[test]
+fn ask_questions_file_answers_each_line_in_order() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    for (path, body) in [
+        ("spec/cache.md", "# Cache\n\nEviction policy for the cache."),
+        ("spec/auth.md", "# Auth\n\nLogin with passwords."),
+        ("spec/billing.md", "# Billing\n\nInvoices are sent monthly."),
+    ] {
+        let write = run_foundry(root, &["spec", "write", "--path", path, "--body", body]);
+        assert!(write.status.success(), "write failed");
+    }
+    let index = run_foundry(root, &["spec", "search", "index"]);
+    assert!(index.status.success(), "index failed");
+    fs::write(
+        root.join("questions.txt"),
+        "invoices monthly\n\neviction policy\nlogin passwords\n",
+    )
+    .expect("write questions");
+
+    let ask = run_foundry(
+        root,
+        &[
+            "spec",
+            "ask",
+            "--questions-file",
+            "questions.txt",
+            "--mode",
+            "lexical",
+            "--top-k",
+            "1",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(
+        ask.status.success(),
+        "batch ask failed: {}",
+        String::from_utf8_lossy(&ask.stderr)
+    );
+    let outputs: serde_json::Value = serde_json::from_slice(&ask.stdout).expect("parse ask");
+    let outputs = outputs.as_array().expect("array of answers");
+    assert_eq!(outputs.len(), 3);
+    let pairs = outputs
+        .iter()
+        .map(|o| {
+            (
+                o["question"].as_str().expect("question"),
+                o["citations"][0]["id"].as_str().expect("citation"),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pairs,
+        [
+            ("invoices monthly", "SPC-003"),
+            ("eviction policy", "SPC-001"),
+            ("login passwords", "SPC-002"),
+        ]
+    );
+    assert!(outputs.iter().all(|o| o["mode"] == "lexical"));
+
+    let markdown = run_foundry(
+        root,
+        &[
+            "spec",
+            "ask",
+            "--questions-file",
+            "questions.txt",
+            "--mode",
+            "lexical",
+            "--format",
+            "markdown",
+        ],
+    );
+    assert!(markdown.status.success(), "markdown batch failed");
+    assert_eq!(
+        String::from_utf8_lossy(&markdown.stdout)
+            .matches("## Question")
+            .count(),
+        3
+    );
+
+    let both = run_foundry(
+        root,
+        &["spec", "ask", "cache", "--questions-file", "questions.txt"],
+    );
+    assert_eq!(both.status.code(), Some(2));
+}
+
+#[test]
+fn impact_conflicts_first_reviews_conflicting_nodes_before_dependents() {
+    let root = tempdir().expect("create temp dir");
+    let root = root.path();
+    let spec_dir = root.join("spec");
+    fs::create_dir_all(&spec_dir).expect("create spec dir");
+    for name in ["a", "b", "c"] {
+        fs::write(spec_dir.join(format!("{name}.md")), format!("# {name}")).expect("write md");
+    }
+    let init = run_foundry(root, &["spec", "init", "--sync"]);
+    assert!(init.status.success(), "init failed");
+    for (from, edge_type) in [("SPC-002", "depends_on"), ("SPC-003", "conflicts_with")] {
+        let link = run_foundry(
+            root,
+            &[
+                "spec",
+                "link",
+                "add",
+                "--from",
+                from,
+                "--to",
+                "SPC-001",
+                "--type",
+                edge_type,
+                "--rationale",
+                "touches a",
+            ],
+        );
+        assert!(link.status.success(), "link add from {from} failed");
+    }
+
+    let order = |extra: &[&str]| -> serde_json::Value {
+        let mut args = vec!["spec", "impact", "SPC-001", "--format", "json"];
+        args.extend_from_slice(extra);
+        let out = run_foundry(root, &args);
+        assert!(out.status.success(), "impact failed");
+        let output: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+        output["recommended_review_order"].clone()
+    };
+    assert_eq!(order(&[]), serde_json::json!(["SPC-001", "SPC-002"]));
+    assert_eq!(
+        order(&["--conflicts-first"]),
+        serde_json::json!(["SPC-001", "SPC-003", "SPC-002"])
+    );
+}
+
+#[test]
+fn completions_bash_lists_spec_subcommands() {
+    let root = tempdir().expect("create temp dir");
+    let out = run_foundry(root.path(), &["spec", "completions", "bash"]);
+    assert!(out.status.success(), "completions bash failed");
+    let script = String::from_utf8_lossy(&out.stdout);
+    assert!(script.contains("_foundry()"), "missing completion function: {script}");
+    assert!(script.contains("foundry,spec)"), "missing spec subcommand");
+    assert!(script.contains("foundry__subcmd__spec,impact)"), "missing impact subcommand");
+
+    let invalid = run_foundry(root.path(), &["spec", "completions", "tcsh"]);
+    assert!(!invalid.status.success(), "unknown shells are rejected");
 }